@@ -1,13 +1,22 @@
-use crate::helpers::{is_writable, LOG_TARGET};
+use crate::helpers::{is_readable, is_writable, to_millis, LOG_TARGET};
 use crate::items::{CacheItem, ProjectsState, RunTargetState, WorkspaceState};
+use crate::prune::{get_size, HashEntry, PruneStats, DAY_MILLIS};
+use crate::remote::{
+    pack_dir, unpack_dir, RemoteCache, REMOTE_MANIFEST_FILE, REMOTE_OUTPUTS_FILE,
+    REMOTE_RUN_STATE_FILE,
+};
 use crate::runfiles::CacheRunfile;
 use moon_config::constants::CONFIG_DIRNAME;
-use moon_error::MoonError;
+use moon_config::PruneCacheConfig;
+use moon_error::{map_io_to_fs_error, map_json_to_error, MoonError};
 use moon_logger::{color, debug, trace};
 use moon_utils::fs;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 pub struct CacheEngine {
     /// The `.moon/cache` directory relative to workspace root.
@@ -22,6 +31,9 @@ pub struct CacheEngine {
 
     /// The `.moon/cache/out` directory. Stores task output.
     pub outputs_dir: PathBuf,
+
+    /// Remote cache that is shared between machines, when configured.
+    pub remote: Option<RemoteCache>,
 }
 
 impl CacheEngine {
@@ -46,6 +58,7 @@ impl CacheEngine {
             hashes_dir,
             runs_dir,
             outputs_dir,
+            remote: None,
         })
     }
 
@@ -86,6 +99,60 @@ impl CacheEngine {
         .await
     }
 
+    /// Delete all hashes, outputs, run states and logs, and task caches.
+    pub async fn clean_all(&self) -> Result<PruneStats, MoonError> {
+        let mut stats = PruneStats::default();
+
+        if !is_writable() {
+            return Ok(stats);
+        }
+
+        stats.hashes = fs::read_dir(&self.hashes_dir).await?.len();
+        stats.logs = self.get_all_run_logs().await?.len();
+
+        for dir in [
+            &self.hashes_dir,
+            &self.outputs_dir,
+            &self.runs_dir,
+            &self.dir.join("tasks"),
+        ] {
+            stats.bytes += get_size(dir).await?;
+
+            trace!(target: LOG_TARGET, "Deleting {}", color::path(dir));
+
+            fs::remove_dir_all(dir).await?;
+        }
+
+        fs::create_dir_all(&self.hashes_dir).await?;
+        fs::create_dir_all(&self.runs_dir).await?;
+        fs::create_dir_all(&self.outputs_dir).await?;
+
+        Ok(stats)
+    }
+
+    /// Write the log of an action's run to `.moon/cache/runs/<name>/<timestamp>.log`,
+    /// so that the output of previous (and possibly flaky) runs is not lost.
+    pub async fn create_run_log(
+        &self,
+        name: &str,
+        contents: &str,
+    ) -> Result<Option<PathBuf>, MoonError> {
+        if !is_writable() {
+            return Ok(None);
+        }
+
+        let path = self
+            .get_run_logs_dir(name)
+            .join(format!("{}.log", to_millis(SystemTime::now())));
+
+        trace!(target: "moon:cache:run-log", "Writing run log {}", color::path(&path));
+
+        fs::create_dir_all(path.parent().unwrap()).await?;
+        fs::write(&path, contents).await?;
+
+        Ok(Some(path))
+    }
+
     pub async fn create_runfile<T: DeserializeOwned + Serialize>(
         &self,
         project_id: &str,
@@ -130,6 +197,193 @@ impl CacheEngine {
         Ok(())
     }
 
+    /// Download the run state, hash manifest, and outputs archive for the
+    /// provided hash from the remote cache, and persist them locally.
+    /// Returns `None` if no remote is configured, or the hash does not exist.
+    pub async fn download_from_remote(
+        &self,
+        hash: &str,
+    ) -> Result<Option<RunTargetState>, MoonError> {
+        let remote = match &self.remote {
+            Some(remote) if is_writable() => remote,
+            _ => return Ok(None),
+        };
+
+        // The run state is uploaded last, so if it exists, the others should too
+        let state: RunTargetState = match remote.download(hash, REMOTE_RUN_STATE_FILE).await? {
+            Some(data) => serde_json::from_slice(&data).map_err(|e| {
+                map_json_to_error(
+                    e,
+                    PathBuf::from(remote.get_url(hash, REMOTE_RUN_STATE_FILE)),
+                )
+            })?,
+            None => return Ok(None),
+        };
+
+        debug!(
+            target: LOG_TARGET,
+            "Remote cache hit for hash {}",
+            color::symbol(hash)
+        );
+
+        if let Some(data) = remote.download(hash, REMOTE_MANIFEST_FILE).await? {
+            fs::write(&self.hashes_dir.join(format!("{}.json", hash)), data).await?;
+        }
+
+        if let Some(data) = remote.download(hash, REMOTE_OUTPUTS_FILE).await? {
+            let dest_dir = self.outputs_dir.join(hash);
+
+            fs::create_dir_all(&dest_dir).await?;
+
+            unpack_dir(&data, &dest_dir)?;
+        }
+
+        Ok(Some(state))
+    }
+
+    /// Return all run log files, for every target and action.
+    async fn get_all_run_logs(&self) -> Result<Vec<PathBuf>, MoonError> {
+        Ok(fs::read_dir_all(&self.runs_dir)
+            .await?
+            .into_iter()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().unwrap_or_default() == "log")
+            .collect())
+    }
+
+    /// Return all run state files, for every target.
+    async fn get_all_run_states(&self) -> Result<Vec<PathBuf>, MoonError> {
+        Ok(fs::read_dir_all(&self.runs_dir)
+            .await?
+            .into_iter()
+            .map(|entry| entry.path())
+            .filter(|path| path.file_name().unwrap_or_default() == "lastRunState.json")
+            .collect())
+    }
+
+    /// Return all hash manifests and their outputs, sorted from newest to oldest.
+    async fn get_hash_entries(&self) -> Result<Vec<HashEntry>, MoonError> {
+        let mut entries = vec![];
+
+        for entry in fs::read_dir(&self.hashes_dir).await? {
+            let manifest_path = entry.path();
+
+            if manifest_path.extension().unwrap_or_default() != "json" {
+                continue;
+            }
+
+            let hash = manifest_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .to_string();
+            let outputs_path = self.outputs_dir.join(&hash);
+
+            // Manifests that fail to parse are grouped together
+            let target = fs::read_json::<Value>(&manifest_path)
+                .await
+                .ok()
+                .and_then(|manifest| {
+                    manifest
+                        .get("target")
+                        .and_then(|t| t.as_str())
+                        .map(|t| t.to_owned())
+                })
+                .unwrap_or_default();
+
+            entries.push(HashEntry {
+                modified: to_millis(fs::metadata(&manifest_path).await?.modified().unwrap()),
+                size: get_size(&manifest_path).await? + get_size(&outputs_path).await?,
+                hash,
+                manifest_path,
+                outputs_path,
+                target,
+            });
+        }
+
+        entries.sort_by(|a, b| b.modified.cmp(&a.modified));
+
+        Ok(entries)
+    }
+
+    pub fn get_run_logs_dir(&self, name: &str) -> PathBuf {
+        self.runs_dir.join(name.replace(':', "/"))
+    }
+
+    /// Return all run log files for the provided name, sorted from oldest to newest.
+    pub async fn get_run_logs(&self, name: &str) -> Result<Vec<PathBuf>, MoonError> {
+        let dir = self.get_run_logs_dir(name);
+
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut logs = fs::read_dir(&dir)
+            .await?
+            .into_iter()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().unwrap_or_default() == "log")
+            .collect::<Vec<_>>();
+
+        // Timestamps are the same length, so sorting by name is chronological
+        logs.sort();
+
+        Ok(logs)
+    }
+
+    pub fn has_task_output_in_out(
+        &self,
+        hash: &str,
+        source_root: &Path,
+        source_path: &Path,
+    ) -> bool {
+        if !is_readable() {
+            return false;
+        }
+
+        self.outputs_dir
+            .join(hash)
+            .join(source_path.strip_prefix(source_root).unwrap())
+            .exists()
+    }
+
+    pub async fn hydrate_task_output_from_out(
+        &self,
+        hash: &str,
+        dest_root: &Path,
+        dest_path: &Path,
+    ) -> Result<bool, MoonError> {
+        if !self.has_task_output_in_out(hash, dest_root, dest_path) {
+            return Ok(false);
+        }
+
+        let source_root = self.outputs_dir.join(hash);
+        let source_path = source_root.join(dest_path.strip_prefix(dest_root).unwrap());
+
+        trace!(
+            target: LOG_TARGET,
+            "Hydrating output {} from {}",
+            color::path(dest_path),
+            color::path(&source_root)
+        );
+
+        // Remove the current output first, as it may be stale,
+        // and hard links are not created for existing files
+        if dest_path.is_file() {
+            fs::remove_file(dest_path).await?;
+        } else {
+            fs::remove_dir_all(dest_path).await?;
+        }
+
+        if source_path.is_file() {
+            fs::link_file(&source_root, &source_path, dest_root).await?;
+        } else {
+            fs::link_dir(&source_root, &source_path, dest_root).await?;
+        }
+
+        Ok(true)
+    }
+
     pub async fn link_task_output_to_out(
         &self,
         hash: &str,
@@ -158,6 +412,235 @@ impl CacheEngine {
         Ok(())
     }
 
+    /// Upload the hash manifest, outputs archive, and run state for the
+    /// provided hash to the remote cache, if configured and writable.
+    pub async fn upload_to_remote(
+        &self,
+        hash: &str,
+        state: &RunTargetState,
+    ) -> Result<(), MoonError> {
+        let remote = match &self.remote {
+            Some(remote) if is_writable() && !remote.read_only => remote,
+            _ => return Ok(()),
+        };
+
+        debug!(
+            target: LOG_TARGET,
+            "Uploading hash {} to remote cache",
+            color::symbol(hash)
+        );
+
+        let manifest_path = self.hashes_dir.join(format!("{}.json", hash));
+
+        if manifest_path.exists() {
+            let data = std::fs::read(&manifest_path)
+                .map_err(|e| map_io_to_fs_error(e, manifest_path.clone()))?;
+
+            remote.upload(hash, REMOTE_MANIFEST_FILE, data).await?;
+        }
+
+        let outputs_dir = self.outputs_dir.join(hash);
+
+        if outputs_dir.exists() {
+            remote
+                .upload(hash, REMOTE_OUTPUTS_FILE, pack_dir(&outputs_dir)?)
+                .await?;
+        }
+
+        let data = serde_json::to_vec(state).map_err(|e| {
+            map_json_to_error(
+                e,
+                PathBuf::from(remote.get_url(hash, REMOTE_RUN_STATE_FILE)),
+            )
+        })?;
+
+        remote.upload(hash, REMOTE_RUN_STATE_FILE, data).await?;
+
+        Ok(())
+    }
+
+    /// Delete hashes, outputs, and run logs that are stale according to the
+    /// provided policy, as well as outputs and run states that no longer have a hash.
+    pub async fn prune(&self, policy: &PruneCacheConfig) -> Result<PruneStats, MoonError> {
+        let mut stats = PruneStats::default();
+
+        if !is_writable() {
+            return Ok(stats);
+        }
+
+        let now = to_millis(SystemTime::now());
+        let is_too_old = |modified: u128| match policy.max_age {
+            Some(days) => now.saturating_sub(modified) > u128::from(days) * DAY_MILLIS,
+            None => false,
+        };
+
+        // Hashes and their outputs, newest first
+        let entries = self.get_hash_entries().await?;
+        let mut stale_hashes = HashSet::new();
+        let mut target_counts: HashMap<&str, usize> = HashMap::new();
+        let mut total_size = 0;
+
+        for entry in &entries {
+            let count = target_counts.entry(&entry.target).or_default();
+            *count += 1;
+
+            let is_over_count = match policy.keep_per_target {
+                Some(keep) => *count > keep,
+                None => false,
+            };
+
+            if is_over_count || is_too_old(entry.modified) {
+                stale_hashes.insert(&entry.hash);
+                continue;
+            }
+
+            // Once the size limit is reached, all older hashes are stale
+            total_size += entry.size;
+
+            if let Some(max_size) = policy.max_size {
+                if total_size > max_size * 1024 * 1024 {
+                    stale_hashes.insert(&entry.hash);
+                }
+            }
+        }
+
+        for entry in &entries {
+            if stale_hashes.contains(&entry.hash) {
+                trace!(
+                    target: "moon:cache:hash",
+                    "Pruning hash {}",
+                    color::path(&entry.manifest_path)
+                );
+
+                fs::remove_file(&entry.manifest_path).await?;
+                fs::remove_dir_all(&entry.outputs_path).await?;
+
+                stats.bytes += entry.size;
+                stats.hashes += 1;
+            }
+        }
+
+        // Outputs that no longer have a hash are unusable
+        for entry in fs::read_dir(&self.outputs_dir).await? {
+            let path = entry.path();
+            let hash = entry.file_name().to_string_lossy().to_string();
+
+            if path.is_dir() && !self.hashes_dir.join(format!("{}.json", hash)).exists() {
+                trace!(
+                    target: "moon:cache:hash",
+                    "Pruning orphaned outputs {}",
+                    color::path(&path)
+                );
+
+                stats.bytes += get_size(&path).await?;
+                stats.hashes += 1;
+
+                fs::remove_dir_all(&path).await?;
+            }
+        }
+
+        // Run states of targets that no longer have a hash are stale, as the target
+        // was either pruned above, or has since been removed or renamed
+        let live_targets = entries
+            .iter()
+            .filter(|entry| !stale_hashes.contains(&entry.hash))
+            .map(|entry| entry.target.as_str())
+            .collect::<HashSet<_>>();
+
+        for state_path in self.get_all_run_states().await? {
+            let state_dir = state_path.parent().unwrap();
+            let target = state_dir
+                .strip_prefix(&self.runs_dir)
+                .unwrap()
+                .to_string_lossy()
+                .replace(std::path::MAIN_SEPARATOR, ":");
+
+            if live_targets.contains(target.as_str()) {
+                continue;
+            }
+
+            trace!(
+                target: LOG_TARGET,
+                "Pruning run state {}",
+                color::path(&state_path)
+            );
+
+            stats.bytes += get_size(&state_path).await?;
+
+            fs::remove_file(&state_path).await?;
+
+            // Run logs are pruned separately below, according to the policy
+            if fs::read_dir(state_dir).await?.is_empty() {
+                fs::remove_dir_all(state_dir).await?;
+            }
+        }
+
+        // Runfiles of projects that no longer have any run state or logs
+        for entry in fs::read_dir(&self.runs_dir).await? {
+            let path = entry.path();
+
+            if !path.is_dir() {
+                continue;
+            }
+
+            let is_stale = fs::read_dir(&path)
+                .await?
+                .iter()
+                .all(|child| child.file_name() == "runfile.json");
+
+            if is_stale {
+                trace!(target: LOG_TARGET, "Pruning runfile {}", color::path(&path));
+
+                stats.bytes += get_size(&path).await?;
+
+                fs::remove_dir_all(&path).await?;
+            }
+        }
+
+        // Run logs, grouped by target or action
+        let mut logs_by_dir: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+
+        for log in self.get_all_run_logs().await? {
+            logs_by_dir
+                .entry(log.parent().unwrap().to_path_buf())
+                .or_default()
+                .push(log);
+        }
+
+        for mut logs in logs_by_dir.into_values() {
+            // Timestamps are the same length, so sorting by name is chronological
+            logs.sort();
+            logs.reverse();
+
+            for (index, log) in logs.iter().enumerate() {
+                let is_over_count = match policy.keep_per_target {
+                    Some(keep) => index >= keep,
+                    None => false,
+                };
+                let modified = to_millis(fs::metadata(log).await?.modified().unwrap());
+
+                if is_over_count || is_too_old(modified) {
+                    trace!(target: LOG_TARGET, "Pruning run log {}", color::path(log));
+
+                    stats.bytes += get_size(log).await?;
+                    stats.logs += 1;
+
+                    fs::remove_file(log).await?;
+                }
+            }
+        }
+
+        debug!(
+            target: LOG_TARGET,
+            "Pruned {} hashes and {} logs, reclaiming {} bytes",
+            stats.hashes,
+            stats.logs,
+            stats.bytes
+        );
+
+        Ok(stats)
+    }
+
     pub async fn save_hash<T>(&self, hash: &str, hasher: &T) -> Result<(), MoonError>
     where
         T: ?Sized + Serialize,
@@ -199,125 +682,483 @@ mod tests {
 
             dir.close().unwrap();
         }
-    }
-
-    mod delete_runfiles {
-        use super::*;
+    }
+
+    mod create_run_log {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn writes_log_for_target() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            let path = cache
+                .create_run_log("foo:bar", "output")
+                .await
+                .unwrap()
+                .unwrap();
+
+            assert!(path.starts_with(dir.path().join(".moon/cache/runs/foo/bar")));
+            assert_eq!(path.extension().unwrap(), "log");
+            assert_eq!(fs::read_to_string(path).unwrap(), "output");
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_write_if_cache_off() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            let path = run_with_env("off", || cache.create_run_log("foo:bar", "output"))
+                .await
+                .unwrap();
+
+            assert_eq!(path, None);
+            assert!(!dir.path().join(".moon/cache/runs/foo/bar").exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_write_if_cache_readonly() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            let path = run_with_env("read", || cache.create_run_log("foo:bar", "output"))
+                .await
+                .unwrap();
+
+            assert_eq!(path, None);
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod get_run_logs {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_logs_in_order() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            dir.child(".moon/cache/runs/foo/bar/1650000000002.log")
+                .write_str("")
+                .unwrap();
+            dir.child(".moon/cache/runs/foo/bar/1650000000001.log")
+                .write_str("")
+                .unwrap();
+            dir.child(".moon/cache/runs/foo/bar/lastRunState.json")
+                .write_str("{}")
+                .unwrap();
+
+            assert_eq!(
+                cache.get_run_logs("foo:bar").await.unwrap(),
+                vec![
+                    cache.runs_dir.join("foo/bar/1650000000001.log"),
+                    cache.runs_dir.join("foo/bar/1650000000002.log"),
+                ]
+            );
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_empty_if_no_logs() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            assert!(cache.get_run_logs("foo:bar").await.unwrap().is_empty());
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod delete_runfiles {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn deletes_dir() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            let runfile1 = cache
+                .create_runfile("123", &"content".to_owned())
+                .await
+                .unwrap();
+            let runfile2 = cache
+                .create_runfile("456", &"content".to_owned())
+                .await
+                .unwrap();
+
+            assert!(runfile1.path.exists());
+            assert!(runfile2.path.exists());
+
+            cache.delete_runfiles().await.unwrap();
+
+            assert!(!runfile1.path.exists());
+            assert!(!runfile2.path.exists());
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod delete_hash {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn deletes_files() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            dir.child(".moon/cache/hashes/abc123.json")
+                .write_str("{}")
+                .unwrap();
+
+            dir.child(".moon/cache/out/abc123/file.js")
+                .write_str("")
+                .unwrap();
+
+            let hash_file = cache.hashes_dir.join("abc123.json");
+            let out_file = cache.outputs_dir.join("abc123/file.js");
+
+            assert!(hash_file.exists());
+            assert!(out_file.exists());
+
+            cache.delete_hash("abc123").await.unwrap();
+
+            assert!(!hash_file.exists());
+            assert!(!out_file.exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_delete_if_cache_off() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            dir.child(".moon/cache/hashes/abc123.json")
+                .write_str("{}")
+                .unwrap();
+
+            dir.child(".moon/cache/out/abc123/file.js")
+                .write_str("")
+                .unwrap();
+
+            let hash_file = cache.hashes_dir.join("abc123.json");
+            let out_file = cache.outputs_dir.join("abc123/file.js");
+
+            assert!(hash_file.exists());
+            assert!(out_file.exists());
+
+            run_with_env("off", || cache.delete_hash("abc123"))
+                .await
+                .unwrap();
+
+            assert!(hash_file.exists());
+            assert!(out_file.exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_delete_if_cache_readonly() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            dir.child(".moon/cache/hashes/abc123.json")
+                .write_str("{}")
+                .unwrap();
+
+            dir.child(".moon/cache/out/abc123/file.js")
+                .write_str("")
+                .unwrap();
+
+            let hash_file = cache.hashes_dir.join("abc123.json");
+            let out_file = cache.outputs_dir.join("abc123/file.js");
+
+            assert!(hash_file.exists());
+            assert!(out_file.exists());
+
+            run_with_env("read", || cache.delete_hash("abc123"))
+                .await
+                .unwrap();
+
+            assert!(hash_file.exists());
+            assert!(out_file.exists());
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod prune {
+        use super::*;
+        use filetime::{set_file_mtime, FileTime};
+
+        fn create_hash(dir: &assert_fs::TempDir, hash: &str, target: &str, age_secs: u64) {
+            let manifest = dir.child(format!(".moon/cache/hashes/{}.json", hash));
+
+            manifest
+                .write_str(&format!(r#"{{"target":"{}"}}"#, target))
+                .unwrap();
+
+            dir.child(format!(".moon/cache/out/{}/file.js", hash))
+                .write_str("output")
+                .unwrap();
+
+            let now = to_millis(SystemTime::now()) / 1000;
+
+            set_file_mtime(
+                manifest.path(),
+                FileTime::from_unix_time((now as u64 - age_secs) as i64, 0),
+            )
+            .unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn keeps_recent_hashes_per_target() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            create_hash(&dir, "a1", "app:build", 300);
+            create_hash(&dir, "a2", "app:build", 200);
+            create_hash(&dir, "a3", "app:build", 100);
+            create_hash(&dir, "b1", "app:test", 400);
+
+            let stats = cache
+                .prune(&PruneCacheConfig {
+                    keep_per_target: Some(2),
+                    ..PruneCacheConfig::default()
+                })
+                .await
+                .unwrap();
+
+            assert_eq!(stats.hashes, 1);
+            assert!(stats.bytes > 0);
+            assert!(!cache.hashes_dir.join("a1.json").exists());
+            assert!(!cache.outputs_dir.join("a1").exists());
+            assert!(cache.hashes_dir.join("a2.json").exists());
+            assert!(cache.hashes_dir.join("a3.json").exists());
+            assert!(cache.hashes_dir.join("b1.json").exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn deletes_hashes_older_than_max_age() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            create_hash(&dir, "old", "app:build", 60 * 60 * 24 * 10);
+            create_hash(&dir, "new", "app:build", 60);
+
+            let stats = cache
+                .prune(&PruneCacheConfig {
+                    max_age: Some(7),
+                    ..PruneCacheConfig::default()
+                })
+                .await
+                .unwrap();
+
+            assert_eq!(stats.hashes, 1);
+            assert!(!cache.hashes_dir.join("old.json").exists());
+            assert!(!cache.outputs_dir.join("old").exists());
+            assert!(cache.hashes_dir.join("new.json").exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn deletes_oldest_hashes_over_max_size() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let content = "x".repeat(600 * 1024);
+
+            for (hash, age) in [("a", 300), ("b", 200), ("c", 100)] {
+                create_hash(&dir, hash, "app:build", age);
+
+                dir.child(format!(".moon/cache/out/{}/large.js", hash))
+                    .write_str(&content)
+                    .unwrap();
+            }
+
+            let stats = cache
+                .prune(&PruneCacheConfig {
+                    max_size: Some(1),
+                    ..PruneCacheConfig::default()
+                })
+                .await
+                .unwrap();
+
+            assert_eq!(stats.hashes, 2);
+            assert!(stats.bytes > 1024 * 1024);
+            assert!(!cache.hashes_dir.join("a.json").exists());
+            assert!(!cache.hashes_dir.join("b.json").exists());
+            assert!(cache.hashes_dir.join("c.json").exists());
+
+            dir.close().unwrap();
+        }
 
         #[tokio::test]
         #[serial]
-        async fn deletes_dir() {
+        async fn deletes_orphaned_outputs() {
             let dir = assert_fs::TempDir::new().unwrap();
             let cache = CacheEngine::create(dir.path()).await.unwrap();
 
-            let runfile1 = cache
-                .create_runfile("123", &"content".to_owned())
-                .await
-                .unwrap();
-            let runfile2 = cache
-                .create_runfile("456", &"content".to_owned())
-                .await
-                .unwrap();
+            create_hash(&dir, "abc", "app:build", 60);
 
-            assert!(runfile1.path.exists());
-            assert!(runfile2.path.exists());
+            dir.child(".moon/cache/out/orphan/file.js")
+                .write_str("output")
+                .unwrap();
 
-            cache.delete_runfiles().await.unwrap();
+            let stats = cache.prune(&PruneCacheConfig::default()).await.unwrap();
 
-            assert!(!runfile1.path.exists());
-            assert!(!runfile2.path.exists());
+            assert_eq!(stats.hashes, 1);
+            assert!(!cache.outputs_dir.join("orphan").exists());
+            assert!(cache.outputs_dir.join("abc").exists());
 
             dir.close().unwrap();
         }
-    }
-
-    mod delete_hash {
-        use super::*;
 
         #[tokio::test]
         #[serial]
-        async fn deletes_files() {
+        async fn keeps_recent_logs_per_target() {
             let dir = assert_fs::TempDir::new().unwrap();
             let cache = CacheEngine::create(dir.path()).await.unwrap();
 
-            dir.child(".moon/cache/hashes/abc123.json")
-                .write_str("{}")
-                .unwrap();
+            create_hash(&dir, "abc", "app:build", 60);
+
+            for name in [
+                "app/build/1650000000001.log",
+                "app/build/1650000000002.log",
+                "app/build/lastRunState.json",
+                "_setupToolchain/1650000000001.log",
+            ] {
+                dir.child(format!(".moon/cache/runs/{}", name))
+                    .write_str("log")
+                    .unwrap();
+            }
 
-            dir.child(".moon/cache/out/abc123/file.js")
-                .write_str("")
+            let stats = cache
+                .prune(&PruneCacheConfig {
+                    keep_per_target: Some(1),
+                    ..PruneCacheConfig::default()
+                })
+                .await
                 .unwrap();
 
-            let hash_file = cache.hashes_dir.join("abc123.json");
-            let out_file = cache.outputs_dir.join("abc123/file.js");
-
-            assert!(hash_file.exists());
-            assert!(out_file.exists());
-
-            cache.delete_hash("abc123").await.unwrap();
-
-            assert!(!hash_file.exists());
-            assert!(!out_file.exists());
+            assert_eq!(stats.logs, 1);
+            assert!(!cache.runs_dir.join("app/build/1650000000001.log").exists());
+            assert!(cache.runs_dir.join("app/build/1650000000002.log").exists());
+            assert!(cache.runs_dir.join("app/build/lastRunState.json").exists());
+            assert!(cache
+                .runs_dir
+                .join("_setupToolchain/1650000000001.log")
+                .exists());
 
             dir.close().unwrap();
         }
 
         #[tokio::test]
         #[serial]
-        async fn doesnt_delete_if_cache_off() {
+        async fn deletes_run_states_without_a_hash() {
             let dir = assert_fs::TempDir::new().unwrap();
             let cache = CacheEngine::create(dir.path()).await.unwrap();
 
-            dir.child(".moon/cache/hashes/abc123.json")
-                .write_str("{}")
-                .unwrap();
+            create_hash(&dir, "abc", "app:build", 60);
+
+            for name in [
+                "app/build/lastRunState.json",
+                "app/lint/1650000000001.log",
+                "app/lint/lastRunState.json",
+                "app/runfile.json",
+                "old/build/lastRunState.json",
+                "old/runfile.json",
+            ] {
+                dir.child(format!(".moon/cache/runs/{}", name))
+                    .write_str("{}")
+                    .unwrap();
+            }
 
-            dir.child(".moon/cache/out/abc123/file.js")
-                .write_str("")
-                .unwrap();
+            cache.prune(&PruneCacheConfig::default()).await.unwrap();
 
-            let hash_file = cache.hashes_dir.join("abc123.json");
-            let out_file = cache.outputs_dir.join("abc123/file.js");
+            assert!(cache.runs_dir.join("app/build/lastRunState.json").exists());
+            assert!(cache.runs_dir.join("app/runfile.json").exists());
+            assert!(!cache.runs_dir.join("app/lint/lastRunState.json").exists());
+            assert!(cache.runs_dir.join("app/lint/1650000000001.log").exists());
+            assert!(!cache.runs_dir.join("old").exists());
 
-            assert!(hash_file.exists());
-            assert!(out_file.exists());
+            dir.close().unwrap();
+        }
 
-            run_with_env("off", || cache.delete_hash("abc123"))
-                .await
-                .unwrap();
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_prune_if_cache_readonly() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
 
-            assert!(hash_file.exists());
-            assert!(out_file.exists());
+            create_hash(&dir, "a1", "app:build", 200);
+            create_hash(&dir, "a2", "app:build", 100);
+
+            let policy = PruneCacheConfig {
+                keep_per_target: Some(1),
+                ..PruneCacheConfig::default()
+            };
+            let stats = run_with_env("read", || cache.prune(&policy)).await.unwrap();
+
+            assert_eq!(stats, PruneStats::default());
+            assert!(cache.hashes_dir.join("a1.json").exists());
 
             dir.close().unwrap();
         }
+    }
+
+    mod clean_all {
+        use super::*;
 
         #[tokio::test]
         #[serial]
-        async fn doesnt_delete_if_cache_readonly() {
+        async fn deletes_everything() {
             let dir = assert_fs::TempDir::new().unwrap();
             let cache = CacheEngine::create(dir.path()).await.unwrap();
 
-            dir.child(".moon/cache/hashes/abc123.json")
+            dir.child(".moon/cache/hashes/abc.json")
                 .write_str("{}")
                 .unwrap();
-
-            dir.child(".moon/cache/out/abc123/file.js")
-                .write_str("")
+            dir.child(".moon/cache/out/abc/file.js")
+                .write_str("output")
                 .unwrap();
-
-            let hash_file = cache.hashes_dir.join("abc123.json");
-            let out_file = cache.outputs_dir.join("abc123/file.js");
-
-            assert!(hash_file.exists());
-            assert!(out_file.exists());
-
-            run_with_env("read", || cache.delete_hash("abc123"))
-                .await
+            dir.child(".moon/cache/runs/app/build/1650000000001.log")
+                .write_str("log")
+                .unwrap();
+            dir.child(".moon/cache/tasks/app/build/eslint/file")
+                .write_str("cache")
                 .unwrap();
 
-            assert!(hash_file.exists());
-            assert!(out_file.exists());
+            let stats = cache.clean_all().await.unwrap();
+
+            assert_eq!(stats.hashes, 1);
+            assert_eq!(stats.logs, 1);
+            assert!(stats.bytes > 0);
+            assert!(!cache.hashes_dir.join("abc.json").exists());
+            assert!(!cache.outputs_dir.join("abc").exists());
+            assert!(!cache.runs_dir.join("app").exists());
+            assert!(!cache.dir.join("tasks").exists());
+            assert!(cache.hashes_dir.exists());
 
             dir.close().unwrap();
         }
@@ -451,7 +1292,7 @@ mod tests {
 
             assert_eq!(
                 fs::read_to_string(item.path).unwrap(),
-                r#"{"exitCode":123,"hash":"","lastRunTime":0,"stderr":"","stdout":"","target":"foo:bar"}"#
+                r#"{"exitCode":123,"hash":"","lastRunDuration":0,"lastRunTime":0,"stderr":"","stdout":"","target":"foo:bar"}"#
             );
 
             dir.close().unwrap();
@@ -522,6 +1363,7 @@ mod tests {
                 item.item,
                 WorkspaceState {
                     last_node_install_time: 123,
+                    last_prune_time: 0,
                     last_version_check_time: 0,
                 }
             );
@@ -547,6 +1389,7 @@ mod tests {
                 item.item,
                 WorkspaceState {
                     last_node_install_time: 123,
+                    last_prune_time: 0,
                     last_version_check_time: 0,
                 }
             );
@@ -727,6 +1570,288 @@ mod tests {
         }
     }
 
+    mod hydrate_task_output_from_out {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn links_file_back_to_project() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            dir.child(".moon/cache/out/abc123/lib/file.js")
+                .write_str("cached")
+                .unwrap();
+
+            let project_root = dir.path().join("project");
+            let output = project_root.join("lib/file.js");
+
+            let result = cache
+                .hydrate_task_output_from_out("abc123", &project_root, &output)
+                .await
+                .unwrap();
+
+            assert!(result);
+            assert_eq!(fs::read_to_string(output).unwrap(), "cached");
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn links_dir_back_to_project() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            dir.child(".moon/cache/out/abc123/lib/one.js")
+                .write_str("")
+                .unwrap();
+
+            dir.child(".moon/cache/out/abc123/lib/nested/two.js")
+                .write_str("")
+                .unwrap();
+
+            let project_root = dir.path().join("project");
+
+            let result = cache
+                .hydrate_task_output_from_out("abc123", &project_root, &project_root.join("lib"))
+                .await
+                .unwrap();
+
+            assert!(result);
+            assert!(project_root.join("lib/one.js").exists());
+            assert!(project_root.join("lib/nested/two.js").exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn replaces_stale_output() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            dir.child(".moon/cache/out/abc123/lib/file.js")
+                .write_str("cached")
+                .unwrap();
+
+            dir.child("project/lib/file.js").write_str("stale").unwrap();
+
+            let project_root = dir.path().join("project");
+            let output = project_root.join("lib/file.js");
+
+            cache
+                .hydrate_task_output_from_out("abc123", &project_root, &output)
+                .await
+                .unwrap();
+
+            assert_eq!(fs::read_to_string(output).unwrap(), "cached");
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_false_if_output_missing() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            let project_root = dir.path().join("project");
+
+            let result = cache
+                .hydrate_task_output_from_out("abc123", &project_root, &project_root.join("lib"))
+                .await
+                .unwrap();
+
+            assert!(!result);
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_hydrate_if_cache_off() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            dir.child(".moon/cache/out/abc123/lib/file.js")
+                .write_str("")
+                .unwrap();
+
+            let project_root = dir.path().join("project");
+            let output = project_root.join("lib/file.js");
+
+            let result = run_with_env("off", || {
+                cache.hydrate_task_output_from_out("abc123", &project_root, &output)
+            })
+            .await
+            .unwrap();
+
+            assert!(!result);
+            assert!(!output.exists());
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod remote {
+        use super::*;
+        use mockito::{mock, Matcher};
+        use moon_config::RemoteCacheConfig;
+
+        async fn create_cache(dir: &Path, read_only: bool) -> CacheEngine {
+            let mut cache = CacheEngine::create(dir).await.unwrap();
+
+            cache.remote = Some(RemoteCache::new(&RemoteCacheConfig {
+                host: mockito::server_url(),
+                read_only,
+            }));
+
+            cache
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn downloads_and_persists_artifacts() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = create_cache(dir.path(), false).await;
+
+            let archive_dir = assert_fs::TempDir::new().unwrap();
+            archive_dir
+                .child("lib/file.js")
+                .write_str("remote")
+                .unwrap();
+
+            let state = mock("GET", "/abc123/runState.json")
+                .with_body(r#"{"exitCode":0,"hash":"abc123","lastRunTime":0,"stderr":"","stdout":"out","target":"foo:bar"}"#)
+                .create();
+            let manifest = mock("GET", "/abc123/manifest.json")
+                .with_body("{}")
+                .create();
+            let outputs = mock("GET", "/abc123/outputs.tar.gz")
+                .with_body(pack_dir(archive_dir.path()).unwrap())
+                .create();
+
+            let item = cache.download_from_remote("abc123").await.unwrap().unwrap();
+
+            state.assert();
+            manifest.assert();
+            outputs.assert();
+
+            assert_eq!(item.hash, "abc123");
+            assert_eq!(item.stdout, "out");
+            assert!(cache.hashes_dir.join("abc123.json").exists());
+            assert_eq!(
+                fs::read_to_string(cache.outputs_dir.join("abc123/lib/file.js")).unwrap(),
+                "remote"
+            );
+
+            archive_dir.close().unwrap();
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_none_on_remote_miss() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = create_cache(dir.path(), false).await;
+
+            let _m = mock("GET", "/abc123/runState.json")
+                .with_status(404)
+                .create();
+
+            assert!(cache
+                .download_from_remote("abc123")
+                .await
+                .unwrap()
+                .is_none());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_download_without_remote() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            assert!(cache
+                .download_from_remote("abc123")
+                .await
+                .unwrap()
+                .is_none());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_download_if_cache_readonly() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = create_cache(dir.path(), false).await;
+
+            let m = mock("GET", Matcher::Any).expect(0).create();
+
+            run_with_env("read", || cache.download_from_remote("abc123"))
+                .await
+                .unwrap();
+
+            m.assert();
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn uploads_artifacts() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = create_cache(dir.path(), false).await;
+
+            dir.child(".moon/cache/hashes/abc123.json")
+                .write_str("{}")
+                .unwrap();
+
+            dir.child(".moon/cache/out/abc123/lib/file.js")
+                .write_str("")
+                .unwrap();
+
+            let manifest = mock("PUT", "/abc123/manifest.json")
+                .match_body("{}")
+                .create();
+            let outputs = mock("PUT", "/abc123/outputs.tar.gz").create();
+            let state = mock("PUT", "/abc123/runState.json").create();
+
+            cache
+                .upload_to_remote("abc123", &RunTargetState::default())
+                .await
+                .unwrap();
+
+            manifest.assert();
+            outputs.assert();
+            state.assert();
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_upload_if_remote_readonly() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = create_cache(dir.path(), true).await;
+
+            let m = mock("PUT", Matcher::Any).expect(0).create();
+
+            cache
+                .upload_to_remote("abc123", &RunTargetState::default())
+                .await
+                .unwrap();
+
+            m.assert();
+
+            dir.close().unwrap();
+        }
+    }
+
     mod save_hash {
         use super::*;
         use serde::Deserialize;
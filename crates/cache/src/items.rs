@@ -90,6 +90,10 @@ pub struct RunTargetState {
 
     pub hash: String,
 
+    /// Duration of the last run in milliseconds, including all attempts.
+    #[serde(default)]
+    pub last_run_duration: u128,
+
     pub last_run_time: u128,
 
     pub stderr: String,
@@ -115,6 +119,9 @@ pub struct WorkspaceState {
     #[serde(default)]
     pub last_node_install_time: u128,
 
+    #[serde(default)]
+    pub last_prune_time: u128,
+
     #[serde(default)]
     pub last_version_check_time: u128,
 }
@@ -1,8 +1,12 @@
 mod engine;
 mod helpers;
 mod items;
+mod prune;
+mod remote;
 mod runfiles;
 
 pub use engine::CacheEngine;
 pub use helpers::*;
 pub use items::*;
+pub use prune::{PruneStats, DAY_MILLIS};
+pub use remote::RemoteCache;
@@ -0,0 +1,54 @@
+use moon_error::MoonError;
+use moon_utils::fs;
+use std::path::{Path, PathBuf};
+
+pub const DAY_MILLIS: u128 = 24 * 60 * 60 * 1000;
+
+/// Statistics about cache entries that were deleted while pruning.
+#[derive(Debug, Default, PartialEq)]
+pub struct PruneStats {
+    /// Total size in bytes of all deleted files.
+    pub bytes: u64,
+
+    /// Amount of hashes (and their outputs) deleted.
+    pub hashes: usize,
+
+    /// Amount of run logs deleted.
+    pub logs: usize,
+}
+
+/// A hash manifest in `.moon/cache/hashes`, and its outputs in `.moon/cache/out`.
+pub struct HashEntry {
+    pub hash: String,
+
+    pub manifest_path: PathBuf,
+
+    /// Last modified time of the manifest in milliseconds.
+    pub modified: u128,
+
+    pub outputs_path: PathBuf,
+
+    /// Combined size of the manifest and outputs in bytes.
+    pub size: u64,
+
+    pub target: String,
+}
+
+/// Return the size of a file, or the combined size of all files within a directory.
+pub async fn get_size(path: &Path) -> Result<u64, MoonError> {
+    if !path.exists() {
+        return Ok(0);
+    }
+
+    if path.is_file() {
+        return Ok(fs::metadata(path).await?.len());
+    }
+
+    let mut size = 0;
+
+    for entry in fs::read_dir_all(path).await? {
+        size += fs::metadata(&entry.path()).await?.len();
+    }
+
+    Ok(size)
+}
@@ -0,0 +1,250 @@
+use crate::helpers::LOG_TARGET;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use moon_config::RemoteCacheConfig;
+use moon_error::{map_io_to_fs_error, MoonError};
+use moon_logger::{color, trace};
+use reqwest::StatusCode;
+use std::env;
+use std::path::Path;
+use tar::{Archive, Builder};
+
+pub const REMOTE_MANIFEST_FILE: &str = "manifest.json";
+
+pub const REMOTE_OUTPUTS_FILE: &str = "outputs.tar.gz";
+
+pub const REMOTE_RUN_STATE_FILE: &str = "runState.json";
+
+pub struct RemoteCache {
+    client: reqwest::Client,
+
+    /// Base URL of the remote server, without a trailing slash.
+    pub host: String,
+
+    /// When enabled, artifacts are downloaded but never uploaded.
+    pub read_only: bool,
+
+    /// Bearer token inherited from `MOON_REMOTE_CACHE_TOKEN`.
+    token: Option<String>,
+}
+
+impl RemoteCache {
+    pub fn new(config: &RemoteCacheConfig) -> Self {
+        RemoteCache {
+            client: reqwest::Client::new(),
+            host: config.host.trim_end_matches('/').to_owned(),
+            read_only: config.read_only,
+            token: env::var("MOON_REMOTE_CACHE_TOKEN").ok(),
+        }
+    }
+
+    pub fn get_url(&self, hash: &str, file: &str) -> String {
+        format!("{}/{}/{}", self.host, hash, file)
+    }
+
+    /// Download an artifact for the provided hash. Will return `None`
+    /// if the artifact does not exist on the remote (a cache miss).
+    pub async fn download(&self, hash: &str, file: &str) -> Result<Option<Vec<u8>>, MoonError> {
+        let url = self.get_url(hash, file);
+        let handle_error = |e: reqwest::Error| MoonError::Http(url.clone(), e.to_string());
+
+        trace!(
+            target: LOG_TARGET,
+            "Downloading remote artifact {}",
+            color::url(&url)
+        );
+
+        let mut request = self.client.get(&url);
+
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await.map_err(handle_error)?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => {
+                Ok(Some(response.bytes().await.map_err(handle_error)?.to_vec()))
+            }
+            status => Err(MoonError::Http(url, status.to_string())),
+        }
+    }
+
+    /// Upload an artifact for the provided hash.
+    pub async fn upload(&self, hash: &str, file: &str, body: Vec<u8>) -> Result<(), MoonError> {
+        let url = self.get_url(hash, file);
+
+        trace!(
+            target: LOG_TARGET,
+            "Uploading remote artifact {}",
+            color::url(&url)
+        );
+
+        let mut request = self.client.put(&url).body(body);
+
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| MoonError::Http(url.clone(), e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(MoonError::Http(url, response.status().to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Archive the contents of a directory into a gzipped tarball.
+pub fn pack_dir(dir: &Path) -> Result<Vec<u8>, MoonError> {
+    let handle_error = |e| map_io_to_fs_error(e, dir.to_path_buf());
+    let mut archive = Builder::new(GzEncoder::new(vec![], Compression::default()));
+
+    archive.append_dir_all(".", dir).map_err(handle_error)?;
+
+    archive
+        .into_inner()
+        .map_err(handle_error)?
+        .finish()
+        .map_err(handle_error)
+}
+
+/// Unpack a gzipped tarball (created with `pack_dir`) into a directory.
+pub fn unpack_dir(bytes: &[u8], dir: &Path) -> Result<(), MoonError> {
+    let mut archive = Archive::new(GzDecoder::new(bytes));
+
+    archive
+        .unpack(dir)
+        .map_err(|e| map_io_to_fs_error(e, dir.to_path_buf()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::*;
+    use mockito::mock;
+    use serial_test::serial;
+
+    fn create_remote() -> RemoteCache {
+        RemoteCache::new(&RemoteCacheConfig {
+            host: format!("{}/", mockito::server_url()),
+            read_only: false,
+        })
+    }
+
+    mod download {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_body() {
+            let m = mock("GET", "/abc123/runState.json")
+                .with_body("{}")
+                .create();
+
+            let data = create_remote()
+                .download("abc123", REMOTE_RUN_STATE_FILE)
+                .await
+                .unwrap();
+
+            m.assert();
+
+            assert_eq!(data, Some(b"{}".to_vec()));
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_none_when_missing() {
+            let m = mock("GET", "/abc123/runState.json")
+                .with_status(404)
+                .create();
+
+            let data = create_remote()
+                .download("abc123", REMOTE_RUN_STATE_FILE)
+                .await
+                .unwrap();
+
+            m.assert();
+
+            assert_eq!(data, None);
+        }
+
+        #[tokio::test]
+        #[serial]
+        #[should_panic(expected = "Http")]
+        async fn errors_on_server_failure() {
+            let _m = mock("GET", "/abc123/runState.json")
+                .with_status(500)
+                .create();
+
+            create_remote()
+                .download("abc123", REMOTE_RUN_STATE_FILE)
+                .await
+                .unwrap();
+        }
+    }
+
+    mod upload {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn puts_body() {
+            let m = mock("PUT", "/abc123/manifest.json")
+                .match_body("{}")
+                .create();
+
+            create_remote()
+                .upload("abc123", REMOTE_MANIFEST_FILE, b"{}".to_vec())
+                .await
+                .unwrap();
+
+            m.assert();
+        }
+
+        #[tokio::test]
+        #[serial]
+        #[should_panic(expected = "Http")]
+        async fn errors_on_server_failure() {
+            let _m = mock("PUT", "/abc123/manifest.json")
+                .with_status(403)
+                .create();
+
+            create_remote()
+                .upload("abc123", REMOTE_MANIFEST_FILE, b"{}".to_vec())
+                .await
+                .unwrap();
+        }
+    }
+
+    mod archive {
+        use super::*;
+
+        #[test]
+        fn packs_and_unpacks_dir() {
+            let source = assert_fs::TempDir::new().unwrap();
+            let dest = assert_fs::TempDir::new().unwrap();
+
+            source.child("lib/one.js").write_str("one").unwrap();
+            source.child("esm/nested/two.js").write_str("two").unwrap();
+
+            let bytes = pack_dir(source.path()).unwrap();
+
+            unpack_dir(&bytes, dest.path()).unwrap();
+
+            dest.child("lib/one.js").assert("one");
+            dest.child("esm/nested/two.js").assert("two");
+
+            source.close().unwrap();
+            dest.close().unwrap();
+        }
+    }
+}
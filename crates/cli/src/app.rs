@@ -15,6 +15,39 @@ pub const BIN_NAME: &str = if cfg!(windows) { "moon.exe" } else { "moon" };
 const HEADING_AFFECTED: &str = "Affected by changes";
 const HEADING_PARALLELISM: &str = "Parallelism and distribution";
 
+#[derive(Debug, Subcommand)]
+pub enum HashCommands {
+    // moon hash diff <left> [right]
+    #[clap(
+        name = "diff",
+        about = "Compare two hash manifests and display their differences."
+    )]
+    Diff {
+        #[clap(help = "Hash to compare from, or a target (project:task) when using --last")]
+        left: String,
+
+        #[clap(help = "Hash to compare to", required_unless_present = "last")]
+        right: Option<String>,
+
+        #[clap(
+            long,
+            help = "Compare the hash of the target's last run to its current hash",
+            conflicts_with = "right"
+        )]
+        last: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SchemaCommands {
+    // moon schema generate
+    #[clap(
+        name = "generate",
+        about = "Generate JSON schemas for all configuration files into .moon/cache/schemas."
+    )]
+    Generate,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     // ENVIRONMENT
@@ -77,6 +110,50 @@ pub enum Commands {
     )]
     Teardown,
 
+    // moon clean
+    #[clap(
+        name = "clean",
+        about = "Clean the workspace and delete stale cache entries.",
+        rename_all = "camelCase"
+    )]
+    Clean {
+        #[clap(long, help = "Delete all hashes, outputs, runs, and task caches")]
+        all: bool,
+
+        #[clap(
+            long,
+            help = "Amount of recent hashes and logs to keep for each target"
+        )]
+        keep_per_target: Option<usize>,
+
+        #[clap(long, help = "Delete hashes and logs older than this many days")]
+        max_age: Option<u64>,
+
+        #[clap(
+            long,
+            help = "Delete the oldest hashes once their total size exceeds this many megabytes"
+        )]
+        max_size: Option<u64>,
+    },
+
+    // moon schema <command>
+    #[clap(
+        name = "schema",
+        about = "Manage JSON schemas for configuration files.",
+        arg_required_else_help = true
+    )]
+    Schema {
+        #[clap(subcommand)]
+        command: SchemaCommands,
+    },
+
+    // moon validate
+    #[clap(
+        name = "validate",
+        about = "Validate all configuration files, and references between projects and tasks."
+    )]
+    Validate,
+
     // PROJECTS
 
     // moon project <id>
@@ -88,6 +165,27 @@ pub enum Commands {
         #[clap(help = "ID of project to display")]
         id: String,
 
+        #[clap(
+            long,
+            help = "List the project's dependencies (direct and transitive)",
+            group = "relations"
+        )]
+        deps: bool,
+
+        #[clap(
+            long,
+            help = "List the project's dependents (direct and transitive)",
+            group = "relations"
+        )]
+        dependents: bool,
+
+        #[clap(
+            long,
+            help = "Maximum depth of dependencies or dependents to list (defaults to all)",
+            requires = "relations"
+        )]
+        depth: Option<usize>,
+
         #[clap(long, help = "Print in JSON format")]
         json: bool,
     },
@@ -95,12 +193,36 @@ pub enum Commands {
     // moon project-graph [id]
     #[clap(
         name = "project-graph",
-        about = "Display a graph of projects in DOT format.",
+        about = "Display a graph of projects in DOT format, or serve an interactive graph.",
         alias = "graph"
     )]
     ProjectGraph {
         #[clap(help = "ID of project to *only* graph")]
         id: Option<String>,
+
+        #[clap(
+            long,
+            help = "Only graph projects with this tag (and their dependencies)",
+            conflicts_with_all = &["id", "serve"]
+        )]
+        tag: Option<String>,
+
+        #[clap(long, help = "Serve an interactive graph of projects and tasks")]
+        serve: bool,
+
+        #[clap(
+            long,
+            default_value = "127.0.0.1",
+            help = "Host to bind the graph server to"
+        )]
+        host: String,
+
+        #[clap(
+            long,
+            default_value_t = 8000,
+            help = "Port to bind the graph server to"
+        )]
+        port: u16,
     },
 
     // JOBS
@@ -118,21 +240,57 @@ pub enum Commands {
         #[clap(long, help = "Current branch, commit, or revision to compare with")]
         head: Option<String>,
 
+        #[clap(
+            long,
+            help = "Don't run dependents that only depend on a project for development"
+        )]
+        ignore_dev_dependents: bool,
+
         #[clap(long, help = "Index of the current job", help_heading = HEADING_PARALLELISM)]
         job: Option<usize>,
 
         #[clap(long, help = "Total amount of jobs to run", help_heading = HEADING_PARALLELISM)]
         job_total: Option<usize>,
+
+        #[clap(
+            long,
+            help = "Write the job distribution plan to a file (without --job), or read it (with --job)",
+            help_heading = HEADING_PARALLELISM
+        )]
+        plan: Option<PathBuf>,
+
+        #[clap(
+            long,
+            help = "Maximum amount of actions to run in parallel (defaults to CPU count)",
+            help_heading = HEADING_PARALLELISM
+        )]
+        concurrency: Option<usize>,
+
+        #[clap(
+            long,
+            help = "Write a report of all actions to a JSON file (or JUnit if the path ends in .xml)"
+        )]
+        report: Option<PathBuf>,
+
+        #[clap(
+            long,
+            help = "Skip actions that depend on a failed action, instead of running them"
+        )]
+        skip_failed_dependents: bool,
     },
 
     // moon run [...targets]
     #[clap(
         name = "run",
-        about = "Run a project task and all its dependent tasks."
+        about = "Run one or many project tasks and all their dependent tasks.",
+        rename_all = "camelCase"
     )]
     Run {
-        #[clap(help = "Target (project:task) to run")]
-        target: TargetID,
+        #[clap(
+            help = "Targets (project:task) to run, supports globs in the project and task position",
+            required = true
+        )]
+        targets: Vec<TargetID>,
 
         #[clap(
             long,
@@ -140,6 +298,29 @@ pub enum Commands {
         )]
         dependents: bool,
 
+        #[clap(
+            long,
+            help = "Don't run dependents that only depend on a project for development",
+            requires = "dependents"
+        )]
+        ignore_dev_dependents: bool,
+
+        #[clap(
+            long,
+            help = "Maximum amount of actions to run in parallel (defaults to CPU count)",
+            help_heading = HEADING_PARALLELISM
+        )]
+        concurrency: Option<usize>,
+
+        #[clap(
+            long,
+            help = "Write a report of all actions to a JSON file (or JUnit if the path ends in .xml)"
+        )]
+        report: Option<PathBuf>,
+
+        #[clap(long, help = "Re-run affected targets when their input files change")]
+        watch: bool,
+
         // Affected
         #[clap(
             long,
@@ -171,6 +352,36 @@ pub enum Commands {
         )]
         passthrough: Vec<String>,
     },
+
+    // moon hash <target>
+    #[clap(
+        name = "hash",
+        about = "Display the hash manifest of a target without running it, or compare manifests.",
+        args_conflicts_with_subcommands = true,
+        arg_required_else_help = true
+    )]
+    Hash {
+        #[clap(help = "Target (project:task) to generate a hash manifest for")]
+        target: Option<TargetID>,
+
+        #[clap(subcommand)]
+        command: Option<HashCommands>,
+    },
+
+    // moon logs <target>
+    #[clap(
+        name = "logs",
+        about = "Display or list the logs of previous runs for a target or action."
+    )]
+    Logs {
+        #[clap(
+            help = "Target (project:task) or action (InstallNodeDeps, SetupToolchain, SyncProject(id)) to display logs for"
+        )]
+        target: String,
+
+        #[clap(long, help = "List all logs instead of displaying the latest")]
+        list: bool,
+    },
 }
 
 #[derive(Debug, Parser)]
@@ -4,11 +4,14 @@ use itertools::Itertools;
 use moon_logger::{color, debug};
 use moon_project::{Target, TouchedFilePaths};
 use moon_terminal::helpers::{replace_style_tokens, safe_exit};
-use moon_utils::{is_ci, path, time};
+use moon_utils::{fs, is_ci, path, time};
 use moon_workspace::DepGraph;
 use moon_workspace::{ActionRunner, ActionStatus, Workspace, WorkspaceError};
-use std::collections::HashSet;
-use std::path::PathBuf;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 type TargetList = Vec<Target>;
 
@@ -127,41 +130,315 @@ fn gather_runnable_targets(
     Ok(targets)
 }
 
-/// Distribute targets across jobs if parallelism is enabled.
-fn distribute_targets_across_jobs(options: &CiOptions, targets: TargetList) -> TargetList {
+/// Group targets that are related through their task dependencies or project
+/// dependents, as they must run on the same job to avoid running a chain twice.
+fn group_related_targets(
+    workspace: &Workspace,
+    targets: &TargetList,
+    ignore_dev_dependents: bool,
+) -> Result<Vec<TargetList>, WorkspaceError> {
+    let indices: HashMap<&str, usize> = targets
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (t.id.as_str(), i))
+        .collect();
+    let mut parents: Vec<usize> = (0..targets.len()).collect();
+
+    fn find(parents: &mut [usize], index: usize) -> usize {
+        let mut root = index;
+
+        while parents[root] != root {
+            root = parents[root];
+        }
+
+        parents[index] = root;
+        root
+    }
+
+    fn union(parents: &mut [usize], a: usize, b: usize) {
+        let a = find(parents, a);
+        let b = find(parents, b);
+
+        // Always point to the lowest index, so that grouping is deterministic
+        if a < b {
+            parents[b] = a;
+        } else {
+            parents[a] = b;
+        }
+    }
+
+    for (index, target) in targets.iter().enumerate() {
+        let (project_id, task_id) = target.ids()?;
+
+        // Walk the task's dependencies transitively
+        let mut queue = vec![target.id.clone()];
+        let mut visited = HashSet::new();
+
+        while let Some(target_id) = queue.pop() {
+            if !visited.insert(target_id.clone()) {
+                continue;
+            }
+
+            if let Some(dep_index) = indices.get(target_id.as_str()) {
+                union(&mut parents, index, *dep_index);
+            }
+
+            let (dep_project_id, dep_task_id) = Target::parse(&target_id)?.ids()?;
+            let dep_project = workspace.projects.load(&dep_project_id)?;
+
+            queue.extend(dep_project.get_task(&dep_task_id)?.deps.clone());
+        }
+
+        // Dependents (direct and transitive) of the same task are also ran
+        let project = workspace.projects.load(&project_id)?;
+
+        let dependents = if ignore_dev_dependents {
+            workspace
+                .projects
+                .get_transitive_non_dev_dependents_of(&project, None)?
+        } else {
+            workspace
+                .projects
+                .get_transitive_dependents_of(&project, None)?
+        };
+
+        for dependent_id in dependents {
+            let dependent_target_id = Target::format(&dependent_id, &task_id)?;
+
+            if let Some(dependent_index) = indices.get(dependent_target_id.as_str()) {
+                union(&mut parents, index, *dependent_index);
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, TargetList> = BTreeMap::new();
+
+    for (index, target) in targets.iter().enumerate() {
+        let root = find(&mut parents, index);
+
+        groups.entry(root).or_default().push(target.clone());
+    }
+
+    Ok(groups.into_values().collect())
+}
+
+/// Assign groups of targets to jobs, balanced by their weight (estimated duration).
+/// Heaviest groups are assigned first to the job with the least amount of work,
+/// and ties are broken by order, so every job computes the same plan.
+fn plan_job_distribution(
+    groups: Vec<(TargetList, u128)>,
+    job_total: usize,
+) -> Vec<(TargetList, u128)> {
+    let mut jobs: Vec<(TargetList, u128)> = vec![(vec![], 0); job_total];
+    let mut groups = groups;
+
+    groups.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    for (targets, weight) in groups {
+        let mut lightest = 0;
+
+        for (index, job) in jobs.iter().enumerate() {
+            if job.1 < jobs[lightest].1 {
+                lightest = index;
+            }
+        }
+
+        jobs[lightest].0.extend(targets);
+        jobs[lightest].1 += weight;
+    }
+
+    for job in &mut jobs {
+        job.0.sort();
+    }
+
+    jobs
+}
+
+/// Group related targets and weigh each group by the sum of its target durations.
+/// Targets without a duration are assumed to take an average amount of time,
+/// so without any durations, groups are weighed by the amount of targets.
+fn weigh_target_groups(
+    workspace: &Workspace,
+    targets: &TargetList,
+    durations: &HashMap<String, u128>,
+    ignore_dev_dependents: bool,
+) -> Result<Vec<(TargetList, u128)>, WorkspaceError> {
+    let default_duration = if durations.is_empty() {
+        1
+    } else {
+        durations.values().sum::<u128>() / durations.len() as u128
+    };
+
+    Ok(
+        group_related_targets(workspace, targets, ignore_dev_dependents)?
+            .into_iter()
+            .map(|group| {
+                let weight = group
+                    .iter()
+                    .map(|t| *durations.get(&t.id).unwrap_or(&default_duration))
+                    .sum();
+
+                (group, weight)
+            })
+            .collect(),
+    )
+}
+
+/// Plan the distribution of targets across all jobs, balanced by the duration
+/// of each target's previous run, and write the plan to a file. This is ran once
+/// (with a restored `.moon/cache`), so that every job reads the exact same plan,
+/// regardless of the state of its own local cache.
+async fn write_job_plan(
+    workspace: &Workspace,
+    options: &CiOptions,
+    targets: TargetList,
+    job_total: usize,
+    plan_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    print_header("Planning targets across jobs");
+    println!("Job total: {}", job_total);
+
+    let mut targets = targets;
+    targets.sort();
+
+    // Gather durations from the previous runs of each target
+    let mut durations = HashMap::new();
+
+    for target in &targets {
+        let state = workspace.cache.cache_run_target_state(&target.id).await?;
+
+        if state.item.last_run_duration > 0 {
+            durations.insert(target.id.clone(), state.item.last_run_duration);
+        }
+    }
+
+    let groups = weigh_target_groups(
+        workspace,
+        &targets,
+        &durations,
+        options.ignore_dev_dependents,
+    )?;
+
+    println!("Target groups: {}", groups.len());
+
+    let jobs = plan_job_distribution(groups, job_total);
+
+    for (index, (job_targets, weight)) in jobs.iter().enumerate() {
+        println!(
+            "Job {}: {} targets, estimated duration {}",
+            index,
+            job_targets.len(),
+            time::elapsed(Duration::from_millis(*weight as u64))
+        );
+    }
+
+    let plan = json!({
+        "jobTotal": job_total,
+        "jobs": jobs
+            .iter()
+            .map(|(job_targets, _)| job_targets.iter().map(|t| t.id.clone()).collect::<Vec<_>>())
+            .collect::<Vec<_>>(),
+    });
+
+    fs::write_json(plan_path, &plan, true).await?;
+
+    println!("Plan written to {}", color::path(plan_path));
+
+    Ok(())
+}
+
+/// Read the targets of the current job from a previously written plan.
+/// Only targets that are runnable for this job are returned.
+async fn read_job_plan(
+    targets: &TargetList,
+    job_index: usize,
+    job_total: usize,
+    plan_path: &Path,
+) -> Result<TargetList, Box<dyn Error>> {
+    let plan: Value = fs::read_json(plan_path).await?;
+
+    if plan["jobTotal"].as_u64() != Some(job_total as u64) {
+        return Err(format!(
+            "Plan {} was not created for a job total of {}.",
+            plan_path.display(),
+            job_total
+        )
+        .into());
+    }
+
+    let planned_ids = plan["jobs"][job_index]
+        .as_array()
+        .map(|ids| {
+            ids.iter()
+                .filter_map(|id| id.as_str())
+                .collect::<HashSet<_>>()
+        })
+        .unwrap_or_default();
+
+    Ok(targets
+        .iter()
+        .filter(|t| planned_ids.contains(t.id.as_str()))
+        .cloned()
+        .collect())
+}
+
+/// Distribute targets across jobs if parallelism is enabled. Related targets
+/// are kept on the same job. When a plan is provided, jobs are balanced by
+/// historical run durations, otherwise by the amount of targets.
+async fn distribute_targets_across_jobs(
+    workspace: &Workspace,
+    options: &CiOptions,
+    targets: TargetList,
+) -> Result<TargetList, Box<dyn Error>> {
     if options.job.is_none() || options.job_total.is_none() {
-        return targets;
+        return Ok(targets);
     }
 
     let job_index = options.job.unwrap();
     let job_total = options.job_total.unwrap();
-    let batch_size = targets.len() / job_total;
-    let batched_targets;
 
     print_header("Distributing targets across jobs");
     println!("Job index: {}", job_index);
-    println!("Job total: {}", job_index);
-    println!("Batch size: {}", batch_size);
-    println!("Batched targets:");
+    println!("Job total: {}", job_total);
+
+    let mut targets = targets;
+    targets.sort();
+
+    let batched_targets = if let Some(plan_path) = &options.plan {
+        println!("Plan: {}", color::path(plan_path));
 
-    if job_index == 0 {
-        batched_targets = targets[0..batch_size].to_vec();
-    } else if job_index == job_total - 1 {
-        batched_targets = targets[(batch_size * job_index)..].to_vec();
+        read_job_plan(&targets, job_index, job_total, plan_path).await?
     } else {
-        batched_targets =
-            targets[(batch_size * job_index)..(batch_size * (job_index + 1))].to_vec();
-    }
+        // Durations from local caches may differ between jobs, and all jobs
+        // must compute the exact same distribution, so only count targets
+        let groups = weigh_target_groups(
+            workspace,
+            &targets,
+            &HashMap::new(),
+            options.ignore_dev_dependents,
+        )?;
+
+        println!("Target groups: {}", groups.len());
+
+        plan_job_distribution(groups, job_total)
+            .into_iter()
+            .nth(job_index)
+            .map(|job| job.0)
+            .unwrap_or_default()
+    };
+
+    println!("Batched targets:");
 
     print_targets(&batched_targets);
 
-    batched_targets
+    Ok(batched_targets)
 }
 
 /// Generate a dependency graph with the runnable targets.
 fn generate_dep_graph(
     workspace: &Workspace,
     targets: &TargetList,
+    ignore_dev_dependents: bool,
 ) -> Result<DepGraph, WorkspaceError> {
     print_header("Generating dependency graph");
 
@@ -172,7 +449,7 @@ fn generate_dep_graph(
         dep_graph.run_target(target, &workspace.projects, None)?;
 
         // And also run its dependents to ensure consumers still work correctly
-        dep_graph.run_target_dependents(target, &workspace.projects)?;
+        dep_graph.run_target_dependents(target, &workspace.projects, ignore_dev_dependents)?;
     }
 
     println!("Target count: {}", targets.len());
@@ -183,9 +460,14 @@ fn generate_dep_graph(
 
 pub struct CiOptions {
     pub base: Option<String>,
+    pub concurrency: Option<usize>,
     pub head: Option<String>,
+    pub ignore_dev_dependents: bool,
     pub job: Option<usize>,
     pub job_total: Option<usize>,
+    pub plan: Option<PathBuf>,
+    pub report: Option<PathBuf>,
+    pub skip_failed_dependents: bool,
 }
 
 pub async fn ci(options: CiOptions) -> Result<(), Box<dyn std::error::Error>> {
@@ -197,14 +479,33 @@ pub async fn ci(options: CiOptions) -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    let targets = distribute_targets_across_jobs(&options, targets);
-    let dep_graph = generate_dep_graph(&workspace, &targets)?;
+    // Only plan the distribution, so that jobs can read it afterwards
+    if let (None, Some(job_total), Some(plan_path)) =
+        (options.job, options.job_total, &options.plan)
+    {
+        return write_job_plan(&workspace, &options, targets, job_total, plan_path).await;
+    }
+
+    let targets = distribute_targets_across_jobs(&workspace, &options, targets).await?;
+    let dep_graph = generate_dep_graph(&workspace, &targets, options.ignore_dev_dependents)?;
 
     // Process all tasks in the graph
     print_header("Running all targets");
 
     let mut runner = ActionRunner::new(workspace);
-    let results = runner.run(dep_graph).await?;
+
+    if let Some(concurrency) = options.concurrency {
+        runner.set_concurrency(concurrency);
+    }
+
+    if options.skip_failed_dependents {
+        runner.skip_failed_dependents();
+    }
+
+    let results = runner
+        .set_report_path(options.report)
+        .run(dep_graph)
+        .await?;
 
     // Print out the results and exit if an error occurs
     let mut error_count = 0;
@@ -214,11 +515,13 @@ pub async fn ci(options: CiOptions) -> Result<(), Box<dyn std::error::Error>> {
 
     for result in &results {
         let status = match result.status {
-            ActionStatus::Passed | ActionStatus::Cached | ActionStatus::Skipped => {
-                color::success("pass")
-            }
+            ActionStatus::Passed
+            | ActionStatus::Cached
+            | ActionStatus::CachedFromRemote
+            | ActionStatus::Skipped => color::success("pass"),
             ActionStatus::Failed | ActionStatus::FailedAndAbort => color::failure("fail"),
             ActionStatus::Invalid => color::invalid("warn"),
+            ActionStatus::SkippedFromFailure => color::muted_light("skip"),
             _ => color::muted_light("oops"),
         };
 
@@ -226,8 +529,12 @@ pub async fn ci(options: CiOptions) -> Result<(), Box<dyn std::error::Error>> {
 
         if matches!(result.status, ActionStatus::Cached) {
             meta.push(String::from("cached"));
+        } else if matches!(result.status, ActionStatus::CachedFromRemote) {
+            meta.push(String::from("cached from remote"));
         } else if matches!(result.status, ActionStatus::Skipped) {
             meta.push(String::from("skipped"));
+        } else if let Some(dependency) = &result.failed_dependency {
+            meta.push(format!("skipped, {} failed", dependency));
         } else if let Some(duration) = result.duration {
             meta.push(time::elapsed(duration));
         }
@@ -258,3 +565,142 @@ pub async fn ci(options: CiOptions) -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod plan_job_distribution {
+        use super::*;
+
+        fn group(ids: &[&str], weight: u128) -> (TargetList, u128) {
+            (
+                ids.iter().map(|id| Target::parse(id).unwrap()).collect(),
+                weight,
+            )
+        }
+
+        fn ids(job: &(TargetList, u128)) -> Vec<String> {
+            job.0.iter().map(|t| t.id.clone()).collect()
+        }
+
+        #[test]
+        fn balances_by_weight() {
+            let jobs = plan_job_distribution(
+                vec![
+                    group(&["a:build"], 10),
+                    group(&["b:build"], 100),
+                    group(&["c:build"], 20),
+                    group(&["d:build"], 30),
+                    group(&["e:build"], 50),
+                ],
+                2,
+            );
+
+            assert_eq!(ids(&jobs[0]), vec!["a:build", "b:build"]);
+            assert_eq!(jobs[0].1, 110);
+            assert_eq!(ids(&jobs[1]), vec!["c:build", "d:build", "e:build"]);
+            assert_eq!(jobs[1].1, 100);
+        }
+
+        #[test]
+        fn keeps_groups_together() {
+            let jobs = plan_job_distribution(
+                vec![
+                    group(&["a:build", "b:build", "c:build"], 30),
+                    group(&["d:build"], 10),
+                    group(&["e:build"], 10),
+                ],
+                2,
+            );
+
+            assert_eq!(ids(&jobs[0]), vec!["a:build", "b:build", "c:build"]);
+            assert_eq!(ids(&jobs[1]), vec!["d:build", "e:build"]);
+        }
+
+        #[test]
+        fn is_deterministic_with_equal_weights() {
+            let groups = || {
+                vec![
+                    group(&["c:build"], 1),
+                    group(&["a:build"], 1),
+                    group(&["b:build"], 1),
+                ]
+            };
+
+            let jobs = plan_job_distribution(groups(), 2);
+
+            assert_eq!(ids(&jobs[0]), vec!["a:build", "c:build"]);
+            assert_eq!(ids(&jobs[1]), vec!["b:build"]);
+            assert_eq!(ids(&plan_job_distribution(groups(), 2)[0]), ids(&jobs[0]));
+        }
+
+        #[test]
+        fn handles_more_jobs_than_groups() {
+            let jobs = plan_job_distribution(vec![group(&["a:build"], 5)], 3);
+
+            assert_eq!(ids(&jobs[0]), vec!["a:build"]);
+            assert!(jobs[1].0.is_empty());
+            assert!(jobs[2].0.is_empty());
+        }
+    }
+
+    mod read_job_plan {
+        use super::*;
+
+        async fn write_plan(name: &str, plan: Value) -> PathBuf {
+            let path = std::env::temp_dir().join(format!("moon-ci-plan-{}.json", name));
+
+            fs::write_json(&path, &plan, false).await.unwrap();
+
+            path
+        }
+
+        fn targets(ids: &[&str]) -> TargetList {
+            ids.iter().map(|id| Target::parse(id).unwrap()).collect()
+        }
+
+        #[tokio::test]
+        async fn returns_planned_targets_for_job() {
+            let path = write_plan(
+                "returns",
+                json!({ "jobTotal": 2, "jobs": [["a:build", "c:build"], ["b:build"]] }),
+            )
+            .await;
+
+            let batched = read_job_plan(&targets(&["a:build", "b:build", "c:build"]), 0, 2, &path)
+                .await
+                .unwrap();
+
+            assert_eq!(batched, targets(&["a:build", "c:build"]));
+        }
+
+        #[tokio::test]
+        async fn filters_targets_that_are_not_runnable() {
+            let path = write_plan(
+                "filters",
+                json!({ "jobTotal": 2, "jobs": [["a:build", "c:build"], ["b:build"]] }),
+            )
+            .await;
+
+            let batched = read_job_plan(&targets(&["a:build", "b:build"]), 0, 2, &path)
+                .await
+                .unwrap();
+
+            assert_eq!(batched, targets(&["a:build"]));
+        }
+
+        #[tokio::test]
+        async fn errors_for_mismatched_job_total() {
+            let path = write_plan(
+                "mismatched",
+                json!({ "jobTotal": 3, "jobs": [["a:build"], [], []] }),
+            )
+            .await;
+
+            assert!(read_job_plan(&targets(&["a:build"]), 0, 2, &path)
+                .await
+                .is_err());
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use moon_config::PruneCacheConfig;
+use moon_logger::color;
+use moon_workspace::Workspace;
+
+// Used when nothing has been configured or passed on the command line
+const DEFAULT_MAX_AGE: u64 = 7;
+
+pub struct CleanOptions {
+    pub all: bool,
+    pub keep_per_target: Option<usize>,
+    pub max_age: Option<u64>,
+    pub max_size: Option<u64>,
+}
+
+fn format_bytes(bytes: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, units[unit])
+    } else {
+        format!("{:.1} {}", size, units[unit])
+    }
+}
+
+pub async fn clean(options: CleanOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = Workspace::load().await?;
+
+    let stats = if options.all {
+        workspace.cache.clean_all().await?
+    } else {
+        // Options passed on the command line take precedence over the config
+        let mut policy = workspace.config.cache.prune.clone().unwrap_or_default();
+
+        if options.keep_per_target.is_some() {
+            policy.keep_per_target = options.keep_per_target;
+        }
+
+        if options.max_age.is_some() {
+            policy.max_age = options.max_age;
+        }
+
+        if options.max_size.is_some() {
+            policy.max_size = options.max_size;
+        }
+
+        if policy.is_empty() {
+            policy = PruneCacheConfig {
+                max_age: Some(DEFAULT_MAX_AGE),
+                ..PruneCacheConfig::default()
+            };
+        }
+
+        workspace.cache.prune(&policy).await?
+    };
+
+    println!(
+        "Cleaned {} hashes and {} logs, reclaiming {}",
+        color::symbol(&stats.hashes.to_string()),
+        color::symbol(&stats.logs.to_string()),
+        color::symbol(&format_bytes(stats.bytes))
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0 GB");
+    }
+}
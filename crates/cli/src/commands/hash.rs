@@ -0,0 +1,142 @@
+use moon_hasher::{diff_manifests, ManifestChange};
+use moon_logger::color;
+use moon_project::Target;
+use moon_workspace::{create_target_hasher, Workspace};
+use serde_json::Value;
+
+/// Generate the hash and manifest for a target, without running it.
+async fn generate_manifest(
+    workspace: &Workspace,
+    target_id: &str,
+) -> Result<(String, Value), Box<dyn std::error::Error>> {
+    let (project_id, task_id) = Target::parse(target_id)?.ids()?;
+    let project = workspace.projects.load(&project_id)?;
+    let task = project.get_runnable_task(&task_id, &workspace.root)?;
+    let hasher = create_target_hasher(workspace, &project, &task, &[]).await?;
+
+    Ok((hasher.to_hash(), serde_json::to_value(&hasher)?))
+}
+
+async fn load_manifest(
+    workspace: &Workspace,
+    hash: &str,
+) -> Result<Value, Box<dyn std::error::Error>> {
+    let path = workspace.cache.hashes_dir.join(format!("{}.json", hash));
+
+    if !path.exists() {
+        return Err(format!("No manifest found for hash {}.", hash).into());
+    }
+
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+pub async fn hash(target_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = Workspace::load().await?;
+    let (hash, manifest) = generate_manifest(&workspace, target_id).await?;
+
+    println!("{}", color::muted(&format!("Hash: {}", hash)));
+    println!("{}", serde_json::to_string_pretty(&manifest)?);
+
+    Ok(())
+}
+
+pub async fn hash_diff(
+    left: &str,
+    right: &Option<String>,
+    last: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = Workspace::load().await?;
+
+    let (left_label, left_manifest, right_label, right_manifest) = if last {
+        // Compare the hash of the last run against the current state
+        let state = workspace.cache.cache_run_target_state(left).await?;
+
+        if state.item.hash.is_empty() {
+            println!("No previous run found for {}", color::target(left));
+
+            return Ok(());
+        }
+
+        let (hash, manifest) = generate_manifest(&workspace, left).await?;
+
+        (
+            state.item.hash.clone(),
+            load_manifest(&workspace, &state.item.hash).await?,
+            hash,
+            manifest,
+        )
+    } else {
+        let right = right.as_ref().unwrap();
+
+        (
+            left.to_owned(),
+            load_manifest(&workspace, left).await?,
+            right.clone(),
+            load_manifest(&workspace, right).await?,
+        )
+    };
+
+    println!(
+        "Comparing {} to {}",
+        color::symbol(&left_label),
+        color::symbol(&right_label)
+    );
+    println!();
+
+    let diffs = diff_manifests(&left_manifest, &right_manifest);
+
+    if diffs.is_empty() {
+        println!("No differences found");
+
+        return Ok(());
+    }
+
+    let mut last_field = "";
+
+    for diff in &diffs {
+        if diff.field != last_field {
+            if !last_field.is_empty() {
+                println!();
+            }
+
+            println!("{}", color::id(&diff.field));
+            last_field = &diff.field;
+        }
+
+        let key = match &diff.key {
+            Some(key) => format!("{} ", key),
+            None => String::new(),
+        };
+
+        match &diff.change {
+            ManifestChange::Added(value) => {
+                println!(
+                    "  {} {}{}",
+                    color::success("+"),
+                    key,
+                    color::muted_light(value)
+                );
+            }
+            ManifestChange::Changed(before, after) => {
+                println!(
+                    "  {} {}{} {} {}",
+                    color::symbol("~"),
+                    key,
+                    color::muted_light(before),
+                    color::muted("->"),
+                    color::muted_light(after)
+                );
+            }
+            ManifestChange::Removed(value) => {
+                println!(
+                    "  {} {}{}",
+                    color::failure("-"),
+                    key,
+                    color::muted_light(value)
+                );
+            }
+        };
+    }
+
+    Ok(())
+}
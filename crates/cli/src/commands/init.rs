@@ -1,3 +1,4 @@
+use crate::commands::schema::{get_schemas_dir, write_schemas};
 use clap::ArgEnum;
 use dialoguer::{Confirm, Select};
 use moon_config::constants::{CONFIG_DIRNAME, CONFIG_PROJECT_FILENAME, CONFIG_WORKSPACE_FILENAME};
@@ -329,6 +330,9 @@ pub async fn init(dest: &str, options: InitOptions) -> Result<(), AnyError> {
     )
     .await?;
 
+    // Generate schemas referenced by the config files, so that editors can validate them
+    write_schemas(&get_schemas_dir(&moon_dir)).await?;
+
     // Append to ignore file
     let mut file = OpenOptions::new()
         .create(true)
@@ -0,0 +1,87 @@
+use moon_logger::color;
+use moon_project::Target;
+use moon_utils::time::chrono::{Local, TimeZone};
+use moon_workspace::{Node, Workspace};
+use std::path::Path;
+
+/// Map the provided name to an action node, either from a target ID,
+/// or an action label like `SetupToolchain` or `SyncProject(app)`.
+fn parse_action_node(name: &str) -> Result<Node, Box<dyn std::error::Error>> {
+    let node = match name {
+        "InstallNodeDeps" => Node::InstallNodeDeps,
+        "SetupToolchain" => Node::SetupToolchain,
+        _ => {
+            if let Some(id) = name
+                .strip_prefix("SyncProject(")
+                .and_then(|n| n.strip_suffix(')'))
+            {
+                Node::SyncProject(id.to_owned())
+            } else {
+                let target_id = name
+                    .strip_prefix("RunTarget(")
+                    .and_then(|n| n.strip_suffix(')'))
+                    .unwrap_or(name);
+
+                // Validate the format
+                Target::parse(target_id)?;
+
+                Node::RunTarget(target_id.to_owned())
+            }
+        }
+    };
+
+    Ok(node)
+}
+
+fn format_log_time(path: &Path) -> String {
+    let millis = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.parse::<i64>().ok())
+        .unwrap_or(0);
+
+    Local
+        .timestamp_millis(millis)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+pub async fn logs(name: &str, list: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let node = parse_action_node(name)?;
+    let workspace = Workspace::load().await?;
+    let logs = workspace.cache.get_run_logs(&node.log_name()).await?;
+
+    if logs.is_empty() {
+        println!("No logs found for {}", color::target(name));
+
+        return Ok(());
+    }
+
+    if list {
+        // Newest first
+        for log in logs.iter().rev() {
+            println!(
+                "{} {}",
+                format_log_time(log),
+                color::path(log.strip_prefix(&workspace.root).unwrap_or(log))
+            );
+        }
+
+        return Ok(());
+    }
+
+    let latest = logs.last().unwrap();
+
+    println!(
+        "{}",
+        color::muted(&format!(
+            "Latest of {} logs, ran at {}",
+            logs.len(),
+            format_log_time(latest)
+        ))
+    );
+    println!();
+    print!("{}", std::fs::read_to_string(latest)?);
+
+    Ok(())
+}
@@ -1,8 +1,13 @@
 pub mod bin;
 pub mod ci;
+pub mod clean;
+pub mod hash;
 pub mod init;
+pub mod logs;
 pub mod project;
 pub mod project_graph;
 pub mod run;
+pub mod schema;
 pub mod setup;
 pub mod teardown;
+pub mod validate;
@@ -1,15 +1,45 @@
 use console::Term;
 use itertools::Itertools;
 use moon_logger::color;
+use moon_project::DependencyScope;
 use moon_terminal::{ExtendedTerm, Label};
 use moon_utils::is_test_env;
 use moon_workspace::Workspace;
 
-pub async fn project(id: &str, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+pub struct ProjectOptions {
+    pub deps: bool,
+    pub dependents: bool,
+    pub depth: Option<usize>,
+    pub json: bool,
+}
+
+pub async fn project(id: &str, options: ProjectOptions) -> Result<(), Box<dyn std::error::Error>> {
     let workspace = Workspace::load().await?;
     let project = workspace.projects.load(id)?;
 
-    if json {
+    if options.deps || options.dependents {
+        let ids = if options.deps {
+            workspace
+                .projects
+                .get_transitive_dependencies_of(&project, options.depth)?
+        } else {
+            workspace
+                .projects
+                .get_transitive_dependents_of(&project, options.depth)?
+        };
+
+        if options.json {
+            println!("{}", serde_json::to_string_pretty(&ids)?);
+        } else {
+            for id in ids {
+                println!("{}", id);
+            }
+        }
+
+        return Ok(());
+    }
+
+    if options.json {
         println!("{}", project.to_json());
 
         return Ok(());
@@ -39,23 +69,33 @@ pub async fn project(id: &str, json: bool) -> Result<(), Box<dyn std::error::Err
             term.render_entry("Channel", &meta.channel)?;
         }
 
+        if !config.tags.is_empty() {
+            term.render_entry_list("Tags", &config.tags)?;
+        }
+
         let mut deps = vec![];
 
-        for dep_id in config.depends_on {
-            match workspace.projects.load(&dep_id) {
-                Ok(dep) => {
-                    deps.push(format!(
-                        "{} {}{}{}",
-                        color::id(&dep_id),
-                        color::muted_light("("),
-                        color::file(&dep.source),
-                        color::muted_light(")"),
-                    ));
-                }
-                Err(_) => {
-                    deps.push(color::id(&dep_id));
-                }
+        for dep_config in config.depends_on {
+            let dep_id = dep_config.id();
+            let mut dep_label = match workspace.projects.load(dep_id) {
+                Ok(dep) => format!(
+                    "{} {}{}{}",
+                    color::id(dep_id),
+                    color::muted_light("("),
+                    color::file(&dep.source),
+                    color::muted_light(")"),
+                ),
+                Err(_) => color::id(dep_id),
             };
+
+            if dep_config.scope() != DependencyScope::Production {
+                dep_label.push_str(&format!(
+                    " {}",
+                    color::muted_light(&format!("[{:?}]", dep_config.scope()).to_lowercase())
+                ));
+            }
+
+            deps.push(dep_label);
         }
 
         if !deps.is_empty() {
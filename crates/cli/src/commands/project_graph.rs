@@ -1,11 +1,27 @@
+mod server;
+
 use moon_workspace::Workspace;
 
-pub async fn project_graph(id: &Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn project_graph(
+    id: &Option<String>,
+    tag: &Option<String>,
+    serve: bool,
+    host: &str,
+    port: u16,
+) -> Result<(), Box<dyn std::error::Error>> {
     let workspace = Workspace::load().await?;
 
+    if serve {
+        return server::serve(workspace, host, port).await;
+    }
+
     // Force load projects into the graph
     if let Some(pid) = id {
         workspace.projects.load(pid)?;
+    } else if let Some(tag) = tag {
+        for pid in workspace.projects.get_ids_with_tag(tag)? {
+            workspace.projects.load(&pid)?;
+        }
     } else {
         for pid in workspace.projects.ids() {
             workspace.projects.load(&pid)?;
@@ -0,0 +1,300 @@
+use moon_logger::{color, debug, trace, warn};
+use moon_project::Target;
+use moon_workspace::{DepGraph, Workspace};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const LOG_TARGET: &str = "moon:project-graph:server";
+
+const INDEX_HTML: &str = include_str!("index.html");
+
+// Requests are only ever simple GETs, so cap how much we read
+const MAX_REQUEST_SIZE: usize = 8192;
+
+struct Response {
+    body: String,
+    content_type: &'static str,
+    status: &'static str,
+}
+
+impl Response {
+    fn html(body: &str) -> Self {
+        Response {
+            body: body.to_owned(),
+            content_type: "text/html; charset=utf-8",
+            status: "200 OK",
+        }
+    }
+
+    fn json(body: Value) -> Self {
+        Response {
+            body: body.to_string(),
+            content_type: "application/json",
+            status: "200 OK",
+        }
+    }
+
+    fn error(status: &'static str, message: String) -> Self {
+        Response {
+            body: json!({ "error": message }).to_string(),
+            content_type: "application/json",
+            status,
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.content_type,
+            self.body.len(),
+            self.body
+        )
+        .into_bytes()
+    }
+}
+
+pub async fn serve(workspace: Workspace, host: &str, port: u16) -> Result<(), Box<dyn Error>> {
+    let listener = TcpListener::bind((host, port)).await?;
+    let url = format!("http://{}", listener.local_addr()?);
+
+    debug!(
+        target: LOG_TARGET,
+        "Graph server listening on {}",
+        color::url(&url)
+    );
+
+    println!("Graph available at {}", color::url(&url));
+    println!("Press Ctrl+C to stop the server");
+
+    let workspace = Arc::new(workspace);
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let workspace = Arc::clone(&workspace);
+
+        tokio::spawn(async move {
+            if let Err(error) = handle_connection(stream, &workspace).await {
+                warn!(target: LOG_TARGET, "Failed to handle request: {}", error);
+            }
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, workspace: &Workspace) -> std::io::Result<()> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 1024];
+
+    // Read until the end of the headers, as we don't support request bodies
+    loop {
+        let size = stream.read(&mut chunk).await?;
+
+        if size == 0 {
+            break;
+        }
+
+        buffer.extend_from_slice(&chunk[0..size]);
+
+        if buffer.windows(4).any(|w| w == b"\r\n\r\n") || buffer.len() >= MAX_REQUEST_SIZE {
+            break;
+        }
+    }
+
+    let request = String::from_utf8_lossy(&buffer);
+    let response = match parse_request_path(&request) {
+        Some(path) => {
+            trace!(target: LOG_TARGET, "Handling request for {}", path);
+
+            route(path, workspace)
+        }
+        None => Response::error("400 Bad Request", String::from("Invalid request.")),
+    };
+
+    stream.write_all(&response.to_bytes()).await?;
+    stream.flush().await?;
+
+    Ok(())
+}
+
+/// Extract the path (without a query string) from the request line of a GET request.
+fn parse_request_path(request: &str) -> Option<&str> {
+    let mut parts = request.lines().next()?.split_whitespace();
+
+    if parts.next()? != "GET" {
+        return None;
+    }
+
+    let path = parts.next()?;
+
+    Some(match path.split_once('?') {
+        Some((path, _)) => path,
+        None => path,
+    })
+}
+
+/// Decode percent-encoded bytes (`%3A`, etc) within a request path segment.
+/// Returns `None` if an escape is malformed or the result is not valid UTF-8.
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(index + 1..index + 3)?).ok()?;
+
+            if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+fn route(path: &str, workspace: &Workspace) -> Response {
+    let result = match path {
+        "/" | "/index.html" => return Response::html(INDEX_HTML),
+        "/api/projects" => get_projects_data(workspace),
+        _ => match path.strip_prefix("/api/dep-graph/") {
+            Some(target_id) => match percent_decode(target_id) {
+                Some(target_id) => get_dep_graph_data(&target_id, workspace),
+                None => {
+                    return Response::error(
+                        "400 Bad Request",
+                        format!("Invalid target {}.", target_id),
+                    );
+                }
+            },
+            None => {
+                return Response::error("404 Not Found", format!("Unknown route {}.", path));
+            }
+        },
+    };
+
+    match result {
+        Ok(data) => Response::json(data),
+        Err(error) => Response::error("500 Internal Server Error", error.to_string()),
+    }
+}
+
+/// Load every project into the graph, and return their tasks, file groups,
+/// config, and resolved dependencies.
+fn get_projects_data(workspace: &Workspace) -> Result<Value, Box<dyn Error>> {
+    let mut projects = vec![];
+
+    for id in workspace.projects.ids() {
+        let project = workspace.projects.load(&id)?;
+        let mut data = serde_json::to_value(&project)?;
+        let mut deps = workspace.projects.get_dependencies_of(&project)?;
+
+        deps.sort();
+        data["dependsOn"] = json!(deps);
+
+        projects.push(data);
+    }
+
+    Ok(json!({ "projects": projects }))
+}
+
+/// Build a dependency graph for the target, and return its nodes and edges.
+fn get_dep_graph_data(target_id: &str, workspace: &Workspace) -> Result<Value, Box<dyn Error>> {
+    let target = Target::parse(target_id)?;
+    let mut dep_graph = DepGraph::default();
+
+    dep_graph.run_target(&target, &workspace.projects, None)?;
+
+    let graph = &dep_graph.graph;
+    let nodes = graph
+        .node_indices()
+        .map(|index| json!({ "id": index.index(), "label": graph[index].label() }))
+        .collect::<Vec<_>>();
+    let edges = graph
+        .edge_indices()
+        .filter_map(|edge| graph.edge_endpoints(edge))
+        .map(|(from, to)| json!({ "from": from.index(), "to": to.index() }))
+        .collect::<Vec<_>>();
+
+    Ok(json!({ "target": target_id, "nodes": nodes, "edges": edges }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse_request_path {
+        use super::*;
+
+        #[test]
+        fn returns_path() {
+            assert_eq!(
+                parse_request_path("GET /api/projects HTTP/1.1\r\nHost: localhost\r\n\r\n"),
+                Some("/api/projects")
+            );
+        }
+
+        #[test]
+        fn strips_query() {
+            assert_eq!(
+                parse_request_path("GET /?foo=bar HTTP/1.1\r\n\r\n"),
+                Some("/")
+            );
+        }
+
+        #[test]
+        fn supports_targets() {
+            assert_eq!(
+                parse_request_path("GET /api/dep-graph/app:build HTTP/1.1\r\n\r\n"),
+                Some("/api/dep-graph/app:build")
+            );
+        }
+
+        #[test]
+        fn ignores_other_methods() {
+            assert_eq!(parse_request_path("POST / HTTP/1.1\r\n\r\n"), None);
+        }
+
+        #[test]
+        fn ignores_empty() {
+            assert_eq!(parse_request_path(""), None);
+        }
+    }
+
+    mod percent_decode {
+        use super::*;
+
+        #[test]
+        fn returns_unencoded_as_is() {
+            assert_eq!(percent_decode("app:build"), Some(String::from("app:build")));
+        }
+
+        #[test]
+        fn decodes_special_chars() {
+            assert_eq!(
+                percent_decode("%23tag%3Abuild"),
+                Some(String::from("#tag:build"))
+            );
+            assert_eq!(
+                percent_decode("app%2Dname%3a%C3%A9"),
+                Some(String::from("app-name:é"))
+            );
+        }
+
+        #[test]
+        fn errors_for_malformed_escapes() {
+            assert_eq!(percent_decode("app%3"), None);
+            assert_eq!(percent_decode("app%zz"), None);
+            assert_eq!(percent_decode("app%+1"), None);
+            assert_eq!(percent_decode("%FF"), None);
+        }
+    }
+}
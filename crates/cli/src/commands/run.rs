@@ -1,15 +1,24 @@
 use clap::ArgEnum;
 use console::Term;
-use moon_logger::color;
-use moon_project::{Target, TouchedFilePaths};
+use moon_logger::{color, map_list};
+use moon_project::{Target, TargetProject, TouchedFilePaths};
+use moon_terminal::helpers::replace_style_tokens;
 use moon_terminal::ExtendedTerm;
-use moon_utils::time;
-use moon_workspace::{Action, ActionRunner, ActionStatus, DepGraph, Workspace, WorkspaceError};
+use moon_utils::{glob, time};
+use moon_workspace::{
+    Action, ActionRunner, ActionStatus, DepGraph, Node, Watcher, Workspace, WorkspaceError,
+};
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::string::ToString;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 use strum_macros::Display;
 
+// How long to wait for file changes to settle before re-running
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(250);
+
 #[derive(ArgEnum, Clone, Debug, Display)]
 pub enum RunStatus {
     Added,
@@ -29,10 +38,14 @@ impl Default for RunStatus {
 
 pub struct RunOptions {
     pub affected: bool,
+    pub concurrency: Option<usize>,
     pub dependents: bool,
+    pub ignore_dev_dependents: bool,
     pub status: RunStatus,
     pub passthrough: Vec<String>,
+    pub report: Option<PathBuf>,
     pub upstream: bool,
+    pub watch: bool,
 }
 
 async fn get_touched_files(
@@ -77,6 +90,7 @@ pub fn render_result_stats(
     let mut pass_count = 0;
     let mut fail_count = 0;
     let mut invalid_count = 0;
+    let mut skipped_count = 0;
 
     let filtered_results = if in_actions_context {
         results
@@ -92,7 +106,7 @@ pub fn render_result_stats(
 
     for result in filtered_results {
         match result.status {
-            ActionStatus::Cached => {
+            ActionStatus::Cached | ActionStatus::CachedFromRemote => {
                 cached_count += 1;
                 pass_count += 1;
             }
@@ -105,6 +119,9 @@ pub fn render_result_stats(
             ActionStatus::Invalid => {
                 invalid_count += 1;
             }
+            ActionStatus::SkippedFromFailure => {
+                skipped_count += 1;
+            }
             _ => {}
         }
     }
@@ -130,6 +147,10 @@ pub fn render_result_stats(
         counts_message.push(color::invalid(&format!("{} invalid", invalid_count)));
     }
 
+    if skipped_count > 0 {
+        counts_message.push(color::muted_light(&format!("{} skipped", skipped_count)));
+    }
+
     let term = Term::buffered_stdout();
     term.write_line("")?;
 
@@ -150,56 +171,125 @@ pub fn render_result_stats(
     Ok(())
 }
 
-pub async fn run(target_id: &str, options: RunOptions) -> Result<(), Box<dyn std::error::Error>> {
-    let target = Target::parse(target_id)?;
+pub async fn run(
+    target_ids: &[String],
+    options: RunOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut targets = vec![];
+    let mut target_globs = vec![];
+
+    for target_id in target_ids {
+        if glob::is_glob(target_id) {
+            target_globs.push(target_id);
+        } else {
+            targets.push(Target::parse(target_id)?);
+        }
+    }
+
     let workspace = Workspace::load().await?;
 
-    // Generate a dependency graph for all the targets that need to be ran
-    let mut dep_graph = DepGraph::default();
+    // Expand target globs into concrete targets
+    for target_glob in target_globs {
+        targets.extend(workspace.projects.expand_target_glob(target_glob)?);
+    }
 
-    if options.affected {
-        let touched_files =
-            get_touched_files(&workspace, &options.status, options.upstream).await?;
-        let inserted_count =
-            dep_graph.run_target(&target, &workspace.projects, Some(&touched_files))?;
+    let touched_files = if options.affected {
+        Some(get_touched_files(&workspace, &options.status, options.upstream).await?)
+    } else {
+        None
+    };
 
-        if inserted_count == 0 {
-            if matches!(options.status, RunStatus::All) {
-                println!(
-                    "Target {} not affected by touched files",
-                    color::target(target_id)
-                );
-            } else {
-                println!(
-                    "Target {} not affected by touched files (using status {})",
-                    color::target(target_id),
-                    color::symbol(&options.status.to_string().to_lowercase())
-                );
+    // Generate a dependency graph for all the targets that need to be ran,
+    // so that shared dependencies are only ran once
+    let mut dep_graph = DepGraph::default();
+    let mut primary_targets = HashSet::new();
+    let mut inserted_count = 0;
+
+    for target in &targets {
+        inserted_count +=
+            dep_graph.run_target(target, &workspace.projects, touched_files.as_ref())?;
+
+        // The all projects and tag scopes must be expanded so that each target streams its output
+        match &target.project {
+            TargetProject::All => {
+                for expanded_target in workspace.projects.expand_target_glob(&target.id)? {
+                    primary_targets.insert(expanded_target.id);
+                }
+            }
+            TargetProject::Tag(tag) => {
+                for project_id in workspace.projects.get_ids_with_tag(tag)? {
+                    primary_targets.insert(Target::format(&project_id, &target.task_id)?);
+                }
+            }
+            _ => {
+                primary_targets.insert(target.id.clone());
             }
-
-            return Ok(());
         }
-    } else {
-        let inserted_count = dep_graph.run_target(&target, &workspace.projects, None)?;
+    }
 
-        if inserted_count == 0 {
-            println!("No tasks found for target {}", color::target(target_id));
+    if inserted_count == 0 {
+        let target_list = map_list(target_ids, |id| color::target(id));
 
-            return Ok(());
+        if !options.affected {
+            println!("No tasks found for target {}", target_list);
+        } else if matches!(options.status, RunStatus::All) {
+            println!("Target {} not affected by touched files", target_list);
+        } else {
+            println!(
+                "Target {} not affected by touched files (using status {})",
+                target_list,
+                color::symbol(&options.status.to_string().to_lowercase())
+            );
+        }
+
+        if options.watch {
+            return watch(&targets, primary_targets, &options).await;
         }
+
+        return Ok(());
     }
 
     if options.dependents {
-        dep_graph.run_target_dependents(&target, &workspace.projects)?;
+        for target in &targets {
+            dep_graph.run_target_dependents(
+                target,
+                &workspace.projects,
+                options.ignore_dev_dependents,
+            )?;
+        }
+    }
+
+    let result = run_dep_graph(workspace, dep_graph, primary_targets.clone(), &options).await;
+
+    if options.watch {
+        if let Err(error) = result {
+            render_watch_error(error);
+        }
+
+        return watch(&targets, primary_targets, &options).await;
     }
 
+    result
+}
+
+async fn run_dep_graph(
+    workspace: Workspace,
+    dep_graph: DepGraph,
+    primary_targets: HashSet<String>,
+    options: &RunOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
     // Process all tasks in the graph
     let mut runner = ActionRunner::new(workspace);
 
+    if let Some(concurrency) = options.concurrency {
+        runner.set_concurrency(concurrency);
+    }
+
     let results = runner
         .bail_on_error()
-        .set_passthrough_args(options.passthrough)
-        .set_primary_target(target_id)
+        .set_passthrough_args(options.passthrough.clone())
+        .set_primary_targets(primary_targets)
+        .set_report_path(options.report.clone())
         .run(dep_graph)
         .await?;
 
@@ -208,3 +298,102 @@ pub async fn run(target_id: &str, options: RunOptions) -> Result<(), Box<dyn std
 
     Ok(())
 }
+
+// Failures should not stop watching, so render them without exiting
+fn render_watch_error(error: Box<dyn std::error::Error>) {
+    eprintln!(
+        "{} {}",
+        color::failure("Error:"),
+        replace_style_tokens(error.to_string().trim())
+    );
+}
+
+/// Watch the inputs of every target in the graph (including dependencies),
+/// and re-run the targets that are affected by changed files.
+async fn watch(
+    targets: &[Target],
+    primary_targets: HashSet<String>,
+    options: &RunOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = Workspace::load().await?;
+    let mut full_graph = DepGraph::default();
+
+    for target in targets {
+        full_graph.run_target(target, &workspace.projects, None)?;
+    }
+
+    let watched_targets = full_graph
+        .graph
+        .node_weights()
+        .filter_map(|node| match node {
+            Node::RunTarget(target_id) => Some(Target::parse(target_id)),
+            _ => None,
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut watcher = Watcher::new(WATCH_DEBOUNCE)?;
+
+    // Writing to the cache should never trigger a run
+    watcher.ignore(&workspace.cache.dir);
+
+    for target in &watched_targets {
+        let (project_id, task_id) = target.ids()?;
+        let project = workspace.projects.load(&project_id)?;
+
+        watcher.watch_task(project.get_task(&task_id)?)?;
+    }
+
+    // The action runner also listens for Ctrl+C while running,
+    // so track it separately to know when to stop watching
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let interrupted_listener = Arc::clone(&interrupted);
+
+    tokio::spawn(async move {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            interrupted_listener.store(true, Ordering::SeqCst);
+        }
+    });
+
+    println!("{}", color::muted("Watching for changes..."));
+
+    loop {
+        let changes = tokio::select! {
+            changes = watcher.next_changes() => match changes {
+                Some(changes) => changes,
+                None => break,
+            },
+            _ = tokio::signal::ctrl_c() => break,
+        };
+
+        // Reload so that config and project changes are picked up
+        let workspace = Workspace::load().await?;
+        let mut dep_graph = DepGraph::default();
+        let mut inserted_count = 0;
+
+        // Only targets affected by the changed files are inserted,
+        // while unaffected dependencies will be cached
+        for target in &watched_targets {
+            inserted_count += dep_graph.run_target(target, &workspace.projects, Some(&changes))?;
+        }
+
+        if inserted_count == 0 {
+            continue;
+        }
+
+        let result = run_dep_graph(workspace, dep_graph, primary_targets.clone(), options).await;
+
+        // Ctrl+C during a run only cancels the running actions,
+        // so stop watching instead of waiting for more changes
+        if interrupted.load(Ordering::SeqCst) {
+            break;
+        }
+
+        if let Err(error) = result {
+            render_watch_error(error);
+        }
+
+        println!("{}", color::muted("Watching for changes..."));
+    }
+
+    Ok(())
+}
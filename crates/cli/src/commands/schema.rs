@@ -0,0 +1,41 @@
+use moon_config::constants::CONFIG_DIRNAME;
+use moon_config::generate_json_schemas;
+use moon_logger::color;
+use moon_utils::fs;
+use moon_workspace::WorkspaceError;
+use std::env;
+use std::path::{Path, PathBuf};
+
+type AnyError = Box<dyn std::error::Error>;
+
+/// Return the `.moon/cache/schemas` directory within the provided `.moon` folder.
+pub fn get_schemas_dir(moon_dir: &Path) -> PathBuf {
+    moon_dir.join("cache").join("schemas")
+}
+
+/// Write JSON schemas for all configuration files into the provided directory,
+/// overwriting any schemas that already exist.
+pub async fn write_schemas(dest_dir: &Path) -> Result<(), AnyError> {
+    fs::create_dir_all(dest_dir).await?;
+
+    for (file_name, schema) in generate_json_schemas()? {
+        fs::write(&dest_dir.join(file_name), schema).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn schema_generate() -> Result<(), AnyError> {
+    // Configs may be invalid, so only locate the workspace instead of loading it
+    let moon_dir = match fs::find_upwards(CONFIG_DIRNAME, &env::current_dir()?) {
+        Some(dir) => dir,
+        None => return Err(WorkspaceError::MissingConfigDir.into()),
+    };
+    let schemas_dir = get_schemas_dir(&moon_dir);
+
+    write_schemas(&schemas_dir).await?;
+
+    println!("Generated schemas in {}", color::path(&schemas_dir));
+
+    Ok(())
+}
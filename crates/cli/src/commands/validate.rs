@@ -0,0 +1,190 @@
+use moon_config::constants::{CONFIG_DIRNAME, CONFIG_PROJECT_FILENAME, CONFIG_WORKSPACE_FILENAME};
+use moon_config::{
+    find_yaml_key_line, locate_figment_errors, GlobalProjectConfig, LocatedConfigError,
+    ProjectConfig, WorkspaceConfig,
+};
+use moon_logger::color;
+use moon_project::{validate_project_references, Project, ProjectError, Target};
+use moon_terminal::helpers::replace_style_tokens;
+use moon_utils::{fs, path};
+use moon_workspace::{Workspace, WorkspaceError};
+use std::env;
+use std::path::{Path, PathBuf};
+
+type AnyError = Box<dyn std::error::Error>;
+
+fn create_error(file: Option<PathBuf>, keys: &[&str], message: String) -> LocatedConfigError {
+    let line = file
+        .as_ref()
+        .and_then(|f| std::fs::read_to_string(f).ok())
+        .and_then(|content| {
+            find_yaml_key_line(
+                &content,
+                &keys.iter().map(|k| k.to_string()).collect::<Vec<_>>(),
+            )
+        });
+
+    LocatedConfigError {
+        file,
+        line,
+        message,
+    }
+}
+
+/// Determine which project config file (and key within it) caused a reference error.
+fn locate_reference_error(
+    error: ProjectError,
+    workspace_root: &Path,
+    project_sources: &[(String, String)],
+) -> LocatedConfigError {
+    let get_config_path = |project_id: &str| {
+        project_sources
+            .iter()
+            .find(|(id, _)| id == project_id)
+            .map(|(_, source)| {
+                workspace_root
+                    .join(path::normalize_separators(source))
+                    .join(CONFIG_PROJECT_FILENAME)
+            })
+            .filter(|config_path| config_path.exists())
+    };
+
+    match &error {
+        ProjectError::UnknownDependency(project_id, _) => create_error(
+            get_config_path(project_id),
+            &["dependsOn"],
+            error.to_string(),
+        ),
+        ProjectError::UnknownTaskDep(target_id, _) => match Target::parse(target_id) {
+            Ok(Target {
+                project_id: Some(project_id),
+                task_id,
+                ..
+            }) => create_error(
+                get_config_path(&project_id),
+                &["tasks", &task_id, "deps"],
+                error.to_string(),
+            ),
+            _ => create_error(None, &[], error.to_string()),
+        },
+        _ => create_error(None, &[], error.to_string()),
+    }
+}
+
+fn render_errors(errors: &[LocatedConfigError], workspace_root: &Path) {
+    for error in errors {
+        let location = match &error.file {
+            Some(file) => {
+                let file = file.strip_prefix(workspace_root).unwrap_or(file);
+
+                match error.line {
+                    Some(line) => format!("{}:{}", file.to_string_lossy(), line),
+                    None => file.to_string_lossy().to_string(),
+                }
+            }
+            None => String::from("workspace"),
+        };
+
+        eprintln!("{} {}", color::failure("✖"), color::file(&location));
+        eprintln!("  {}", replace_style_tokens(&error.message));
+        eprintln!();
+    }
+}
+
+pub async fn validate() -> Result<(), AnyError> {
+    // Configs may be invalid, so only locate the workspace instead of loading it
+    let moon_dir = match fs::find_upwards(CONFIG_DIRNAME, &env::current_dir()?) {
+        Some(dir) => dir,
+        None => return Err(WorkspaceError::MissingConfigDir.into()),
+    };
+    let workspace_root = moon_dir.parent().unwrap().to_path_buf();
+    let workspace_config_path = moon_dir.join(CONFIG_WORKSPACE_FILENAME);
+    let global_config_path = moon_dir.join(CONFIG_PROJECT_FILENAME);
+    let mut errors = vec![];
+
+    if !workspace_config_path.exists() {
+        return Err(WorkspaceError::MissingWorkspaceConfigFile.into());
+    }
+
+    if let Err(config_errors) = WorkspaceConfig::load(workspace_config_path) {
+        errors.extend(locate_figment_errors(config_errors));
+    }
+
+    let global_config = if global_config_path.exists() {
+        match GlobalProjectConfig::load(global_config_path) {
+            Ok(config) => Some(config),
+            Err(config_errors) => {
+                errors.extend(locate_figment_errors(config_errors));
+                None
+            }
+        }
+    } else {
+        Some(GlobalProjectConfig::default())
+    };
+
+    // Projects cannot be loaded without valid workspace configs
+    let mut project_count = 0;
+
+    if let Some(global_config) = global_config.filter(|_| errors.is_empty()) {
+        let workspace = Workspace::load().await?;
+        let ids = workspace.projects.ids();
+        let mut projects = vec![];
+        let mut project_sources = vec![];
+
+        project_count = ids.len();
+
+        for id in &ids {
+            let source = workspace.projects.get_source(id).unwrap().to_owned();
+            let config_path = workspace_root
+                .join(path::normalize_separators(&source))
+                .join(CONFIG_PROJECT_FILENAME);
+
+            project_sources.push((id.clone(), source.clone()));
+
+            // Load the config separately first, so that every error is reported
+            if config_path.exists() {
+                if let Err(config_errors) = ProjectConfig::load(&config_path) {
+                    errors.extend(locate_figment_errors(config_errors));
+                    continue;
+                }
+            }
+
+            match Project::new(id, &source, &workspace_root, &global_config) {
+                Ok(project) => projects.push(project),
+                Err(error) => {
+                    errors.push(create_error(
+                        Some(config_path).filter(|p| p.exists()),
+                        &[],
+                        error.to_string(),
+                    ));
+                }
+            }
+        }
+
+        for error in validate_project_references(&ids, &projects) {
+            errors.push(locate_reference_error(
+                error,
+                &workspace_root,
+                &project_sources,
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        render_errors(&errors, &workspace_root);
+
+        return Err(format!(
+            "Found {} configuration problem{}.",
+            errors.len(),
+            if errors.len() == 1 { "" } else { "s" }
+        )
+        .into());
+    }
+
+    println!(
+        "Validated workspace and {} projects, no problems found",
+        color::symbol(&project_count.to_string())
+    );
+
+    Ok(())
+}
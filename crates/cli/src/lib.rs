@@ -5,14 +5,19 @@ mod helpers;
 
 use crate::commands::bin::bin;
 use crate::commands::ci::{ci, CiOptions};
+use crate::commands::clean::{clean, CleanOptions};
+use crate::commands::hash::{hash, hash_diff};
 use crate::commands::init::{init, InitOptions};
-use crate::commands::project::project;
+use crate::commands::logs::logs;
+use crate::commands::project::{project, ProjectOptions};
 use crate::commands::project_graph::project_graph;
 use crate::commands::run::{run, RunOptions};
+use crate::commands::schema::schema_generate;
 use crate::commands::setup::setup;
 use crate::commands::teardown::teardown;
+use crate::commands::validate::validate;
 use crate::helpers::setup_colors;
-use app::{App, Commands};
+use app::{App, Commands, HashCommands, SchemaCommands};
 use clap::Parser;
 use console::Term;
 use enums::LogLevel;
@@ -58,18 +63,46 @@ pub async fn run_cli() {
         Commands::Bin { tool } => bin(tool).await,
         Commands::Ci {
             base,
+            concurrency,
             head,
+            ignore_dev_dependents,
             job,
             job_total,
+            plan,
+            report,
+            skip_failed_dependents,
         } => {
             ci(CiOptions {
                 base: base.clone(),
+                concurrency: *concurrency,
                 head: head.clone(),
+                ignore_dev_dependents: *ignore_dev_dependents,
                 job: *job,
                 job_total: *job_total,
+                plan: plan.clone(),
+                report: report.clone(),
+                skip_failed_dependents: *skip_failed_dependents,
             })
             .await
         }
+        Commands::Clean {
+            all,
+            keep_per_target,
+            max_age,
+            max_size,
+        } => {
+            clean(CleanOptions {
+                all: *all,
+                keep_per_target: *keep_per_target,
+                max_age: *max_age,
+                max_size: *max_size,
+            })
+            .await
+        }
+        Commands::Hash { target, command } => match command {
+            Some(HashCommands::Diff { left, right, last }) => hash_diff(left, right, *last).await,
+            None => hash(target.as_ref().unwrap()).await,
+        },
         Commands::Init {
             dest,
             force,
@@ -88,30 +121,66 @@ pub async fn run_cli() {
             )
             .await
         }
-        Commands::Project { id, json } => project(id, *json).await,
-        Commands::ProjectGraph { id } => project_graph(id).await,
+        Commands::Logs { target, list } => logs(target, *list).await,
+        Commands::Project {
+            id,
+            deps,
+            dependents,
+            depth,
+            json,
+        } => {
+            project(
+                id,
+                ProjectOptions {
+                    deps: *deps,
+                    dependents: *dependents,
+                    depth: *depth,
+                    json: *json,
+                },
+            )
+            .await
+        }
+        Commands::ProjectGraph {
+            id,
+            tag,
+            serve,
+            host,
+            port,
+        } => project_graph(id, tag, *serve, host, *port).await,
         Commands::Run {
-            target,
+            targets,
             affected,
+            concurrency,
             dependents,
+            ignore_dev_dependents,
             status,
             passthrough,
+            report,
             upstream,
+            watch,
         } => {
             run(
-                target,
+                targets,
                 RunOptions {
                     affected: *affected,
+                    concurrency: *concurrency,
                     dependents: *dependents,
+                    ignore_dev_dependents: *ignore_dev_dependents,
                     status: status.clone(),
                     passthrough: passthrough.clone(),
+                    report: report.clone(),
                     upstream: *upstream,
+                    watch: *watch,
                 },
             )
             .await
         }
+        Commands::Schema { command } => match command {
+            SchemaCommands::Generate => schema_generate().await,
+        },
         Commands::Setup => setup().await,
         Commands::Teardown => teardown().await,
+        Commands::Validate => validate().await,
     };
 
     if let Err(error) = result {
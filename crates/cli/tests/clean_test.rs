@@ -0,0 +1,63 @@
+use moon_utils::test::{create_fixtures_sandbox, create_moon_command_in, get_assert_output};
+use predicates::prelude::*;
+use std::fs;
+
+#[test]
+fn cleans_everything() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    create_moon_command_in(fixture.path())
+        .arg("run")
+        .arg("base:runFromProject")
+        .assert();
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("clean")
+        .arg("--all")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert.success();
+
+    assert!(predicate::str::contains("Cleaned 1 hashes").eval(&output));
+    assert!(!fixture
+        .path()
+        .join(".moon/cache/runs/base/runFromProject")
+        .exists());
+    assert_eq!(
+        fs::read_dir(fixture.path().join(".moon/cache/hashes"))
+            .unwrap()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn keeps_recent_hashes_per_target() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    // Passthrough args change the hash
+    for arg in ["a", "b"] {
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("base:runFromProject")
+            .arg("--")
+            .arg(arg)
+            .assert();
+    }
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("clean")
+        .arg("--keepPerTarget")
+        .arg("1")
+        .assert();
+
+    assert!(predicate::str::contains("Cleaned").eval(&get_assert_output(&assert)));
+
+    assert_eq!(
+        fs::read_dir(fixture.path().join(".moon/cache/hashes"))
+            .unwrap()
+            .count(),
+        1
+    );
+}
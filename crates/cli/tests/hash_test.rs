@@ -0,0 +1,158 @@
+use moon_utils::test::{create_fixtures_sandbox, create_moon_command_in, get_assert_output};
+use predicates::prelude::*;
+use std::fs;
+
+#[test]
+fn displays_manifest_of_target() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("hash")
+        .arg("base:runFromProject")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert.success();
+
+    assert!(predicate::str::contains("Hash: ").eval(&output));
+    assert!(predicate::str::contains("\"target\": \"base:runFromProject\"").eval(&output));
+    assert!(predicate::str::contains("\"command\": \"echo\"").eval(&output));
+
+    // Does not run the target
+    assert!(!fixture
+        .path()
+        .join(".moon/cache/runs/base/runFromProject")
+        .exists());
+}
+
+#[test]
+fn errors_for_unknown_task() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("hash")
+        .arg("base:unknown")
+        .assert();
+
+    assert.failure();
+}
+
+mod diff {
+    use super::*;
+
+    fn write_manifest(dir: &std::path::Path, hash: &str, content: &str) {
+        let hashes_dir = dir.join(".moon/cache/hashes");
+
+        fs::create_dir_all(&hashes_dir).unwrap();
+        fs::write(hashes_dir.join(format!("{}.json", hash)), content).unwrap();
+    }
+
+    #[test]
+    fn displays_differences() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        write_manifest(
+            fixture.path(),
+            "aaa",
+            r#"{ "command": "tsc", "deps": ["a:build"], "inputHashes": { "src/a.ts": "1", "src/b.ts": "2" } }"#,
+        );
+
+        write_manifest(
+            fixture.path(),
+            "bbb",
+            r#"{ "command": "tsc", "deps": ["b:build"], "inputHashes": { "src/b.ts": "3", "src/c.ts": "4" } }"#,
+        );
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("hash")
+            .arg("diff")
+            .arg("aaa")
+            .arg("bbb")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert.success();
+
+        assert!(predicate::str::contains("Comparing aaa to bbb").eval(&output));
+        assert!(predicate::str::contains("- a:build").eval(&output));
+        assert!(predicate::str::contains("+ b:build").eval(&output));
+        assert!(predicate::str::contains("- src/a.ts 1").eval(&output));
+        assert!(predicate::str::contains("~ src/b.ts 2 -> 3").eval(&output));
+        assert!(predicate::str::contains("+ src/c.ts 4").eval(&output));
+        assert!(!predicate::str::contains("command").eval(&output));
+    }
+
+    #[test]
+    fn displays_no_differences() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        write_manifest(fixture.path(), "aaa", r#"{ "command": "tsc" }"#);
+        write_manifest(fixture.path(), "bbb", r#"{ "command": "tsc" }"#);
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("hash")
+            .arg("diff")
+            .arg("aaa")
+            .arg("bbb")
+            .assert();
+
+        assert!(predicate::str::contains("No differences found").eval(&get_assert_output(&assert)));
+    }
+
+    #[test]
+    fn errors_for_missing_hash() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        write_manifest(fixture.path(), "aaa", r#"{ "command": "tsc" }"#);
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("hash")
+            .arg("diff")
+            .arg("aaa")
+            .arg("unknown")
+            .assert();
+
+        assert!(
+            predicate::str::contains("No manifest found for hash unknown")
+                .eval(&get_assert_output(&assert))
+        );
+    }
+
+    #[test]
+    fn compares_last_run_to_current() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("base:runFromProject")
+            .assert();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("hash")
+            .arg("diff")
+            .arg("base:runFromProject")
+            .arg("--last")
+            .assert();
+
+        assert.success();
+
+        assert!(predicate::str::contains("Comparing").eval(&get_assert_output(&assert)));
+    }
+
+    #[test]
+    fn handles_no_last_run() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("hash")
+            .arg("diff")
+            .arg("base:runFromProject")
+            .arg("--last")
+            .assert();
+
+        assert!(
+            predicate::str::contains("No previous run found for base:runFromProject")
+                .eval(&get_assert_output(&assert))
+        );
+    }
+}
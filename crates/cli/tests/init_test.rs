@@ -46,7 +46,7 @@ fn creates_workspace_config_from_template() {
         .assert();
 
     assert!(
-        predicate::str::contains("https://moonrepo.dev/schemas/workspace.json")
+        predicate::str::contains("$schema: './cache/schemas/workspace.json'")
             .eval(&fs::read_to_string(workspace_config).unwrap())
     );
 }
@@ -65,11 +65,34 @@ fn creates_project_config_from_template() {
         .assert();
 
     assert!(
-        predicate::str::contains("https://moonrepo.dev/schemas/global-project.json")
+        predicate::str::contains("$schema: './cache/schemas/global-project.json'")
             .eval(&fs::read_to_string(project_config).unwrap())
     );
 }
 
+#[test]
+#[serial]
+fn creates_schemas_for_config_headers() {
+    let fixture = create_fixtures_sandbox("init-sandbox");
+    let root = fixture.path();
+    let schemas_dir = root.join(".moon").join("cache").join("schemas");
+
+    create_moon_command_in(root)
+        .arg("init")
+        .arg("--yes")
+        .arg(&root)
+        .assert();
+
+    assert!(schemas_dir.join("global-project.json").exists());
+    assert!(schemas_dir.join("project.json").exists());
+    assert!(schemas_dir.join("workspace.json").exists());
+
+    assert!(predicate::str::contains(
+        "# yaml-language-server: $schema=./cache/schemas/workspace.json"
+    )
+    .eval(&fs::read_to_string(root.join(".moon").join("workspace.yml")).unwrap()));
+}
+
 #[test]
 #[serial]
 fn creates_gitignore_file() {
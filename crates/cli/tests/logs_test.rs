@@ -0,0 +1,123 @@
+use moon_utils::test::{
+    create_fixtures_sandbox, create_moon_command, create_moon_command_in, get_assert_output,
+};
+use predicates::prelude::*;
+
+#[test]
+fn displays_latest_log_of_target() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    create_moon_command_in(fixture.path())
+        .arg("run")
+        .arg("base:runFromProject")
+        .assert();
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("logs")
+        .arg("base:runFromProject")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert.success();
+
+    assert!(predicate::str::contains("Action: RunTarget(base:runFromProject)").eval(&output));
+    assert!(predicate::str::contains("Status: passed").eval(&output));
+    assert!(predicate::str::contains("Command: echo").eval(&output));
+    assert!(predicate::str::contains("MOON_PROJECT_ID=base").eval(&output));
+    assert!(predicate::str::contains("in project").eval(&output));
+}
+
+#[test]
+fn keeps_log_of_every_run() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    for _ in 0..2 {
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("base:runFromProject")
+            .assert();
+    }
+
+    let logs = std::fs::read_dir(fixture.path().join(".moon/cache/runs/base/runFromProject"))
+        .unwrap()
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .unwrap()
+                .path()
+                .extension()
+                .unwrap_or_default()
+                == "log"
+        })
+        .count();
+
+    assert_eq!(logs, 2);
+}
+
+#[test]
+fn writes_logs_for_other_actions() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    create_moon_command_in(fixture.path())
+        .arg("run")
+        .arg("base:runFromProject")
+        .assert();
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("logs")
+        .arg("SetupToolchain")
+        .assert();
+
+    assert!(predicate::str::contains("Action: SetupToolchain").eval(&get_assert_output(&assert)));
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("logs")
+        .arg("SyncProject(base)")
+        .assert();
+
+    assert!(predicate::str::contains("Action: SyncProject(base)").eval(&get_assert_output(&assert)));
+}
+
+#[test]
+fn lists_logs() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    create_moon_command_in(fixture.path())
+        .arg("run")
+        .arg("base:runFromProject")
+        .assert();
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("logs")
+        .arg("base:runFromProject")
+        .arg("--list")
+        .assert();
+
+    assert!(
+        predicate::str::contains(".moon/cache/runs/base/runFromProject/")
+            .eval(&get_assert_output(&assert))
+    );
+}
+
+#[test]
+fn handles_no_logs() {
+    let assert = create_moon_command("cases")
+        .arg("logs")
+        .arg("base:runFromProject")
+        .assert();
+
+    assert!(
+        predicate::str::contains("No logs found for base:runFromProject")
+            .eval(&get_assert_output(&assert))
+    );
+}
+
+#[test]
+fn errors_for_invalid_target() {
+    let assert = create_moon_command("cases")
+        .arg("logs")
+        .arg("base")
+        .assert();
+
+    assert.failure();
+}
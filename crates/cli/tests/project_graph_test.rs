@@ -36,3 +36,104 @@ fn single_project_no_dependencies() {
 
     assert_snapshot!(get_assert_output(&assert));
 }
+
+mod serve {
+    use moon_utils::test::get_fixtures_dir;
+    use predicates::prelude::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpStream;
+    use std::process::{Child, Command, Stdio};
+
+    fn spawn_server(fixture: &str) -> (Child, String) {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_moon"))
+            .args(["project-graph", "--serve", "--port", "0"])
+            .current_dir(get_fixtures_dir(fixture))
+            .env("MOON_CACHE", "off")
+            .env("MOON_TEST", "true")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        let mut reader = BufReader::new(child.stdout.take().unwrap());
+        let mut line = String::new();
+
+        reader.read_line(&mut line).unwrap();
+
+        let address = line
+            .split("http://")
+            .nth(1)
+            .expect("Server address not printed")
+            .trim()
+            .to_owned();
+
+        (child, address)
+    }
+
+    fn request(address: &str, path: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        let mut response = String::new();
+
+        write!(stream, "GET {} HTTP/1.1\r\nHost: {}\r\n\r\n", path, address).unwrap();
+        stream.read_to_string(&mut response).unwrap();
+
+        response
+    }
+
+    #[test]
+    fn serves_index_page() {
+        let (mut child, address) = spawn_server("projects");
+        let response = request(&address, "/");
+
+        child.kill().unwrap();
+
+        assert!(predicate::str::contains("200 OK").eval(&response));
+        assert!(predicate::str::contains("text/html").eval(&response));
+    }
+
+    #[test]
+    fn serves_projects_data() {
+        let (mut child, address) = spawn_server("projects");
+        let response = request(&address, "/api/projects");
+
+        child.kill().unwrap();
+
+        assert!(predicate::str::contains("200 OK").eval(&response));
+        assert!(predicate::str::contains("\"id\":\"foo\"").eval(&response));
+        assert!(predicate::str::contains("\"dependsOn\":[\"bar\",\"baz\"]").eval(&response));
+        assert!(predicate::str::contains("\"fileGroups\"").eval(&response));
+    }
+
+    #[test]
+    fn serves_dep_graph_data() {
+        let (mut child, address) = spawn_server("cases");
+        let response = request(&address, "/api/dep-graph/base:runFromProject");
+
+        child.kill().unwrap();
+
+        assert!(predicate::str::contains("200 OK").eval(&response));
+        assert!(predicate::str::contains("RunTarget(base:runFromProject)").eval(&response));
+        assert!(predicate::str::contains("SetupToolchain").eval(&response));
+    }
+
+    #[test]
+    fn errors_for_invalid_target() {
+        let (mut child, address) = spawn_server("cases");
+        let response = request(&address, "/api/dep-graph/base:unknown");
+
+        child.kill().unwrap();
+
+        assert!(predicate::str::contains("500 Internal Server Error").eval(&response));
+        assert!(predicate::str::contains("\"error\"").eval(&response));
+    }
+
+    #[test]
+    fn errors_for_unknown_route() {
+        let (mut child, address) = spawn_server("projects");
+        let response = request(&address, "/unknown");
+
+        child.kill().unwrap();
+
+        assert!(predicate::str::contains("404 Not Found").eval(&response));
+    }
+}
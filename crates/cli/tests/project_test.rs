@@ -1,5 +1,11 @@
 use insta::assert_snapshot;
-use moon_utils::test::{create_moon_command, get_assert_output, get_assert_stderr_output};
+use moon_utils::test::{
+    create_fixtures_sandbox, create_moon_command, create_moon_command_in, get_assert_output,
+    get_assert_stderr_output,
+};
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
 
 #[test]
 fn unknown_project() {
@@ -75,3 +81,73 @@ fn with_tasks() {
 
     assert_snapshot!(get_assert_output(&assert));
 }
+
+mod relations {
+    use super::*;
+
+    // foo -> bar -> basic -> noConfig
+    fn link_bar_to_basic(root: &Path) {
+        fs::write(root.join("deps/bar/project.yml"), "dependsOn:\n  - basic\n").unwrap();
+    }
+
+    #[test]
+    fn lists_transitive_deps() {
+        let fixture = create_fixtures_sandbox("projects");
+        link_bar_to_basic(fixture.path());
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("project")
+            .arg("foo")
+            .arg("--deps")
+            .assert();
+
+        assert.success().stdout("bar\nbasic\nbaz\nnoConfig\n");
+    }
+
+    #[test]
+    fn lists_deps_up_to_depth() {
+        let fixture = create_fixtures_sandbox("projects");
+        link_bar_to_basic(fixture.path());
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("project")
+            .arg("foo")
+            .arg("--deps")
+            .arg("--depth")
+            .arg("2")
+            .assert();
+
+        assert.success().stdout("bar\nbasic\nbaz\n");
+    }
+
+    #[test]
+    fn lists_transitive_dependents_as_json() {
+        let fixture = create_fixtures_sandbox("projects");
+        link_bar_to_basic(fixture.path());
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("project")
+            .arg("noConfig")
+            .arg("--dependents")
+            .arg("--json")
+            .assert();
+
+        let output = get_assert_output(&assert);
+        let ids: Vec<String> = serde_json::from_str(output.trim()).unwrap();
+
+        assert_eq!(ids, vec!["bar", "basic", "foo"]);
+    }
+
+    #[test]
+    fn errors_for_depth_without_relation() {
+        let assert = create_moon_command("projects")
+            .arg("project")
+            .arg("foo")
+            .arg("--depth")
+            .arg("1")
+            .assert();
+
+        assert.failure();
+        assert!(predicate::str::contains("--deps").eval(&get_assert_stderr_output(&assert)));
+    }
+}
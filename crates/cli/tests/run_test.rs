@@ -306,6 +306,26 @@ mod dependencies {
 
         assert_snapshot!(get_assert_output(&assert));
     }
+
+    #[test]
+    fn runs_the_graph_in_order_with_concurrency() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("depsA:dependencyOrder")
+            .arg("--concurrency")
+            .arg("1")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        let c = output.find("deps=c").unwrap();
+        let b = output.find("deps=b").unwrap();
+        let a = output.find("deps=a").unwrap();
+
+        assert!(c < b && b < a);
+        assert!(predicate::str::contains("Tasks: 3 completed").eval(&output));
+    }
 }
 
 mod target_scopes {
@@ -386,6 +406,184 @@ mod target_scopes {
     }
 }
 
+mod multiple_targets {
+    use super::*;
+
+    #[test]
+    fn runs_all_targets() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("targetScopeA:all")
+            .arg("targetScopeB:all")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("targetScopeA:all").eval(&output));
+        assert!(predicate::str::contains("targetScopeB:all").eval(&output));
+        assert!(!predicate::str::contains("targetScopeC:all").eval(&output));
+        assert!(predicate::str::contains("Tasks: 2 completed").eval(&output));
+    }
+
+    #[test]
+    fn runs_shared_deps_once() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("targetScopeA:deps")
+            .arg("depsA:standard")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("targetScopeA:deps").eval(&output));
+        assert!(predicate::str::contains("Tasks: 4 completed").eval(&output));
+    }
+
+    #[test]
+    fn supports_project_globs() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("targetScope*:all")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("targetScopeA:all").eval(&output));
+        assert!(predicate::str::contains("targetScopeB:all").eval(&output));
+        assert!(predicate::str::contains("targetScopeC:all").eval(&output));
+        assert!(predicate::str::contains("Tasks: 3 completed").eval(&output));
+    }
+
+    #[test]
+    fn supports_task_globs() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("targetScopeB:self*")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("targetScopeB:self").eval(&output));
+        assert!(predicate::str::contains("targetScopeB:selfOther").eval(&output));
+        assert!(predicate::str::contains("Tasks: 2 completed").eval(&output));
+    }
+
+    #[test]
+    fn logs_when_no_glob_matches() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("unknown*:all")
+            .assert();
+
+        assert!(
+            predicate::str::contains("No tasks found for target unknown*:all")
+                .eval(&get_assert_output(&assert))
+        );
+    }
+}
+
+#[cfg(not(windows))]
+mod report {
+    use super::*;
+
+    #[test]
+    fn writes_json_report() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("base:runFromProject")
+            .arg("--report")
+            .arg("report.json")
+            .assert();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&read_to_string(fixture.path().join("report.json")).unwrap())
+                .unwrap();
+        let action = report["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|a| a["label"] == "RunTarget(base:runFromProject)")
+            .unwrap();
+
+        assert_eq!(action["status"], "passed");
+        assert_eq!(action["cached"], false);
+        assert!(action["hash"].is_string());
+        assert_eq!(action["attempts"][0]["exitCode"], 0);
+        assert!(predicate::str::contains("in project")
+            .eval(action["attempts"][0]["stdout"].as_str().unwrap()));
+    }
+
+    #[test]
+    fn writes_cached_state() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("base:runFromProject")
+            .assert();
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("base:runFromProject")
+            .arg("--report")
+            .arg("report.json")
+            .assert();
+
+        let report = read_to_string(fixture.path().join("report.json")).unwrap();
+
+        assert!(predicate::str::contains("\"status\": \"cached\"").eval(&report));
+        assert!(predicate::str::contains("\"cached\": true").eval(&report));
+    }
+
+    #[test]
+    fn writes_report_on_failure() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("system:exitNonZero")
+            .arg("--report")
+            .arg("report.json")
+            .assert()
+            .failure();
+
+        let report = read_to_string(fixture.path().join("report.json")).unwrap();
+
+        assert!(predicate::str::contains("RunTarget(system:exitNonZero)").eval(&report));
+        assert!(predicate::str::contains("\"status\": \"failed\"").eval(&report));
+    }
+
+    #[test]
+    fn writes_junit_report() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("system:exitNonZero")
+            .arg("--report")
+            .arg("reports/junit.xml")
+            .assert()
+            .failure();
+
+        let report = read_to_string(fixture.path().join("reports/junit.xml")).unwrap();
+
+        assert!(predicate::str::starts_with("<?xml").eval(&report));
+        assert!(
+            predicate::str::contains("<testcase name=\"RunTarget(system:exitNonZero)\"")
+                .eval(&report)
+        );
+        assert!(predicate::str::contains("<failure message=").eval(&report));
+    }
+}
+
 mod node {
     use super::*;
 
@@ -821,6 +1019,82 @@ mod node {
             );
         }
 
+        #[test]
+        fn syncs_scoped_dependencies_to_package_json() {
+            let fixture = create_fixtures_sandbox("cases");
+
+            append_workspace_config(
+                &fixture.path().join(".moon/workspace.yml"),
+                "  syncProjectWorkspaceDependencies: true",
+            );
+
+            fs::write(
+                fixture.path().join("depends-on/project.yml"),
+                r#"
+dependsOn:
+  - id: depsA
+    scope: development
+  - id: depsB
+    scope: peer
+
+tasks:
+  standard:
+    command: node
+    args: -e "'noop'"
+"#,
+            )
+            .unwrap();
+
+            create_moon_command_in(fixture.path())
+                .arg("run")
+                .arg("dependsOn:standard")
+                .assert();
+
+            let package: serde_json::Value = serde_json::from_str(
+                &read_to_string(fixture.path().join("depends-on/package.json")).unwrap(),
+            )
+            .unwrap();
+
+            assert!(package["dependencies"]["test-cases-deps-a"].is_null());
+            assert!(package["dependencies"]["test-cases-deps-b"].is_null());
+            assert!(package["devDependencies"]["test-cases-deps-a"].is_string());
+            assert!(package["peerDependencies"]["test-cases-deps-b"].is_string());
+        }
+
+        #[test]
+        fn doesnt_sync_build_dependencies_to_package_json() {
+            let fixture = create_fixtures_sandbox("cases");
+
+            append_workspace_config(
+                &fixture.path().join(".moon/workspace.yml"),
+                "  syncProjectWorkspaceDependencies: true",
+            );
+
+            fs::write(
+                fixture.path().join("depends-on/project.yml"),
+                r#"
+dependsOn:
+  - id: depsA
+    scope: build
+
+tasks:
+  standard:
+    command: node
+    args: -e "'noop'"
+"#,
+            )
+            .unwrap();
+
+            create_moon_command_in(fixture.path())
+                .arg("run")
+                .arg("dependsOn:standard")
+                .assert();
+
+            let content = read_to_string(fixture.path().join("depends-on/package.json")).unwrap();
+
+            assert!(!predicate::str::contains("test-cases-deps-a").eval(&content));
+        }
+
         #[test]
         fn syncs_as_reference_to_tsconfig_json() {
             let fixture = create_fixtures_sandbox("cases");
@@ -1050,6 +1324,53 @@ mod system {
         assert_snapshot!(get_assert_output(&assert));
     }
 
+    #[tokio::test]
+    async fn hashes_node_configs() {
+        use moon_cache::{CacheItem, RunTargetState};
+
+        let fixture = create_fixtures_sandbox("cases");
+
+        fs::write(
+            fixture.path().join("system/tsconfig.json"),
+            r#"{ "compilerOptions": { "module": "es2022", "target": "es2020" } }"#,
+        )
+        .unwrap();
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("system:echo")
+            .assert();
+
+        let state = CacheItem::load(
+            fixture
+                .path()
+                .join(".moon/cache/runs/system/echo/lastRunState.json"),
+            RunTargetState::default(),
+            0,
+        )
+        .await
+        .unwrap();
+
+        let manifest: serde_json::Value = serde_json::from_str(
+            &read_to_string(
+                fixture
+                    .path()
+                    .join(format!(".moon/cache/hashes/{}.json", state.item.hash)),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(manifest["command"], "echo");
+        assert_eq!(manifest["args"], serde_json::json!(["hello"]));
+        assert_eq!(manifest["packageDependencies"], serde_json::json!({}));
+        assert_eq!(
+            manifest["tsconfigCompilerOptions"],
+            serde_json::json!({ "module": "Es2022", "target": "Es2020" })
+        );
+        assert_eq!(manifest["toolVersions"], serde_json::json!({}));
+    }
+
     #[test]
     fn handles_ls() {
         let fixture = create_fixtures_sandbox("cases");
@@ -1178,6 +1499,131 @@ mod system {
 
         assert_snapshot!(get_assert_output(&assert));
     }
+
+    #[test]
+    fn terminates_on_timeout() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("system:timeout")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert.failure();
+        assert!(predicate::str::contains("Process bash timed out after 1s.").eval(&output));
+        assert!(!predicate::str::contains("This should not appear!").eval(&output));
+    }
+
+    #[test]
+    fn keeps_partial_output_on_timeout() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("system:timeout")
+            .assert()
+            .failure();
+
+        let log = fs::read_dir(fixture.path().join(".moon/cache/runs/system/timeout"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.extension().unwrap_or_default() == "log")
+            .unwrap();
+
+        assert!(predicate::str::contains("before").eval(&read_to_string(log).unwrap()));
+    }
+
+    #[test]
+    fn terminates_child_processes_on_timeout() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("system:timeoutWithChildren")
+            .assert();
+
+        assert.failure();
+
+        // The subshell would create the file if it was orphaned
+        std::thread::sleep(std::time::Duration::from_secs(4));
+
+        assert!(!fixture.path().join("system/orphan.txt").exists());
+    }
+
+    mod persistent {
+        use super::*;
+
+        #[test]
+        fn runs_dependents_once_ready() {
+            let fixture = create_fixtures_sandbox("cases");
+
+            let assert = create_moon_command_in(fixture.path())
+                .arg("run")
+                .arg("system:dependsOnPersistent")
+                .assert();
+            let output = get_assert_output(&assert);
+
+            assert.success();
+            assert!(predicate::str::contains("server ready").eval(&output));
+            assert!(predicate::str::contains("(ready)").eval(&output));
+            assert!(predicate::str::contains("stdout").eval(&output));
+
+            // Terminated once the run has ended
+            assert!(!predicate::str::contains("This should not appear!").eval(&output));
+        }
+
+        #[test]
+        fn errors_if_not_ready_in_time() {
+            let fixture = create_fixtures_sandbox("cases");
+
+            let assert = create_moon_command_in(fixture.path())
+                .arg("run")
+                .arg("system:persistentNeverReady")
+                .assert();
+            let output = get_assert_output(&assert);
+
+            assert.failure();
+            assert!(predicate::str::contains(
+                "Persistent target system:persistentNeverReady was not ready after 1s."
+            )
+            .eval(&output));
+        }
+
+        #[test]
+        fn errors_if_exits_before_ready() {
+            let fixture = create_fixtures_sandbox("cases");
+
+            let assert = create_moon_command_in(fixture.path())
+                .arg("run")
+                .arg("system:persistentExits")
+                .assert();
+            let output = get_assert_output(&assert);
+
+            assert.failure();
+            assert!(predicate::str::contains(
+                "Persistent target system:persistentExits exited before it was ready."
+            )
+            .eval(&output));
+        }
+
+        #[test]
+        fn is_never_cached() {
+            let fixture = create_fixtures_sandbox("cases");
+
+            create_moon_command_in(fixture.path())
+                .arg("run")
+                .arg("system:dependsOnPersistent")
+                .assert();
+
+            let assert = create_moon_command_in(fixture.path())
+                .arg("run")
+                .arg("system:dependsOnPersistent")
+                .assert();
+
+            assert!(predicate::str::contains("server ready").eval(&get_assert_output(&assert)));
+        }
+    }
 }
 
 #[cfg(windows)]
@@ -1471,4 +1917,51 @@ mod outputs {
             .join("esm/two.js")
             .exists());
     }
+
+    #[tokio::test]
+    async fn hydrates_missing_outputs_from_cache() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("outputs:generateFileAndFolder")
+            .assert();
+
+        let project_root = fixture.path().join("outputs");
+
+        fs::remove_file(project_root.join("lib/one.js")).unwrap();
+        fs::remove_dir_all(project_root.join("esm")).unwrap();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("outputs:generateFileAndFolder")
+            .assert();
+
+        assert!(predicate::str::contains("(cached)").eval(&get_assert_output(&assert)));
+        assert!(project_root.join("lib/one.js").exists());
+        assert!(project_root.join("esm/two.js").exists());
+    }
+
+    #[tokio::test]
+    async fn reruns_when_outputs_missing_from_cache() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("outputs:generateFile")
+            .assert();
+
+        let hash = extract_hash_from_run(fixture.path(), "outputs:generateFile").await;
+
+        fs::remove_dir_all(fixture.path().join(".moon/cache/out").join(&hash)).unwrap();
+        fs::remove_file(fixture.path().join("outputs/lib/one.js")).unwrap();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("outputs:generateFile")
+            .assert();
+
+        assert!(!predicate::str::contains("(cached)").eval(&get_assert_output(&assert)));
+        assert!(fixture.path().join("outputs/lib/one.js").exists());
+    }
 }
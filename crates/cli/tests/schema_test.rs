@@ -0,0 +1,66 @@
+use moon_utils::test::{create_fixtures_sandbox, create_moon_command_in, get_assert_output};
+use predicates::prelude::*;
+use std::fs;
+
+mod generate {
+    use super::*;
+
+    #[test]
+    fn writes_schemas_to_cache_dir() {
+        let fixture = create_fixtures_sandbox("cases");
+        let schemas_dir = fixture.path().join(".moon/cache/schemas");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("schema")
+            .arg("generate")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert.success();
+
+        assert!(predicate::str::contains("Generated schemas in").eval(&output));
+
+        for (file_name, title) in [
+            ("global-project.json", "GlobalProjectConfig"),
+            ("project.json", "ProjectConfig"),
+            ("workspace.json", "WorkspaceConfig"),
+        ] {
+            let content = fs::read_to_string(schemas_dir.join(file_name)).unwrap();
+
+            assert!(predicate::str::contains(format!("\"title\": \"{}\"", title)).eval(&content));
+        }
+    }
+
+    #[test]
+    fn overwrites_existing_schemas() {
+        let fixture = create_fixtures_sandbox("cases");
+        let schema_path = fixture.path().join(".moon/cache/schemas/workspace.json");
+
+        fs::create_dir_all(schema_path.parent().unwrap()).unwrap();
+        fs::write(&schema_path, "{}").unwrap();
+
+        create_moon_command_in(fixture.path())
+            .arg("schema")
+            .arg("generate")
+            .assert()
+            .success();
+
+        assert_ne!(fs::read_to_string(schema_path).unwrap(), "{}");
+    }
+
+    #[test]
+    fn works_from_a_project_dir() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(&fixture.path().join("base"))
+            .arg("schema")
+            .arg("generate")
+            .assert()
+            .success();
+
+        assert!(fixture
+            .path()
+            .join(".moon/cache/schemas/workspace.json")
+            .exists());
+    }
+}
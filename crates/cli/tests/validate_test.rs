@@ -0,0 +1,115 @@
+use moon_utils::test::{
+    create_fixtures_sandbox, create_moon_command, create_moon_command_in, get_assert_output,
+};
+use predicates::prelude::*;
+use std::fs;
+
+#[test]
+fn passes_for_valid_configs() {
+    let fixture = create_fixtures_sandbox("validate");
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("validate")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert.success();
+
+    assert!(predicate::str::contains("Validated workspace and 2 projects").eval(&output));
+}
+
+#[test]
+fn errors_for_invalid_workspace_config() {
+    let assert = create_moon_command("config-invalid-workspace")
+        .arg("validate")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert.failure();
+
+    assert!(predicate::str::contains(".moon/workspace.yml").eval(&output));
+    assert!(predicate::str::contains("Found 1 configuration problem.").eval(&output));
+}
+
+#[test]
+fn errors_for_invalid_project_config() {
+    let assert = create_moon_command("config-invalid-project")
+        .arg("validate")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert.failure();
+
+    assert!(predicate::str::contains("test/project.yml").eval(&output));
+    assert!(predicate::str::contains("Found 1 configuration problem.").eval(&output));
+}
+
+#[test]
+fn reports_errors_from_all_projects() {
+    let fixture = create_fixtures_sandbox("validate");
+
+    fs::write(fixture.path().join("a/project.yml"), "fileGroups: 123").unwrap();
+    fs::write(fixture.path().join("b/project.yml"), "dependsOn: true").unwrap();
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("validate")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert.failure();
+
+    assert!(predicate::str::contains("a/project.yml:1").eval(&output));
+    assert!(predicate::str::contains("b/project.yml:1").eval(&output));
+    assert!(predicate::str::contains("Found 2 configuration problems.").eval(&output));
+}
+
+#[test]
+fn errors_for_unknown_references() {
+    let fixture = create_fixtures_sandbox("validate");
+
+    fs::write(
+        fixture.path().join("a/project.yml"),
+        "dependsOn:\n  - b\n  - c\n\ntasks:\n  build:\n    command: echo\n    type: system\n    deps:\n      - 'b:test'\n",
+    )
+    .unwrap();
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("validate")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert.failure();
+
+    assert!(predicate::str::contains("a/project.yml:1").eval(&output));
+    assert!(predicate::str::contains("Project a depends on unknown project c.").eval(&output));
+    assert!(predicate::str::contains("a/project.yml:9").eval(&output));
+    assert!(
+        predicate::str::contains("Task a:build depends on unknown target b:test.").eval(&output)
+    );
+}
+
+#[test]
+fn errors_for_dependency_cycles() {
+    let fixture = create_fixtures_sandbox("validate");
+
+    fs::write(
+        fixture.path().join("b/project.yml"),
+        "dependsOn:\n  - a\n\ntasks:\n  build:\n    command: echo\n    type: system\n    deps:\n      - 'a:build'\n",
+    )
+    .unwrap();
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("validate")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert.failure();
+
+    assert!(
+        predicate::str::contains("Dependency cycle detected between projects a, b.").eval(&output)
+    );
+    assert!(
+        predicate::str::contains("Dependency cycle detected between tasks a:build, b:build.")
+            .eval(&output)
+    );
+}
@@ -1,6 +1,8 @@
-use figment::{Error as FigmentError, Figment};
+use figment::{Error as FigmentError, Figment, Source};
 use serde_json::Value;
 use std::borrow::Cow;
+use std::fs;
+use std::path::PathBuf;
 use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
 
 pub fn create_validation_error(code: &'static str, path: &str, message: String) -> ValidationError {
@@ -72,3 +74,178 @@ pub fn map_validation_errors_to_figment_errors(
     errors.extend(nested_errors);
     errors
 }
+
+fn get_yaml_key(line: &str) -> Option<&str> {
+    let (key, _) = line.split_once(':')?;
+
+    Some(key.trim().trim_matches(|c| c == '\'' || c == '"'))
+}
+
+/// Find the line number (1-based) of a nested key within YAML content, by walking
+/// the indentation of each mapping. When a key cannot be found, or refers to a list
+/// item, the line of the closest parent key is returned.
+pub fn find_yaml_key_line(content: &str, path: &[String]) -> Option<usize> {
+    let lines = content.lines().collect::<Vec<_>>();
+    let mut found_line = None;
+    let mut parent_indent: Option<usize> = None;
+    let mut start = 0;
+
+    for key in path {
+        let mut block_indent = None;
+        let mut matched = None;
+
+        for (index, line) in lines.iter().enumerate().skip(start) {
+            let trimmed = line.trim_start();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let indent = line.len() - trimmed.len();
+
+            // Left the parent mapping
+            if parent_indent.map(|p| indent <= p).unwrap_or(false) {
+                break;
+            }
+
+            // Only match keys at the same depth, not within nested mappings
+            if *block_indent.get_or_insert(indent) != indent {
+                continue;
+            }
+
+            if get_yaml_key(trimmed) == Some(key.as_str()) {
+                matched = Some((index, indent));
+                break;
+            }
+        }
+
+        match matched {
+            Some((index, indent)) => {
+                found_line = Some(index + 1);
+                parent_indent = Some(indent);
+                start = index + 1;
+            }
+            None => break,
+        }
+    }
+
+    found_line
+}
+
+/// A configuration error, with the file and line of the offending key when known.
+#[derive(Debug)]
+pub struct LocatedConfigError {
+    pub file: Option<PathBuf>,
+
+    /// Line number (1-based) of the key within the file.
+    pub line: Option<usize>,
+
+    pub message: String,
+}
+
+/// Flatten figment errors, and locate the config file and line that each error
+/// originated from, if they can be determined.
+pub fn locate_figment_errors(errors: Vec<FigmentError>) -> Vec<LocatedConfigError> {
+    let mut list = vec![];
+
+    for error in errors {
+        for nested_error in error {
+            let file = match nested_error
+                .metadata
+                .as_ref()
+                .and_then(|m| m.source.as_ref())
+            {
+                Some(Source::File(file)) => Some(file.clone()),
+                _ => None,
+            };
+
+            let line = file
+                .as_ref()
+                .and_then(|f| fs::read_to_string(f).ok())
+                .and_then(|content| find_yaml_key_line(&content, &nested_error.path));
+
+            list.push(LocatedConfigError {
+                file,
+                line,
+                message: nested_error.to_string(),
+            });
+        }
+    }
+
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use moon_utils::string_vec;
+
+    mod find_yaml_key_line {
+        use super::*;
+
+        const CONTENT: &str = r#"# Comment
+type: 'library'
+
+fileGroups:
+  sources:
+    - 'src/**/*'
+
+tasks:
+  lint:
+    command: 'eslint'
+  build:
+    command: 'tsc'
+    options:
+      # Nested comment
+      cache: true
+"#;
+
+        #[test]
+        fn finds_root_keys() {
+            assert_eq!(find_yaml_key_line(CONTENT, &string_vec!["type"]), Some(2));
+            assert_eq!(find_yaml_key_line(CONTENT, &string_vec!["tasks"]), Some(8));
+        }
+
+        #[test]
+        fn finds_nested_keys() {
+            assert_eq!(
+                find_yaml_key_line(CONTENT, &string_vec!["tasks", "build", "command"]),
+                Some(12)
+            );
+            assert_eq!(
+                find_yaml_key_line(CONTENT, &string_vec!["tasks", "build", "options", "cache"]),
+                Some(15)
+            );
+        }
+
+        #[test]
+        fn doesnt_match_keys_in_other_mappings() {
+            assert_eq!(
+                find_yaml_key_line(CONTENT, &string_vec!["tasks", "lint", "options"]),
+                Some(9)
+            );
+        }
+
+        #[test]
+        fn returns_parent_for_list_items() {
+            assert_eq!(
+                find_yaml_key_line(CONTENT, &string_vec!["fileGroups", "sources", "0"]),
+                Some(5)
+            );
+        }
+
+        #[test]
+        fn supports_quoted_keys() {
+            assert_eq!(
+                find_yaml_key_line("'type': 'tool'\n\"tasks\": {}", &string_vec!["tasks"]),
+                Some(2)
+            );
+        }
+
+        #[test]
+        fn returns_none_for_unknown_keys() {
+            assert_eq!(find_yaml_key_line(CONTENT, &string_vec!["unknown"]), None);
+            assert_eq!(find_yaml_key_line(CONTENT, &[]), None);
+        }
+    }
+}
@@ -3,23 +3,38 @@ mod errors;
 pub mod package;
 mod project;
 mod providers;
+mod schema;
 pub mod tsconfig;
 mod types;
 mod validators;
 mod workspace;
 
-pub use errors::format_figment_errors;
+pub use errors::{
+    find_yaml_key_line, format_figment_errors, locate_figment_errors, LocatedConfigError,
+};
 pub use project::global::GlobalProjectConfig;
-pub use project::task::{TaskConfig, TaskMergeStrategy, TaskOptionsConfig, TaskType};
-pub use project::{ProjectConfig, ProjectLanguage, ProjectMetadataConfig, ProjectType};
+pub use project::task::{
+    TaskConfig, TaskMergeStrategy, TaskOptionEnvFile, TaskOptionsConfig, TaskReadyCheckConfig,
+    TaskType,
+};
+pub use project::{
+    DependencyConfig, DependencyScope, ProjectConfig, ProjectDependsOn, ProjectLanguage,
+    ProjectMetadataConfig, ProjectType,
+};
+pub use schema::{
+    generate_json_schemas, SCHEMA_GLOBAL_PROJECT_FILENAME, SCHEMA_PROJECT_FILENAME,
+    SCHEMA_WORKSPACE_FILENAME,
+};
 pub use types::{FileGlob, FilePath, FilePathOrGlob, ProjectID, TargetID, TaskID};
 pub use validator::ValidationErrors;
+pub use workspace::deno::default_deno_version;
 pub use workspace::node::{
     default_node_version, default_npm_version, default_pnpm_version, default_yarn_version,
 };
 pub use workspace::{
-    NodeConfig, NpmConfig, PackageManager, PnpmConfig, TypeScriptConfig, VcsConfig, VcsManager,
-    WorkspaceConfig, YarnConfig,
+    CacheConfig, ConstraintsConfig, DenoConfig, NodeConfig, NpmConfig, PackageManager, PnpmConfig,
+    PruneCacheConfig, RemoteCacheConfig, TypeScriptConfig, VcsConfig, VcsManager, WorkspaceConfig,
+    YarnConfig,
 };
 
 pub fn load_workspace_config_template() -> &'static str {
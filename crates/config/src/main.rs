@@ -1,28 +1,10 @@
-use moon_config::{GlobalProjectConfig, ProjectConfig, WorkspaceConfig};
-use schemars::schema_for;
+use moon_config::generate_json_schemas;
 use std::fs;
+use std::path::Path;
 
 fn main() {
     // Generate JSON schemas derived from our structs
-    let project_schema = schema_for!(ProjectConfig);
-    let global_project_schema = schema_for!(GlobalProjectConfig);
-    let workspace_schema = schema_for!(WorkspaceConfig);
-
-    fs::write(
-        "website/static/schemas/project.json",
-        serde_json::to_string_pretty(&project_schema).unwrap(),
-    )
-    .unwrap();
-
-    fs::write(
-        "website/static/schemas/global-project.json",
-        serde_json::to_string_pretty(&global_project_schema).unwrap(),
-    )
-    .unwrap();
-
-    fs::write(
-        "website/static/schemas/workspace.json",
-        serde_json::to_string_pretty(&workspace_schema).unwrap(),
-    )
-    .unwrap();
+    for (file_name, schema) in generate_json_schemas().unwrap() {
+        fs::write(Path::new("website/static/schemas").join(file_name), schema).unwrap();
+    }
 }
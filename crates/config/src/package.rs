@@ -173,26 +173,56 @@ impl PackageJson {
         Ok(())
     }
 
+    /// Return true if the package is declared in either the `dependencies`,
+    /// `devDependencies`, or `peerDependencies` fields.
+    pub fn has_dependency(&self, name: &str) -> bool {
+        [
+            &self.dependencies,
+            &self.dev_dependencies,
+            &self.peer_dependencies,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|deps| deps.contains_key(name))
+    }
+
     /// Add a package and version range to the `dependencies` field.
     /// If `is_missing` is true, only add if it doesn't already exist.
     /// Return true if the new value is different from the old value.
     pub fn add_dependency(&mut self, name: &str, range: &str, if_missing: bool) -> bool {
-        let mut dependencies = match &self.dependencies {
-            Some(deps) => deps.clone(),
-            None => BTreeMap::new(),
-        };
+        if add_to_deps_set(&mut self.dependencies, name, range, if_missing) {
+            self.dirty = true;
 
-        // Only add if the dependency doesnt already exist
-        if if_missing && dependencies.contains_key(name) {
-            return false;
+            return true;
         }
 
-        dependencies.insert(name.to_owned(), range.to_owned());
+        false
+    }
 
-        self.dirty = true;
-        self.dependencies = Some(dependencies);
+    /// Add a package and version range to the `devDependencies` field.
+    /// If `is_missing` is true, only add if it doesn't already exist.
+    /// Return true if the new value is different from the old value.
+    pub fn add_dev_dependency(&mut self, name: &str, range: &str, if_missing: bool) -> bool {
+        if add_to_deps_set(&mut self.dev_dependencies, name, range, if_missing) {
+            self.dirty = true;
 
-        true
+            return true;
+        }
+
+        false
+    }
+
+    /// Add a package and version range to the `peerDependencies` field.
+    /// If `is_missing` is true, only add if it doesn't already exist.
+    /// Return true if the new value is different from the old value.
+    pub fn add_peer_dependency(&mut self, name: &str, range: &str, if_missing: bool) -> bool {
+        if add_to_deps_set(&mut self.peer_dependencies, name, range, if_missing) {
+            self.dirty = true;
+
+            return true;
+        }
+
+        false
     }
 
     /// Add a version range to the `engines` field.
@@ -227,6 +257,19 @@ impl PackageJson {
     }
 }
 
+fn add_to_deps_set(deps: &mut Option<DepsSet>, name: &str, range: &str, if_missing: bool) -> bool {
+    let deps = deps.get_or_insert_with(BTreeMap::new);
+
+    // Only add if the dependency doesnt already exist
+    if if_missing && deps.contains_key(name) {
+        return false;
+    }
+
+    deps.insert(name.to_owned(), range.to_owned());
+
+    true
+}
+
 pub type BinSet = BTreeMap<String, String>;
 pub type DepsMetaSet = BTreeMap<String, DependencyMeta>;
 pub type DepsSet = BTreeMap<String, String>;
@@ -497,6 +540,84 @@ mod test {
         }
     }
 
+    mod add_dev_dependency {
+        use super::*;
+
+        #[test]
+        fn adds_if_not_set() {
+            let mut pkg = PackageJson::default();
+
+            assert!(pkg.add_dev_dependency("foo", "1.2.3", true));
+
+            assert_eq!(pkg.dependencies, None);
+            assert_eq!(pkg.dev_dependencies.unwrap().get("foo").unwrap(), &"1.2.3");
+        }
+
+        #[test]
+        fn doesnt_add_if_set_and_missing_true() {
+            let mut pkg = PackageJson {
+                dev_dependencies: Some(BTreeMap::from([("foo".to_owned(), "1.2.3".to_owned())])),
+                ..PackageJson::default()
+            };
+
+            assert!(!pkg.add_dev_dependency("foo", "4.5.6", true));
+
+            assert_eq!(pkg.dev_dependencies.unwrap().get("foo").unwrap(), &"1.2.3");
+        }
+    }
+
+    mod add_peer_dependency {
+        use super::*;
+
+        #[test]
+        fn adds_if_not_set() {
+            let mut pkg = PackageJson::default();
+
+            assert!(pkg.add_peer_dependency("foo", "1.2.3", true));
+
+            assert_eq!(pkg.dependencies, None);
+            assert_eq!(pkg.peer_dependencies.unwrap().get("foo").unwrap(), &"1.2.3");
+        }
+
+        #[test]
+        fn doesnt_add_if_set_and_missing_true() {
+            let mut pkg = PackageJson {
+                peer_dependencies: Some(BTreeMap::from([("foo".to_owned(), "1.2.3".to_owned())])),
+                ..PackageJson::default()
+            };
+
+            assert!(!pkg.add_peer_dependency("foo", "4.5.6", true));
+
+            assert_eq!(pkg.peer_dependencies.unwrap().get("foo").unwrap(), &"1.2.3");
+        }
+    }
+
+    mod has_dependency {
+        use super::*;
+
+        #[test]
+        fn returns_false_if_not_set() {
+            let pkg = PackageJson::default();
+
+            assert!(!pkg.has_dependency("foo"));
+        }
+
+        #[test]
+        fn checks_all_dep_types() {
+            let pkg = PackageJson {
+                dependencies: Some(BTreeMap::from([("foo".to_owned(), "1.2.3".to_owned())])),
+                dev_dependencies: Some(BTreeMap::from([("bar".to_owned(), "1.2.3".to_owned())])),
+                peer_dependencies: Some(BTreeMap::from([("baz".to_owned(), "1.2.3".to_owned())])),
+                ..PackageJson::default()
+            };
+
+            assert!(pkg.has_dependency("foo"));
+            assert!(pkg.has_dependency("bar"));
+            assert!(pkg.has_dependency("baz"));
+            assert!(!pkg.has_dependency("qux"));
+        }
+    }
+
     mod add_engine {
         use super::*;
 
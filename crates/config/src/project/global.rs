@@ -292,14 +292,19 @@ fileGroups:
                     TaskConfig {
                         command: Some(String::from("e")),
                         options: TaskOptionsConfig {
+                            env_file: None,
+                            hash_env_vars: None,
                             merge_args: None,
                             merge_deps: None,
                             merge_env: None,
                             merge_inputs: None,
                             merge_outputs: None,
+                            persistent: None,
+                            ready_check: None,
                             retry_count: None,
                             run_in_ci: Some(false),
                             run_from_workspace_root: None,
+                            timeout: None,
                         },
                         ..TaskConfig::default()
                     },
@@ -44,6 +44,14 @@ fn validate_tasks(map: &HashMap<String, TaskConfig>) -> Result<(), ValidationErr
     Ok(())
 }
 
+fn validate_tags(list: &[String]) -> Result<(), ValidationError> {
+    for (index, item) in list.iter().enumerate() {
+        validate_id(&format!("tags[{}]", index), item)?;
+    }
+
+    Ok(())
+}
+
 fn validate_channel(value: &str) -> Result<(), ValidationError> {
     if !value.is_empty() && !value.starts_with('#') {
         return Err(create_validation_error(
@@ -70,7 +78,7 @@ impl Default for ProjectLanguage {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, JsonSchema, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ProjectType {
     Application,
@@ -85,6 +93,53 @@ impl Default for ProjectType {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyScope {
+    Production,
+    Development,
+    Peer,
+    Build,
+}
+
+impl Default for DependencyScope {
+    fn default() -> Self {
+        DependencyScope::Production
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+pub struct DependencyConfig {
+    pub id: ProjectID,
+
+    #[serde(default)]
+    pub scope: DependencyScope,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ProjectDependsOn {
+    String(ProjectID),
+    Object(DependencyConfig),
+}
+
+impl ProjectDependsOn {
+    pub fn id(&self) -> &ProjectID {
+        match self {
+            ProjectDependsOn::String(id) => id,
+            ProjectDependsOn::Object(config) => &config.id,
+        }
+    }
+
+    /// Return the scope of the dependency. The string form is always a production dependency.
+    pub fn scope(&self) -> DependencyScope {
+        match self {
+            ProjectDependsOn::String(_) => DependencyScope::Production,
+            ProjectDependsOn::Object(config) => config.scope,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
 pub struct ProjectMetadataConfig {
     pub name: String,
@@ -122,7 +177,7 @@ pub struct ProjectWorkspaceConfig {
 #[schemars(default)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectConfig {
-    pub depends_on: Vec<ProjectID>,
+    pub depends_on: Vec<ProjectDependsOn>,
 
     #[validate(custom = "validate_file_groups")]
     pub file_groups: FileGroups,
@@ -132,6 +187,9 @@ pub struct ProjectConfig {
     #[validate]
     pub project: Option<ProjectMetadataConfig>,
 
+    #[validate(custom = "validate_tags")]
+    pub tags: Vec<String>,
+
     #[validate(custom = "validate_tasks")]
     #[validate]
     pub tasks: HashMap<String, TaskConfig>,
@@ -220,6 +278,75 @@ fileGroups:
     }
 
     mod depends_on {
+        use super::*;
+
+        #[test]
+        fn supports_string_and_object_forms() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_PROJECT_FILENAME,
+                    r#"
+dependsOn:
+    - a
+    - id: b
+    - id: c
+      scope: development"#,
+                )?;
+
+                let config = load_jailed_config()?;
+
+                assert_eq!(
+                    config.depends_on,
+                    vec![
+                        ProjectDependsOn::String(String::from("a")),
+                        ProjectDependsOn::Object(DependencyConfig {
+                            id: String::from("b"),
+                            scope: DependencyScope::Production,
+                        }),
+                        ProjectDependsOn::Object(DependencyConfig {
+                            id: String::from("c"),
+                            scope: DependencyScope::Development,
+                        }),
+                    ]
+                );
+
+                assert_eq!(
+                    config
+                        .depends_on
+                        .iter()
+                        .map(|d| (d.id().as_str(), d.scope()))
+                        .collect::<Vec<_>>(),
+                    vec![
+                        ("a", DependencyScope::Production),
+                        ("b", DependencyScope::Production),
+                        ("c", DependencyScope::Development),
+                    ]
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "data did not match any variant of untagged enum ProjectDependsOn"
+        )]
+        fn invalid_scope() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_PROJECT_FILENAME,
+                    r#"
+dependsOn:
+    - id: a
+      scope: optional"#,
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
         #[test]
         #[should_panic(
             expected = "invalid type: found unsigned int `123`, expected a sequence for key \"project.dependsOn\""
@@ -270,6 +397,55 @@ fileGroups:
         }
     }
 
+    mod tags {
+        use super::*;
+
+        #[test]
+        fn loads_list() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_PROJECT_FILENAME,
+                    "tags:\n    - frontend\n    - react",
+                )?;
+
+                let config = load_jailed_config()?;
+
+                assert_eq!(config.tags, string_vec!["frontend", "react"]);
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "invalid type: found string \"frontend\", expected a sequence for key \"project.tags\""
+        )]
+        fn invalid_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(constants::CONFIG_PROJECT_FILENAME, "tags: frontend")?;
+
+                load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "Must be a valid ID")]
+        fn invalid_id() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_PROJECT_FILENAME,
+                    "tags:\n    - '#frontend'",
+                )?;
+
+                load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+    }
+
     mod tasks {
         use super::*;
 
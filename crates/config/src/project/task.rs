@@ -1,5 +1,8 @@
 use crate::types::{FilePath, FilePathOrGlob, TargetID};
-use crate::validators::{validate_child_or_root_path, validate_target};
+use crate::validators::{
+    validate_child_or_root_path, validate_env_var_patterns, validate_regex, validate_target,
+    validate_url,
+};
 use moon_utils::process::split_args;
 use schemars::gen::SchemaGenerator;
 use schemars::schema::Schema;
@@ -29,6 +32,34 @@ fn validate_inputs(list: &[String]) -> Result<(), ValidationError> {
     Ok(())
 }
 
+fn validate_env_file(file: &TaskOptionEnvFile) -> Result<(), ValidationError> {
+    match file {
+        TaskOptionEnvFile::File(path) => {
+            validate_child_or_root_path("envFile", path)?;
+        }
+        TaskOptionEnvFile::Files(paths) => {
+            for (index, path) in paths.iter().enumerate() {
+                validate_child_or_root_path(&format!("envFile[{}]", index), path)?;
+            }
+        }
+        _ => {}
+    };
+
+    Ok(())
+}
+
+fn validate_hash_env_vars(list: &[String]) -> Result<(), ValidationError> {
+    validate_env_var_patterns("hashEnvVars", list)
+}
+
+fn validate_ready_output(pattern: &str) -> Result<(), ValidationError> {
+    validate_regex("readyCheck.output", pattern)
+}
+
+fn validate_ready_url(url: &str) -> Result<(), ValidationError> {
+    validate_url("readyCheck.url", url, false)
+}
+
 fn validate_outputs(list: &[String]) -> Result<(), ValidationError> {
     for (index, item) in list.iter().enumerate() {
         validate_child_or_root_path(&format!("outputs[{}]", index), item)?;
@@ -40,6 +71,7 @@ fn validate_outputs(list: &[String]) -> Result<(), ValidationError> {
 #[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TaskType {
+    Deno,
     Node,
     System,
 }
@@ -64,9 +96,56 @@ impl Default for TaskMergeStrategy {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TaskOptionEnvFile {
+    Enabled(bool),
+    File(FilePath),
+    Files(Vec<FilePath>),
+}
+
+impl TaskOptionEnvFile {
+    /// Return a list of env files to load, in order of precedence (lowest first).
+    /// When enabled, defaults to a `.env` file in the project root.
+    pub fn to_files(&self) -> Vec<FilePath> {
+        match self {
+            TaskOptionEnvFile::Enabled(true) => vec![String::from(".env")],
+            TaskOptionEnvFile::Enabled(false) => vec![],
+            TaskOptionEnvFile::File(path) => vec![path.clone()],
+            TaskOptionEnvFile::Files(paths) => paths.clone(),
+        }
+    }
+}
+
+/// Determines when a persistent task is ready, so that its dependents can run.
+/// When multiple checks are configured, all of them must pass.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskReadyCheckConfig {
+    /// Regular expression that a line of stdout or stderr must match.
+    #[validate(custom = "validate_ready_output")]
+    pub output: Option<String>,
+
+    /// TCP port on localhost that must accept connections.
+    pub port: Option<u16>,
+
+    /// Seconds to wait for the task to become ready. Defaults to 60.
+    pub timeout: Option<u64>,
+
+    /// HTTP URL that must respond without a server error.
+    #[validate(custom = "validate_ready_url")]
+    pub url: Option<String>,
+}
+
 #[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskOptionsConfig {
+    #[validate(custom = "validate_env_file")]
+    pub env_file: Option<TaskOptionEnvFile>,
+
+    #[validate(custom = "validate_hash_env_vars")]
+    pub hash_env_vars: Option<Vec<String>>,
+
     pub merge_args: Option<TaskMergeStrategy>,
 
     pub merge_deps: Option<TaskMergeStrategy>,
@@ -77,25 +156,37 @@ pub struct TaskOptionsConfig {
 
     pub merge_outputs: Option<TaskMergeStrategy>,
 
+    pub persistent: Option<bool>,
+
+    #[validate]
+    pub ready_check: Option<TaskReadyCheckConfig>,
+
     pub retry_count: Option<u8>,
 
     #[serde(rename = "runInCI")]
     pub run_in_ci: Option<bool>,
 
     pub run_from_workspace_root: Option<bool>,
+
+    pub timeout: Option<u64>,
 }
 
 impl Default for TaskOptionsConfig {
     fn default() -> Self {
         TaskOptionsConfig {
+            env_file: None,
+            hash_env_vars: None,
             merge_args: Some(TaskMergeStrategy::default()),
             merge_deps: Some(TaskMergeStrategy::default()),
             merge_env: Some(TaskMergeStrategy::default()),
             merge_inputs: Some(TaskMergeStrategy::default()),
             merge_outputs: Some(TaskMergeStrategy::default()),
+            persistent: Some(false),
+            ready_check: None,
             retry_count: Some(0),
             run_in_ci: Some(true),
             run_from_workspace_root: Some(false),
+            timeout: None,
         }
     }
 }
@@ -599,6 +690,70 @@ options:
             });
         }
 
+        #[test]
+        #[should_panic(
+            expected = "invalid type: found string \"abc\", expected a sequence for key \"default.options.hashEnvVars\""
+        )]
+        fn invalid_hash_env_vars_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    r#"
+command: foo
+options:
+    hashEnvVars: abc
+"#,
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "invalid type: found string \"abc\", expected a boolean for key \"default.options.persistent\""
+        )]
+        fn invalid_persistent_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    r#"
+command: foo
+options:
+    persistent: abc
+"#,
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "invalid type: found string \"abc\", expected u16 for key \"default.options.readyCheck.port\""
+        )]
+        fn invalid_ready_check_port_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    r#"
+command: foo
+options:
+    readyCheck:
+        port: abc
+"#,
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
         #[test]
         #[should_panic(
             expected = "invalid type: found string \"abc\", expected u8 for key \"default.options.retryCount\""
@@ -619,5 +774,26 @@ options:
                 Ok(())
             });
         }
+
+        #[test]
+        #[should_panic(
+            expected = "invalid type: found string \"abc\", expected u64 for key \"default.options.timeout\""
+        )]
+        fn invalid_timeout_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    r#"
+command: foo
+options:
+    timeout: abc
+"#,
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
     }
 }
@@ -0,0 +1,57 @@
+use crate::{GlobalProjectConfig, ProjectConfig, WorkspaceConfig};
+use schemars::schema_for;
+use serde_json::Error as JsonError;
+
+pub const SCHEMA_GLOBAL_PROJECT_FILENAME: &str = "global-project.json";
+
+pub const SCHEMA_PROJECT_FILENAME: &str = "project.json";
+
+pub const SCHEMA_WORKSPACE_FILENAME: &str = "workspace.json";
+
+/// Generate JSON schemas derived from our configuration structs,
+/// and return a list of schema file names to their pretty printed content.
+pub fn generate_json_schemas() -> Result<Vec<(&'static str, String)>, JsonError> {
+    Ok(vec![
+        (
+            SCHEMA_GLOBAL_PROJECT_FILENAME,
+            serde_json::to_string_pretty(&schema_for!(GlobalProjectConfig))?,
+        ),
+        (
+            SCHEMA_PROJECT_FILENAME,
+            serde_json::to_string_pretty(&schema_for!(ProjectConfig))?,
+        ),
+        (
+            SCHEMA_WORKSPACE_FILENAME,
+            serde_json::to_string_pretty(&schema_for!(WorkspaceConfig))?,
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_all_schemas() {
+        let schemas = generate_json_schemas().unwrap();
+        let names = schemas.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            vec!["global-project.json", "project.json", "workspace.json"]
+        );
+    }
+
+    #[test]
+    fn includes_titles() {
+        for (name, content) in generate_json_schemas().unwrap() {
+            let title = match name {
+                SCHEMA_GLOBAL_PROJECT_FILENAME => "GlobalProjectConfig",
+                SCHEMA_PROJECT_FILENAME => "ProjectConfig",
+                _ => "WorkspaceConfig",
+            };
+
+            assert!(content.contains(&format!("\"title\": \"{}\"", title)));
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::errors::create_validation_error;
-use moon_utils::regex::{matches_id, matches_target};
+use moon_utils::glob::{create_glob, is_glob};
+use moon_utils::regex::{matches_id, matches_target, Regex};
 use semver::Version;
 use std::path::Path;
 use validator::{validate_url as validate_base_url, ValidationError};
@@ -87,6 +88,40 @@ pub fn validate_target(key: &str, target_id: &str) -> Result<(), ValidationError
     Ok(())
 }
 
+// Validate each value is an environment variable name, or a glob that matches names.
+pub fn validate_env_var_patterns(key: &str, list: &[String]) -> Result<(), ValidationError> {
+    for (index, pattern) in list.iter().enumerate() {
+        let is_valid = if is_glob(pattern) {
+            create_glob(pattern).is_ok()
+        } else {
+            !pattern.is_empty() && !pattern.contains('=')
+        };
+
+        if !is_valid {
+            return Err(create_validation_error(
+                "invalid_env_var",
+                &format!("{}[{}]", key, index),
+                String::from("Must be a valid environment variable name or glob"),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Validate the value is a regular expression that can be compiled.
+pub fn validate_regex(key: &str, value: &str) -> Result<(), ValidationError> {
+    if let Err(error) = Regex::new(value) {
+        return Err(create_validation_error(
+            "invalid_regex",
+            key,
+            format!("Invalid regular expression: {}", error),
+        ));
+    }
+
+    Ok(())
+}
+
 // Validate the value is a URL, and optionally check if HTTPS.
 pub fn validate_url(key: &str, value: &str, https_only: bool) -> Result<(), ValidationError> {
     if !validate_base_url(value) || !value.starts_with("http") {
@@ -294,6 +329,48 @@ mod tests {
         }
     }
 
+    mod validate_env_var_patterns {
+        use super::*;
+
+        #[test]
+        fn passes_for_names_and_globs() {
+            assert!(validate_env_var_patterns(
+                "key",
+                &[String::from("NODE_ENV"), String::from("NEXT_PUBLIC_*")]
+            )
+            .is_ok());
+        }
+
+        #[test]
+        fn fails_for_empty() {
+            assert!(validate_env_var_patterns("key", &[String::new()]).is_err());
+        }
+
+        #[test]
+        fn fails_for_assignments() {
+            assert!(validate_env_var_patterns("key", &[String::from("NODE_ENV=test")]).is_err());
+        }
+
+        #[test]
+        fn fails_for_invalid_globs() {
+            assert!(validate_env_var_patterns("key", &[String::from("{FOO,{BAR}")]).is_err());
+        }
+    }
+
+    mod validate_regex {
+        use super::*;
+
+        #[test]
+        fn passes_for_valid_pattern() {
+            assert!(validate_regex("key", "Listening on port \\d+").is_ok());
+        }
+
+        #[test]
+        fn fails_for_invalid_pattern() {
+            assert!(validate_regex("key", "ready(").is_err());
+        }
+    }
+
     mod validate_url {
         use super::*;
 
@@ -0,0 +1,70 @@
+use crate::errors::create_validation_error;
+use crate::validators::validate_url;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+fn validate_remote_host(value: &str) -> Result<(), ValidationError> {
+    validate_url("cache.remote.host", value, false)
+}
+
+fn validate_keep_per_target(value: &usize) -> Result<(), ValidationError> {
+    if *value == 0 {
+        return Err(create_validation_error(
+            "invalid_keep_per_target",
+            "cache.prune.keepPerTarget",
+            String::from("Must keep at least 1 hash per target"),
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
+#[schemars(default)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneCacheConfig {
+    /// Only keep the most recent hashes (and their outputs) and run logs
+    /// for each target.
+    #[validate(custom = "validate_keep_per_target")]
+    pub keep_per_target: Option<usize>,
+
+    /// Delete hashes, outputs, and run logs older than this many days.
+    pub max_age: Option<u64>,
+
+    /// Delete the oldest hashes and outputs once their total size exceeds
+    /// this many megabytes.
+    pub max_size: Option<u64>,
+}
+
+impl PruneCacheConfig {
+    pub fn is_empty(&self) -> bool {
+        self.keep_per_target.is_none() && self.max_age.is_none() && self.max_size.is_none()
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
+#[schemars(default)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteCacheConfig {
+    /// Base URL of an HTTP or S3-compatible server. Artifacts are
+    /// read and written with `GET` and `PUT` requests to `<host>/<hash>/<file>`.
+    #[validate(custom = "validate_remote_host")]
+    pub host: String,
+
+    /// Only download artifacts from the remote, never upload.
+    pub read_only: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
+#[schemars(default)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheConfig {
+    /// When configured, stale cache entries are automatically pruned
+    /// (at most once a day) before running targets.
+    #[validate]
+    pub prune: Option<PruneCacheConfig>,
+
+    #[validate]
+    pub remote: Option<RemoteCacheConfig>,
+}
@@ -0,0 +1,48 @@
+use crate::project::ProjectType;
+use crate::types::ProjectID;
+use crate::validators::validate_id;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use validator::{Validate, ValidationError};
+
+type TagRelationshipsMap = HashMap<String, Vec<String>>;
+
+fn validate_forbidden_dependencies(list: &[ProjectID]) -> Result<(), ValidationError> {
+    for (index, item) in list.iter().enumerate() {
+        validate_id(
+            &format!("constraints.forbiddenDependencies[{}]", index),
+            item,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn validate_tag_relationships(map: &TagRelationshipsMap) -> Result<(), ValidationError> {
+    for (tag, allowed_tags) in map {
+        validate_id(&format!("constraints.tagRelationships.{}", tag), tag)?;
+
+        for (index, item) in allowed_tags.iter().enumerate() {
+            validate_id(
+                &format!("constraints.tagRelationships.{}[{}]", tag, index),
+                item,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
+#[schemars(default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintsConfig {
+    #[validate(custom = "validate_forbidden_dependencies")]
+    pub forbidden_dependencies: Vec<ProjectID>,
+
+    #[validate(custom = "validate_tag_relationships")]
+    pub tag_relationships: TagRelationshipsMap,
+
+    pub type_relationships: HashMap<ProjectType, Vec<ProjectType>>,
+}
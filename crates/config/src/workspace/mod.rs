@@ -1,5 +1,8 @@
 // .moon/workspace.yml
 
+mod cache;
+mod constraints;
+pub mod deno;
 pub mod node;
 mod typescript;
 mod vcs;
@@ -8,7 +11,12 @@ use crate::constants;
 use crate::errors::map_validation_errors_to_figment_errors;
 use crate::providers::url::Url;
 use crate::types::{FileGlob, FilePath};
-use crate::validators::{validate_child_relative_path, validate_extends, validate_id};
+use crate::validators::{
+    validate_child_relative_path, validate_env_var_patterns, validate_extends, validate_id,
+};
+pub use cache::{CacheConfig, PruneCacheConfig, RemoteCacheConfig};
+pub use constraints::ConstraintsConfig;
+pub use deno::DenoConfig;
 use figment::{
     providers::{Format, Serialized, Yaml},
     Error as FigmentError, Figment,
@@ -49,20 +57,36 @@ fn validate_projects(projects: &ProjectsMap) -> Result<(), ValidationError> {
     Ok(())
 }
 
+fn validate_hash_env_vars(list: &[String]) -> Result<(), ValidationError> {
+    validate_env_var_patterns("actionRunner.hashEnvVars", list)
+}
+
+fn validate_redact_env_vars(list: &[String]) -> Result<(), ValidationError> {
+    validate_env_var_patterns("actionRunner.redactEnvVars", list)
+}
+
 #[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
 #[schemars(default)]
 #[serde(rename_all = "camelCase")]
 pub struct ActionRunnerConfig {
+    #[validate(custom = "validate_hash_env_vars")]
+    pub hash_env_vars: Vec<String>,
+
     pub inherit_colors_for_piped_tasks: bool,
 
     pub log_running_command: bool,
+
+    #[validate(custom = "validate_redact_env_vars")]
+    pub redact_env_vars: Vec<String>,
 }
 
 impl Default for ActionRunnerConfig {
     fn default() -> Self {
         ActionRunnerConfig {
+            hash_env_vars: vec![],
             inherit_colors_for_piped_tasks: true,
             log_running_command: false,
+            redact_env_vars: vec![],
         }
     }
 }
@@ -75,6 +99,15 @@ pub struct WorkspaceConfig {
     #[validate]
     pub action_runner: ActionRunnerConfig,
 
+    #[validate]
+    pub cache: CacheConfig,
+
+    #[validate]
+    pub constraints: ConstraintsConfig,
+
+    #[validate]
+    pub deno: Option<DenoConfig>,
+
     #[validate(custom = "validate_extends")]
     pub extends: Option<String>,
 
@@ -131,6 +164,12 @@ impl WorkspaceConfig {
             config.node.version = node_version;
         }
 
+        if let Ok(deno_version) = env::var("MOON_DENO_VERSION") {
+            if let Some(deno_config) = &mut config.deno {
+                deno_config.version = deno_version;
+            }
+        }
+
         if let Ok(npm_version) = env::var("MOON_NPM_VERSION") {
             config.node.npm.version = npm_version;
         }
@@ -258,6 +297,9 @@ mod tests {
                 config,
                 WorkspaceConfig {
                     action_runner: ActionRunnerConfig::default(),
+                    cache: CacheConfig::default(),
+                    constraints: ConstraintsConfig::default(),
+                    deno: None,
                     extends: None,
                     node: NodeConfig::default(),
                     projects: HashMap::new(),
@@ -445,6 +487,67 @@ node:
         // }
     }
 
+    mod deno {
+        use super::*;
+
+        #[test]
+        fn loads_version() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+deno:
+    version: '1.20.0'"#,
+                )?;
+
+                let config = super::load_jailed_config(jail.directory())?;
+
+                assert_eq!(
+                    config.deno,
+                    Some(DenoConfig {
+                        version: String::from("1.20.0"),
+                    })
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "invalid type: found unsigned int `123`, expected struct DenoConfig for key \"workspace.deno\""
+        )]
+        fn invalid_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(super::constants::CONFIG_WORKSPACE_FILENAME, "deno: 123")?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Must be a valid semantic version for key \"workspace.deno.version\""
+        )]
+        fn invalid_version() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+deno:
+    version: 'foo bar'"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+    }
+
     mod node {
         use super::*;
 
@@ -465,6 +568,9 @@ node:
                     config,
                     WorkspaceConfig {
                         action_runner: ActionRunnerConfig::default(),
+                        cache: CacheConfig::default(),
+                        constraints: ConstraintsConfig::default(),
+                        deno: None,
                         extends: None,
                         node: NodeConfig {
                             package_manager: PackageManager::Yarn,
@@ -834,6 +940,293 @@ projects: {}
         }
     }
 
+    mod action_runner {
+        use super::*;
+
+        #[test]
+        fn loads_hash_env_vars() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+actionRunner:
+    hashEnvVars: ['NODE_ENV', 'NEXT_PUBLIC_*']"#,
+                )?;
+
+                let config = super::load_jailed_config(jail.directory())?;
+
+                assert_eq!(
+                    config.action_runner.hash_env_vars,
+                    vec![String::from("NODE_ENV"), String::from("NEXT_PUBLIC_*")]
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Must be a valid environment variable name or glob for key \"workspace.actionRunner.hashEnvVars[0]\""
+        )]
+        fn invalid_hash_env_var() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+actionRunner:
+    hashEnvVars: ['NODE_ENV=production']"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn loads_redact_env_vars() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+actionRunner:
+    redactEnvVars: ['*_TOKEN']"#,
+                )?;
+
+                let config = super::load_jailed_config(jail.directory())?;
+
+                assert_eq!(
+                    config.action_runner.redact_env_vars,
+                    vec![String::from("*_TOKEN")]
+                );
+
+                Ok(())
+            });
+        }
+    }
+
+    mod cache {
+        use super::*;
+
+        #[test]
+        fn loads_prune() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+cache:
+    prune:
+        keepPerTarget: 3
+        maxAge: 7"#,
+                )?;
+
+                let config = super::load_jailed_config(jail.directory())?;
+
+                assert_eq!(
+                    config.cache.prune,
+                    Some(PruneCacheConfig {
+                        keep_per_target: Some(3),
+                        max_age: Some(7),
+                        max_size: None,
+                    })
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Must keep at least 1 hash per target for key \"workspace.cache.prune.keepPerTarget\""
+        )]
+        fn invalid_prune_keep_per_target() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+cache:
+    prune:
+        keepPerTarget: 0"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn loads_remote() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+cache:
+    remote:
+        host: 'https://cache.example.com/moon'"#,
+                )?;
+
+                let config = super::load_jailed_config(jail.directory())?;
+
+                assert_eq!(
+                    config.cache.remote,
+                    Some(RemoteCacheConfig {
+                        host: String::from("https://cache.example.com/moon"),
+                        read_only: false,
+                    })
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "invalid type: found unsigned int `123`, expected struct RemoteCacheConfig for key \"workspace.cache.remote\""
+        )]
+        fn invalid_remote_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+cache:
+    remote: 123"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "Must be a valid URL for key \"workspace.cache.remote.host\"")]
+        fn invalid_remote_host() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+cache:
+    remote:
+        host: 'not a url'"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+    }
+
+    mod constraints {
+        use super::*;
+        use crate::ProjectType;
+
+        #[test]
+        fn loads_constraints() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+constraints:
+    forbiddenDependencies: ['app']
+    tagRelationships:
+        frontend: ['frontend', 'shared']
+    typeRelationships:
+        library: ['library', 'tool']"#,
+                )?;
+
+                let config = super::load_jailed_config(jail.directory())?;
+
+                assert_eq!(
+                    config.constraints,
+                    ConstraintsConfig {
+                        forbidden_dependencies: vec![String::from("app")],
+                        tag_relationships: HashMap::from([(
+                            String::from("frontend"),
+                            vec![String::from("frontend"), String::from("shared")]
+                        )]),
+                        type_relationships: HashMap::from([(
+                            ProjectType::Library,
+                            vec![ProjectType::Library, ProjectType::Tool]
+                        )]),
+                    }
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Must be a valid ID (accepts A-Z, a-z, 0-9, - (dashes), _ (underscores), and must start with a letter) for key \"workspace.constraints.forbiddenDependencies[0]\""
+        )]
+        fn invalid_forbidden_dependency() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+constraints:
+    forbiddenDependencies: ['@app']"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Must be a valid ID (accepts A-Z, a-z, 0-9, - (dashes), _ (underscores), and must start with a letter) for key \"workspace.constraints.tagRelationships.frontend[0]\""
+        )]
+        fn invalid_tag_relationship() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+constraints:
+    tagRelationships:
+        frontend: ['#shared']"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "unknown variant")]
+        fn invalid_type_relationship() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+constraints:
+    typeRelationships:
+        service: ['library']"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+    }
+
     mod projects {
         use super::*;
         use std::collections::HashMap;
@@ -970,6 +1363,9 @@ vcs:
                     config,
                     WorkspaceConfig {
                         action_runner: ActionRunnerConfig::default(),
+                        cache: CacheConfig::default(),
+                        constraints: ConstraintsConfig::default(),
+                        deno: None,
                         extends: None,
                         node: NodeConfig::default(),
                         projects: HashMap::new(),
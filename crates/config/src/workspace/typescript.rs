@@ -6,6 +6,8 @@ use validator::Validate;
 #[schemars(default)]
 #[serde(rename_all = "camelCase")]
 pub struct TypeScriptConfig {
+    pub infer_project_references: bool,
+
     pub project_config_file_name: String,
 
     pub root_config_file_name: String,
@@ -16,6 +18,7 @@ pub struct TypeScriptConfig {
 impl Default for TypeScriptConfig {
     fn default() -> Self {
         TypeScriptConfig {
+            infer_project_references: false,
             project_config_file_name: String::from("tsconfig.json"),
             root_config_file_name: String::from("tsconfig.json"),
             sync_project_references: true,
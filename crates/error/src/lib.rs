@@ -15,6 +15,9 @@ pub enum MoonError {
     #[error("Failed to create a hard link from <path>{0}</path> to <path>{1}</path>.")]
     HardLink(PathBuf, PathBuf),
 
+    #[error("HTTP request to <url>{0}</url> failed: {1}")]
+    Http(String, String),
+
     #[error("Failed to parse <path>{0}</path>: {1}")]
     Json(PathBuf, #[source] JsonError),
 
@@ -30,12 +33,18 @@ pub enum MoonError {
     #[error("Process failure for <shell>{0}</shell>: {1}")]
     Process(String, #[source] IoError),
 
+    #[error("Process <shell>{0}</shell> was cancelled.")]
+    ProcessCancelled(String, Vec<u8>, Vec<u8>), // bin, partial stdout, partial stderr
+
     #[error("Process <shell>{0}</shell> failed with a <symbol>{1}</symbol> exit code.")]
     ProcessNonZero(String, i32),
 
     #[error("Process <shell>{0}</shell> failed with a <symbol>{1}</symbol> exit code.\n<muted>{2}</muted>")]
     ProcessNonZeroWithOutput(String, i32, String),
 
+    #[error("Process <shell>{0}</shell> timed out after <symbol>{1}s</symbol>.")]
+    ProcessTimeout(String, u64, Vec<u8>, Vec<u8>), // bin, secs, partial stdout, partial stderr
+
     #[error("{0}")]
     Unknown(#[source] IoError),
 }
@@ -0,0 +1,229 @@
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+#[derive(Debug, PartialEq)]
+pub enum ManifestChange {
+    Added(String),
+    Changed(String, String),
+    Removed(String),
+}
+
+/// A single difference between 2 hash manifests. When the field is a map
+/// (input files, env vars, dependencies, etc), the `key` is the entry that changed.
+#[derive(Debug, PartialEq)]
+pub struct ManifestDiff {
+    pub change: ManifestChange,
+
+    pub field: String,
+
+    pub key: Option<String>,
+}
+
+impl ManifestDiff {
+    fn new(field: &str, key: Option<&str>, change: ManifestChange) -> Self {
+        ManifestDiff {
+            change,
+            field: field.to_owned(),
+            key: key.map(|k| k.to_owned()),
+        }
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        _ => value.to_string(),
+    }
+}
+
+fn diff_field(field: &str, left: Option<&Value>, right: Option<&Value>) -> Vec<ManifestDiff> {
+    let mut diffs = vec![];
+
+    match (left, right) {
+        (Some(Value::Object(left_map)), Some(Value::Object(right_map))) => {
+            let keys = left_map
+                .keys()
+                .chain(right_map.keys())
+                .collect::<BTreeSet<_>>();
+
+            for key in keys {
+                match (left_map.get(key), right_map.get(key)) {
+                    (Some(l), Some(r)) if l != r => {
+                        diffs.push(ManifestDiff::new(
+                            field,
+                            Some(key),
+                            ManifestChange::Changed(value_to_string(l), value_to_string(r)),
+                        ));
+                    }
+                    (Some(l), None) => {
+                        diffs.push(ManifestDiff::new(
+                            field,
+                            Some(key),
+                            ManifestChange::Removed(value_to_string(l)),
+                        ));
+                    }
+                    (None, Some(r)) => {
+                        diffs.push(ManifestDiff::new(
+                            field,
+                            Some(key),
+                            ManifestChange::Added(value_to_string(r)),
+                        ));
+                    }
+                    _ => {}
+                };
+            }
+        }
+        (Some(Value::Array(left_list)), Some(Value::Array(right_list))) => {
+            for item in left_list {
+                if !right_list.contains(item) {
+                    diffs.push(ManifestDiff::new(
+                        field,
+                        None,
+                        ManifestChange::Removed(value_to_string(item)),
+                    ));
+                }
+            }
+
+            for item in right_list {
+                if !left_list.contains(item) {
+                    diffs.push(ManifestDiff::new(
+                        field,
+                        None,
+                        ManifestChange::Added(value_to_string(item)),
+                    ));
+                }
+            }
+        }
+        (Some(l), Some(r)) if l != r => {
+            diffs.push(ManifestDiff::new(
+                field,
+                None,
+                ManifestChange::Changed(value_to_string(l), value_to_string(r)),
+            ));
+        }
+        (Some(l), None) => {
+            diffs.push(ManifestDiff::new(
+                field,
+                None,
+                ManifestChange::Removed(value_to_string(l)),
+            ));
+        }
+        (None, Some(r)) => {
+            diffs.push(ManifestDiff::new(
+                field,
+                None,
+                ManifestChange::Added(value_to_string(r)),
+            ));
+        }
+        _ => {}
+    };
+
+    diffs
+}
+
+/// Compare 2 hash manifests (the JSON written by `CacheEngine.save_hash`)
+/// and return the field-level differences, from `left` to `right`.
+pub fn diff_manifests(left: &Value, right: &Value) -> Vec<ManifestDiff> {
+    let empty = serde_json::Map::new();
+    let left_map = left.as_object().unwrap_or(&empty);
+    let right_map = right.as_object().unwrap_or(&empty);
+    let mut diffs = vec![];
+
+    let fields = left_map
+        .keys()
+        .chain(right_map.keys())
+        .collect::<BTreeSet<_>>();
+
+    for field in fields {
+        diffs.extend(diff_field(field, left_map.get(field), right_map.get(field)));
+    }
+
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn returns_nothing_for_same_manifests() {
+        let manifest = json!({ "command": "tsc", "args": ["--build"] });
+
+        assert_eq!(diff_manifests(&manifest, &manifest), vec![]);
+    }
+
+    #[test]
+    fn detects_changed_values() {
+        assert_eq!(
+            diff_manifests(
+                &json!({ "nodeVersion": "16.0.0" }),
+                &json!({ "nodeVersion": "18.0.0" })
+            ),
+            vec![ManifestDiff::new(
+                "nodeVersion",
+                None,
+                ManifestChange::Changed("16.0.0".into(), "18.0.0".into())
+            )]
+        );
+    }
+
+    #[test]
+    fn detects_list_changes() {
+        assert_eq!(
+            diff_manifests(
+                &json!({ "deps": ["a:build", "b:build"] }),
+                &json!({ "deps": ["b:build", "c:build"] })
+            ),
+            vec![
+                ManifestDiff::new("deps", None, ManifestChange::Removed("a:build".into())),
+                ManifestDiff::new("deps", None, ManifestChange::Added("c:build".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_map_changes() {
+        assert_eq!(
+            diff_manifests(
+                &json!({ "inputHashes": { "a.ts": "1", "b.ts": "2" } }),
+                &json!({ "inputHashes": { "b.ts": "3", "c.ts": "4" } })
+            ),
+            vec![
+                ManifestDiff::new(
+                    "inputHashes",
+                    Some("a.ts"),
+                    ManifestChange::Removed("1".into())
+                ),
+                ManifestDiff::new(
+                    "inputHashes",
+                    Some("b.ts"),
+                    ManifestChange::Changed("2".into(), "3".into())
+                ),
+                ManifestDiff::new(
+                    "inputHashes",
+                    Some("c.ts"),
+                    ManifestChange::Added("4".into())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_missing_fields() {
+        assert_eq!(
+            diff_manifests(
+                &json!({ "command": "tsc" }),
+                &json!({ "processEnvVars": { "CI": "true" } })
+            ),
+            vec![
+                ManifestDiff::new("command", None, ManifestChange::Removed("tsc".into())),
+                ManifestDiff::new(
+                    "processEnvVars",
+                    None,
+                    ManifestChange::Added("{\"CI\":\"true\"}".into())
+                ),
+            ]
+        );
+    }
+}
@@ -1,10 +1,54 @@
 use moon_config::package::PackageJson;
 use moon_config::tsconfig::TsConfigJson;
 use moon_project::{Project, Task};
+use moon_utils::glob::{self, GlobSet};
 use moon_utils::path;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+
+/// Hash an arbitrary value so that it can be persisted in the manifest
+/// without leaking its contents (secrets in `.env` files, etc).
+fn hash_value(value: &str) -> String {
+    let mut sha = Sha256::new();
+    sha.update(value.as_bytes());
+
+    format!("{:x}", sha.finalize())
+}
+
+/// Match environment variable names against a list of names and globs.
+struct EnvVarMatcher {
+    globset: Option<GlobSet>,
+
+    names: Vec<String>,
+}
+
+impl EnvVarMatcher {
+    fn new(patterns: &[String]) -> Self {
+        let (globs, names): (Vec<String>, Vec<String>) =
+            patterns.iter().cloned().partition(|p| glob::is_glob(p));
+
+        // Patterns are validated when loading configs
+        let globset = if globs.is_empty() {
+            None
+        } else {
+            GlobSet::new(&globs).ok()
+        };
+
+        EnvVarMatcher { globset, names }
+    }
+
+    fn matches(&self, key: &str) -> bool {
+        self.names.iter().any(|name| name == key)
+            || self
+                .globset
+                .as_ref()
+                .map(|set| set.matches(Path::new(key)).unwrap_or(false))
+                .unwrap_or(false)
+    }
+}
 
 #[derive(Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -18,7 +62,7 @@ pub struct TargetHasher {
     // Task `deps`
     deps: Vec<String>,
 
-    // Environment variables
+    // Environment variables, mapped to a hash of their value
     env_vars: BTreeMap<String, String>,
 
     // Input files and globs mapped to a unique hash
@@ -36,12 +80,19 @@ pub struct TargetHasher {
     // `package.json` `peerDependencies`
     package_peer_dependencies: BTreeMap<String, String>,
 
+    // Process environment variables matched by `hashEnvVars`, mapped to
+    // their value (or a hash of it when matched by `redactEnvVars`)
+    process_env_vars: BTreeMap<String, String>,
+
     // `project.yml` `dependsOn`
     project_deps: Vec<String>,
 
     // Task `target`
     target: String,
 
+    // Versions of platform tools (excluding Node.js) that run the task
+    tool_versions: BTreeMap<String, String>,
+
     // `tsconfig.json` `compilerOptions`
     tsconfig_compiler_options: BTreeMap<String, String>,
 
@@ -96,6 +147,41 @@ impl TargetHasher {
         }
     }
 
+    /// Hash the current values of process environment variables whose names
+    /// match the provided names or globs. Unset variables are ignored. Values are
+    /// stored as-is, unless they match the redact patterns, in which case only a
+    /// hash of the value is stored.
+    pub fn hash_process_env_vars(&mut self, patterns: &[String], redact_patterns: &[String]) {
+        if patterns.is_empty() {
+            return;
+        }
+
+        let matcher = EnvVarMatcher::new(patterns);
+        let redact_matcher = EnvVarMatcher::new(redact_patterns);
+
+        for (key, value) in env::vars_os() {
+            // Patterns only match valid names, but values may not be valid UTF-8
+            let key = match key.into_string() {
+                Ok(key) => key,
+                Err(_) => continue,
+            };
+
+            if !matcher.matches(&key) {
+                continue;
+            }
+
+            let value = value.to_string_lossy();
+
+            let value = if redact_matcher.matches(&key) {
+                hash_value(&value)
+            } else {
+                value.into_owned()
+            };
+
+            self.process_env_vars.insert(key, value);
+        }
+    }
+
     /// Hash `dependsOn` from the owning project.
     pub fn hash_project(&mut self, project: &Project) {
         self.project_deps = project.get_dependencies(); // Sorted
@@ -106,6 +192,11 @@ impl TargetHasher {
         self.command = task.command.clone();
         self.args = task.args.clone();
         self.deps = task.deps.clone();
+        self.env_vars.extend(
+            task.env
+                .iter()
+                .map(|(key, value)| (key.clone(), hash_value(value))),
+        );
         self.target = task.target.clone();
 
         // Sort vectors to be deterministic
@@ -113,6 +204,13 @@ impl TargetHasher {
         self.deps.sort();
     }
 
+    /// Hash the version of a tool that the task is ran with, as upgrading
+    /// the tool may alter the output.
+    pub fn hash_tool_version(&mut self, tool: &str, version: &str) {
+        self.tool_versions
+            .insert(tool.to_owned(), version.to_owned());
+    }
+
     /// Hash `tsconfig.json` compiler options that may alter compiled/generated output.
     pub fn hash_tsconfig_json(&mut self, tsconfig: &TsConfigJson) {
         if let Some(compiler_options) = &tsconfig.compiler_options {
@@ -164,6 +262,7 @@ impl TargetHasher {
         hash_vec(&self.deps, &mut sha);
         hash_btree(&self.env_vars, &mut sha);
         hash_btree(&self.input_hashes, &mut sha);
+        hash_btree(&self.process_env_vars, &mut sha);
 
         // Deps
         hash_vec(&self.project_deps, &mut sha);
@@ -174,6 +273,9 @@ impl TargetHasher {
         // Config
         hash_btree(&self.tsconfig_compiler_options, &mut sha);
 
+        // Platform
+        hash_btree(&self.tool_versions, &mut sha);
+
         format!("{:x}", sha.finalize())
     }
 }
@@ -265,6 +367,139 @@ mod tests {
         }
     }
 
+    mod process_env_vars {
+        use super::*;
+
+        #[test]
+        fn captures_vars_by_name() {
+            env::set_var("MOON_HASHER_NAME_FOO", "foo");
+            env::set_var("MOON_HASHER_NAME_BAR", "bar");
+
+            let mut hasher = TargetHasher::new(String::from("0.0.0"));
+            hasher.hash_process_env_vars(
+                &[
+                    String::from("MOON_HASHER_NAME_FOO"),
+                    String::from("MOON_HASHER_NAME_UNKNOWN"),
+                ],
+                &[],
+            );
+
+            assert_eq!(
+                hasher.process_env_vars,
+                BTreeMap::from([("MOON_HASHER_NAME_FOO".to_owned(), "foo".to_owned())])
+            );
+
+            env::remove_var("MOON_HASHER_NAME_FOO");
+            env::remove_var("MOON_HASHER_NAME_BAR");
+        }
+
+        #[test]
+        fn captures_vars_by_glob() {
+            env::set_var("MOON_HASHER_GLOB_FOO", "foo");
+            env::set_var("MOON_HASHER_GLOB_BAR", "bar");
+            env::set_var("MOON_HASHER_OTHER", "other");
+
+            let mut hasher = TargetHasher::new(String::from("0.0.0"));
+            hasher.hash_process_env_vars(&[String::from("MOON_HASHER_GLOB_*")], &[]);
+
+            assert_eq!(
+                hasher.process_env_vars,
+                BTreeMap::from([
+                    ("MOON_HASHER_GLOB_BAR".to_owned(), "bar".to_owned()),
+                    ("MOON_HASHER_GLOB_FOO".to_owned(), "foo".to_owned())
+                ])
+            );
+
+            env::remove_var("MOON_HASHER_GLOB_FOO");
+            env::remove_var("MOON_HASHER_GLOB_BAR");
+            env::remove_var("MOON_HASHER_OTHER");
+        }
+
+        #[test]
+        fn redacts_matching_values() {
+            env::set_var("MOON_HASHER_REDACT_TOKEN", "hunter2");
+            env::set_var("MOON_HASHER_REDACT_MODE", "production");
+
+            let mut hasher = TargetHasher::new(String::from("0.0.0"));
+            hasher.hash_process_env_vars(
+                &[String::from("MOON_HASHER_REDACT_*")],
+                &[String::from("*_TOKEN")],
+            );
+
+            assert_eq!(
+                hasher.process_env_vars,
+                BTreeMap::from([
+                    (
+                        "MOON_HASHER_REDACT_MODE".to_owned(),
+                        "production".to_owned()
+                    ),
+                    ("MOON_HASHER_REDACT_TOKEN".to_owned(), hash_value("hunter2"))
+                ])
+            );
+
+            env::remove_var("MOON_HASHER_REDACT_TOKEN");
+            env::remove_var("MOON_HASHER_REDACT_MODE");
+        }
+
+        #[test]
+        fn returns_diff_hash_for_diff_values() {
+            let patterns = [String::from("MOON_HASHER_VALUE")];
+
+            env::set_var("MOON_HASHER_VALUE", "staging");
+
+            let mut hasher1 = TargetHasher::new(String::from("0.0.0"));
+            hasher1.hash_process_env_vars(&patterns, &[]);
+
+            env::set_var("MOON_HASHER_VALUE", "production");
+
+            let mut hasher2 = TargetHasher::new(String::from("0.0.0"));
+            hasher2.hash_process_env_vars(&patterns, &[]);
+
+            assert_ne!(hasher1.to_hash(), hasher2.to_hash());
+
+            env::remove_var("MOON_HASHER_VALUE");
+        }
+    }
+
+    mod env_vars {
+        use super::*;
+        use moon_config::TaskConfig;
+        use std::collections::HashMap;
+
+        fn create_task(value: &str) -> Task {
+            Task::from_config(
+                String::from("project:task"),
+                &TaskConfig {
+                    env: Some(HashMap::from([("SECRET".to_owned(), value.to_owned())])),
+                    ..TaskConfig::default()
+                },
+            )
+        }
+
+        #[test]
+        fn doesnt_store_raw_values() {
+            let mut hasher = TargetHasher::new(String::from("0.0.0"));
+            hasher.hash_task(&create_task("hunter2"));
+
+            assert_eq!(
+                hasher.env_vars,
+                BTreeMap::from([("SECRET".to_owned(), hash_value("hunter2"))])
+            );
+            assert!(!serde_json::to_string(&hasher).unwrap().contains("hunter2"));
+        }
+
+        #[test]
+        fn returns_diff_hash_for_diff_values() {
+            let mut hasher1 = TargetHasher::new(String::from("0.0.0"));
+            hasher1.hash_task(&create_task("staging"));
+
+            let mut hasher2 = TargetHasher::new(String::from("0.0.0"));
+            hasher2.hash_task(&create_task("production"));
+
+            assert_ne!(hasher1.to_hash(), hasher2.to_hash());
+        }
+    }
+
     mod package_json {
         use super::*;
 
@@ -297,6 +532,32 @@ mod tests {
         }
     }
 
+    mod tool_versions {
+        use super::*;
+
+        #[test]
+        fn returns_diff_hash_for_diff_versions() {
+            let mut hasher1 = TargetHasher::new(String::from("0.0.0"));
+            hasher1.hash_tool_version("deno", "1.0.0");
+
+            let mut hasher2 = TargetHasher::new(String::from("0.0.0"));
+            hasher2.hash_tool_version("deno", "2.0.0");
+
+            assert_ne!(hasher1.to_hash(), hasher2.to_hash());
+        }
+
+        #[test]
+        fn doesnt_change_default_hash_when_empty() {
+            let hasher = TargetHasher::new(String::from("0.0.0"));
+
+            assert!(hasher.tool_versions.is_empty());
+            assert_eq!(
+                hasher.to_hash(),
+                String::from("ae2cf745a63ca5f47a7218ae5b4a8267295305591457a33a79c46754c1dcce0b")
+            );
+        }
+    }
+
     mod tsconfig_json {
         use super::*;
 
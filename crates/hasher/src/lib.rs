@@ -1,3 +1,5 @@
+mod diff;
 mod hasher;
 
+pub use diff::{diff_manifests, ManifestChange, ManifestDiff};
 pub use hasher::TargetHasher;
@@ -0,0 +1,114 @@
+use crate::DENO;
+use moon_lang::LangError;
+use std::env::consts;
+
+pub fn get_bin_name() -> String {
+    if cfg!(windows) {
+        format!("{}.exe", DENO.binary)
+    } else {
+        DENO.binary.to_owned()
+    }
+}
+
+pub fn get_download_file() -> Result<String, LangError> {
+    let target = match (consts::OS, consts::ARCH) {
+        ("linux", "x86_64") => "x86_64-unknown-linux-gnu",
+        ("macos", "x86_64") => "x86_64-apple-darwin",
+        ("macos", "aarch64") => "aarch64-apple-darwin",
+        ("windows", "x86_64") => "x86_64-pc-windows-msvc",
+        ("linux" | "macos" | "windows", arch) => {
+            return Err(LangError::UnsupportedArchitecture(
+                arch.to_string(),
+                String::from("Deno"),
+            ));
+        }
+        (os, _) => {
+            return Err(LangError::UnsupportedPlatform(
+                os.to_string(),
+                String::from("Deno"),
+            ));
+        }
+    };
+
+    Ok(format!("deno-{}.zip", target))
+}
+
+pub fn get_deno_url(version: &str, host: &str, path: &str) -> String {
+    format!(
+        "{host}/denoland/deno/releases/download/v{version}/{path}",
+        host = host,
+        version = version,
+        path = path,
+    )
+}
+
+/// Extract the version from the output of `deno --version`, which looks like:
+///
+/// deno 1.23.0 (release, x86_64-apple-darwin)
+/// v8 10.4.132.8
+/// typescript 4.7.2
+pub fn parse_version_output(output: &str) -> Option<String> {
+    output
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("deno "))
+        .and_then(|line| line.split_whitespace().next())
+        .map(|version| version.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod get_bin_name {
+        use super::*;
+
+        #[test]
+        #[cfg(windows)]
+        fn returns_exe() {
+            assert_eq!(get_bin_name(), "deno.exe".to_owned());
+        }
+
+        #[test]
+        #[cfg(not(windows))]
+        fn returns_bin() {
+            assert_eq!(get_bin_name(), "deno".to_owned());
+        }
+    }
+
+    mod get_deno_url {
+        use super::*;
+
+        #[test]
+        fn includes_version_and_file() {
+            assert_eq!(
+                get_deno_url(
+                    "1.23.0",
+                    "https://github.com",
+                    "deno-x86_64-unknown-linux-gnu.zip"
+                ),
+                "https://github.com/denoland/deno/releases/download/v1.23.0/deno-x86_64-unknown-linux-gnu.zip".to_owned()
+            );
+        }
+    }
+
+    mod parse_version_output {
+        use super::*;
+
+        #[test]
+        fn returns_version() {
+            assert_eq!(
+                parse_version_output(
+                    "deno 1.23.0 (release, x86_64-apple-darwin)\nv8 10.4.132.8\ntypescript 4.7.2"
+                ),
+                Some("1.23.0".to_owned())
+            );
+        }
+
+        #[test]
+        fn returns_none_for_unknown_output() {
+            assert_eq!(parse_version_output("node v16.0.0"), None);
+            assert_eq!(parse_version_output(""), None);
+        }
+    }
+}
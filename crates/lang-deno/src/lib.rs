@@ -0,0 +1,10 @@
+pub mod deno;
+
+use moon_lang::Language;
+
+pub const DENO: Language = Language {
+    binary: "deno",
+    default_version: "1.23.0",
+    vendor_bins_dir: "vendor",
+    vendor_dir: "vendor",
+};
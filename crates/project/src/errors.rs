@@ -9,12 +9,27 @@ pub enum ProjectError {
     #[error("A dependency cycle has been detected between projects.")]
     DependencyCycleDetected,
 
+    #[error(
+        "Project <id>{0}</id> cannot depend on project <id>{1}</id>, as it has been forbidden by workspace constraints."
+    )]
+    ForbiddenDependency(String, String),
+
     #[error(
         "Failed to validate <file>{0}/{}</file> configuration file.\n\n{1}",
         constants::CONFIG_PROJECT_FILENAME
     )]
     InvalidConfigFile(String, String),
 
+    #[error(
+        "Project <id>{0}</id> with tag <symbol>{1}</symbol> cannot depend on project <id>{2}</id>, as it does not have any of the tags <symbol>{3}</symbol>."
+    )]
+    InvalidTagRelationship(String, String, String, String),
+
+    #[error(
+        "Project <id>{0}</id> of type <symbol>{1}</symbol> cannot depend on project <id>{2}</id> of type <symbol>{3}</symbol>."
+    )]
+    InvalidTypeRelationship(String, String, String, String),
+
     #[error("No project exists at path <file>{0}</file>.")]
     MissingProject(String),
 
@@ -23,12 +38,24 @@ pub enum ProjectError {
     )]
     NoOutputGlob(PathBuf, String),
 
+    #[error("Dependency cycle detected between projects <id>{0}</id>.")]
+    ProjectCycleDetected(String),
+
+    #[error("Dependency cycle detected between tasks <target>{0}</target>.")]
+    TaskCycleDetected(String),
+
     #[error("No project has been configured with the ID <id>{0}</id>.")]
     UnconfiguredID(String),
 
     #[error("Task <id>{0}</id> has not been configured for project <id>{1}</id>.")]
     UnconfiguredTask(String, String),
 
+    #[error("Project <id>{0}</id> depends on unknown project <id>{1}</id>.")]
+    UnknownDependency(String, String),
+
+    #[error("Task <target>{0}</target> depends on unknown target <target>{1}</target>.")]
+    UnknownTaskDep(String, String),
+
     #[error(transparent)]
     Glob(#[from] GlobError),
 
@@ -67,6 +94,11 @@ pub enum TargetError {
 
     #[error("Project self scope (~:) is not supported in run contexts.")]
     NoProjectSelfInRunContext,
+
+    #[error(
+        "Project tag scope (#tag:) is not supported in task deps, for target <target>{0}</target>."
+    )]
+    NoProjectTagInTaskDeps(String),
 }
 
 #[derive(Error, Debug)]
@@ -85,6 +117,11 @@ pub enum TokenError {
     #[error("Token <symbol>{0}</symbol> cannot be used within <id>{1}</id>.")]
     InvalidTokenContext(String, String), // token, context
 
+    #[error(
+        "Environment variable <symbol>{1}</symbol> used in token <symbol>{0}</symbol> is not set."
+    )]
+    MissingEnvVar(String, String), // token, name
+
     #[error("No globs defined in file group <id>{0}</id>.")]
     NoGlobs(String), // file group
 
@@ -9,6 +9,7 @@ mod task;
 pub mod test;
 mod token;
 mod types;
+mod validate;
 
 pub use constants::ROOT_NODE_ID;
 pub use errors::{ProjectError, TargetError};
@@ -16,9 +17,10 @@ pub use helpers::*;
 pub use types::*;
 
 // Projects
-pub use moon_config::{ProjectID, ProjectType};
+pub use moon_config::{DependencyScope, ProjectID, ProjectType};
 pub use project::{Project, ProjectsMap};
 pub use project_graph::ProjectGraph;
+pub use validate::validate_project_references;
 
 // Tasks & targets
 pub use moon_config::{TargetID, TaskID, TaskType};
@@ -7,7 +7,8 @@ use moon_config::constants::CONFIG_PROJECT_FILENAME;
 use moon_config::package::PackageJson;
 use moon_config::tsconfig::TsConfigJson;
 use moon_config::{
-    format_figment_errors, FilePath, GlobalProjectConfig, ProjectConfig, ProjectID, TaskID,
+    format_figment_errors, DependencyScope, FilePath, GlobalProjectConfig, ProjectConfig,
+    ProjectID, ProjectType, TaskID,
 };
 use moon_logger::{color, debug, trace, Logable};
 use moon_utils::path;
@@ -23,7 +24,7 @@ pub type ProjectsMap = HashMap<ProjectID, Project>;
 pub type TasksMap = HashMap<TaskID, Task>;
 
 // project.yml
-fn load_project_config(
+pub fn load_project_config(
     log_target: &str,
     project_root: &Path,
     project_source: &str,
@@ -100,6 +101,7 @@ fn create_tasks_from_config(
     project_root: &Path,
     project_id: &str,
     file_groups: &FileGroupsMap,
+    implicit_deps: &[ProjectID],
 ) -> Result<TasksMap, ProjectError> {
     let mut tasks = HashMap::<String, Task>::new();
     let mut depends_on = vec![];
@@ -113,7 +115,7 @@ fn create_tasks_from_config(
     let mut rename: HashMap<TaskID, TaskID> = HashMap::new();
 
     if let Some(local_config) = config {
-        depends_on.extend(local_config.depends_on.clone());
+        depends_on.extend(local_config.depends_on.iter().map(|dep| dep.id().clone()));
         rename = local_config.workspace.inherited_tasks.rename.clone();
 
         if let Some(include_config) = &local_config.workspace.inherited_tasks.include {
@@ -126,6 +128,13 @@ fn create_tasks_from_config(
         }
     }
 
+    // Inferred dependencies are also used when expanding `^:` deps
+    for dep_id in implicit_deps {
+        if !depends_on.contains(dep_id) {
+            depends_on.push(dep_id.clone());
+        }
+    }
+
     // Add global tasks first while taking inheritance config into account
     for (task_id, task_config) in &global_config.tasks {
         // None = Include all
@@ -237,6 +246,11 @@ pub struct Project {
     /// Unique ID for the project. Is the LHS of the `projects` setting.
     pub id: ProjectID,
 
+    /// Dependencies inferred from the project's `package.json` and/or `tsconfig.json`,
+    /// when enabled in the workspace. Is merged with the `dependsOn` setting.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub implicit_deps: Vec<ProjectID>,
+
     /// Logging target label.
     #[serde(skip)]
     pub log_target: String,
@@ -265,6 +279,7 @@ impl Default for Project {
             config: None,
             file_groups: HashMap::new(),
             id: String::new(),
+            implicit_deps: vec![],
             log_target: String::new(),
             package_json: OnceCell::new(),
             root: PathBuf::new(),
@@ -280,6 +295,7 @@ impl PartialEq for Project {
         self.config == other.config
             && self.file_groups == other.file_groups
             && self.id == other.id
+            && self.implicit_deps == other.implicit_deps
             && self.root == other.root
             && self.source == other.source
             && self.tasks == other.tasks
@@ -298,6 +314,18 @@ impl Project {
         source: &str,
         workspace_root: &Path,
         global_config: &GlobalProjectConfig,
+    ) -> Result<Project, ProjectError> {
+        Project::new_with_implicit_deps(id, source, workspace_root, global_config, vec![])
+    }
+
+    /// Create a project with dependencies that were inferred from its `package.json`
+    /// and/or `tsconfig.json`, so that they are included when expanding `^:` task deps.
+    pub fn new_with_implicit_deps(
+        id: &str,
+        source: &str,
+        workspace_root: &Path,
+        global_config: &GlobalProjectConfig,
+        implicit_deps: Vec<ProjectID>,
     ) -> Result<Project, ProjectError> {
         let root = workspace_root.join(&path::normalize_separators(source));
         let log_target = format!("moon:project:{}", id);
@@ -324,12 +352,14 @@ impl Project {
             &root,
             id,
             &file_groups,
+            &implicit_deps,
         )?;
 
         Ok(Project {
             config,
             file_groups,
             id: String::from(id),
+            implicit_deps,
             log_target,
             package_json: OnceCell::new(),
             root,
@@ -339,19 +369,50 @@ impl Project {
         })
     }
 
-    /// Return a list of project IDs this project depends on.
+    /// Return a list of project IDs this project depends on,
+    /// both explicitly configured and implicitly inferred.
     pub fn get_dependencies(&self) -> Vec<ProjectID> {
         let mut depends_on = vec![];
 
         if let Some(config) = &self.config {
-            depends_on.extend_from_slice(&config.depends_on);
+            depends_on.extend(config.depends_on.iter().map(|dep| dep.id().clone()));
         }
 
+        depends_on.extend_from_slice(&self.implicit_deps);
         depends_on.sort();
+        depends_on.dedup();
 
         depends_on
     }
 
+    /// Return the scope in which this project depends on the provided project,
+    /// or `None` if not a dependency. Implicit dependencies are always production.
+    pub fn get_dependency_scope(&self, dep_id: &str) -> Option<DependencyScope> {
+        if let Some(config) = &self.config {
+            if let Some(dep) = config.depends_on.iter().find(|dep| dep.id() == dep_id) {
+                return Some(dep.scope());
+            }
+        }
+
+        if self.implicit_deps.iter().any(|id| id == dep_id) {
+            return Some(DependencyScope::Production);
+        }
+
+        None
+    }
+
+    /// Return true if the provided project was inferred as an implicit dependency,
+    /// and was not explicitly configured in `dependsOn`.
+    pub fn is_implicit_dependency(&self, dep_id: &str) -> bool {
+        if let Some(config) = &self.config {
+            if config.depends_on.iter().any(|dep| dep.id() == dep_id) {
+                return false;
+            }
+        }
+
+        self.implicit_deps.iter().any(|id| id == dep_id)
+    }
+
     /// Return the "package.json" name, if the file exists.
     pub async fn get_package_name(&self) -> Result<Option<String>, ProjectError> {
         self.load_package_json().await?;
@@ -365,6 +426,23 @@ impl Project {
         Ok(None)
     }
 
+    /// Return the type of project, falling back to the default type
+    /// when a project config has not been defined.
+    pub fn get_type(&self) -> ProjectType {
+        match &self.config {
+            Some(config) => config.type_of.clone(),
+            None => ProjectType::default(),
+        }
+    }
+
+    /// Return true if the project has been configured with the provided tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        match &self.config {
+            Some(config) => config.tags.iter().any(|t| t == tag),
+            None => false,
+        }
+    }
+
     /// Return a task with the defined ID.
     pub fn get_task(&self, task_id: &str) -> Result<&Task, ProjectError> {
         match self.tasks.get(task_id) {
@@ -376,6 +454,24 @@ impl Project {
         }
     }
 
+    /// Return a copy of the task with the defined ID, with variables from its env files
+    /// loaded and its environment variable tokens expanded, so that it can be ran.
+    /// Env files are not loaded with the project, so that their values (which may be
+    /// secrets) are not serialized into runfiles or `moon project` output.
+    pub fn get_runnable_task(
+        &self,
+        task_id: &str,
+        workspace_root: &Path,
+    ) -> Result<Task, ProjectError> {
+        let mut task = self.get_task(task_id)?.clone();
+        let data = TokenSharedData::new(&self.file_groups, workspace_root, &self.root);
+
+        task.expand_env(workspace_root, &self.root)?;
+        task.expand_env_args(TokenResolver::for_args(&data))?;
+
+        Ok(task)
+    }
+
     /// Load and parse the package's `package.json` if it exists.
     #[track_caller]
     pub async fn load_package_json(&self) -> Result<bool, ProjectError> {
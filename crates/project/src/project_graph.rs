@@ -1,19 +1,24 @@
 use crate::constants::ROOT_NODE_ID;
-use crate::errors::ProjectError;
+use crate::errors::{ProjectError, TargetError};
 use crate::helpers::detect_projects_with_globs;
-use crate::project::Project;
+use crate::project::{load_project_config, Project};
+use crate::target::Target;
 use crate::types::{ProjectsSourceMap, TouchedFilePaths};
 use moon_cache::CacheEngine;
 use moon_config::constants::{
     CONFIG_DIRNAME, CONFIG_PROJECT_FILENAME, CONFIG_WORKSPACE_FILENAME, FLAG_PROJECTS_USING_GLOB,
 };
-use moon_config::{GlobalProjectConfig, ProjectID};
+use moon_config::package::PackageJson;
+use moon_config::tsconfig::TsConfigJson;
+use moon_config::{ConstraintsConfig, DependencyScope, GlobalProjectConfig, ProjectID};
 use moon_logger::{color, debug, map_list, trace};
+use moon_utils::glob::GlobSet;
+use moon_utils::path;
 use petgraph::dot::{Config, Dot};
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock, RwLockWriteGuard};
 
@@ -75,6 +80,10 @@ async fn load_projects_from_cache(
 }
 
 pub struct ProjectGraph {
+    /// Rules that dependency edges between projects must adhere to.
+    /// Is the `constraints` setting in `.moon/workspace.yml`.
+    constraints: ConstraintsConfig,
+
     /// The global project configuration that all projects inherit from.
     /// Is loaded from `.moon/project.yml`.
     global_config: GlobalProjectConfig,
@@ -86,6 +95,10 @@ pub struct ProjectGraph {
     /// to query the graph by ID as it only supports it by index.
     indices: Arc<RwLock<IndicesType>>,
 
+    /// Mapping of project IDs to dependencies that were inferred
+    /// from `package.json` and `tsconfig.json` files.
+    implicit_deps: HashMap<ProjectID, Vec<ProjectID>>,
+
     /// The mapping of projects by ID to a relative file system location.
     /// Is the `projects` setting in `.moon/workspace.yml`.
     projects_config: HashMap<ProjectID, String>,
@@ -118,9 +131,11 @@ impl ProjectGraph {
         });
 
         Ok(ProjectGraph {
+            constraints: ConstraintsConfig::default(),
             global_config,
             graph: Arc::new(RwLock::new(graph)),
             indices: Arc::new(RwLock::new(HashMap::new())),
+            implicit_deps: HashMap::new(),
             projects_config: load_projects_from_cache(workspace_root, projects_config, cache)
                 .await?,
             workspace_root: workspace_root.to_path_buf(),
@@ -134,6 +149,196 @@ impl ProjectGraph {
         nodes
     }
 
+    /// Return a list of project IDs that have been configured with the provided tag,
+    /// in ascending order. Projects are *not* loaded into the graph to determine this,
+    /// only their configs are read, so that tasks are not expanded.
+    pub fn get_ids_with_tag(&self, tag: &str) -> Result<Vec<ProjectID>, ProjectError> {
+        let mut ids = vec![];
+
+        for (id, source) in &self.projects_config {
+            let root = self.workspace_root.join(path::normalize_separators(source));
+            let config = load_project_config(&format!("moon:project:{}", id), &root, source)?;
+
+            if let Some(config) = config {
+                if config.tags.iter().any(|t| t == tag) {
+                    ids.push(id.clone());
+                }
+            }
+        }
+
+        ids.sort();
+
+        Ok(ids)
+    }
+
+    /// Return the source (file path relative from the workspace root)
+    /// of a configured project, without loading the project.
+    pub fn get_source(&self, id: &str) -> Option<&str> {
+        self.projects_config.get(id).map(|source| source.as_str())
+    }
+
+    /// Set the constraints that are checked against each dependency edge
+    /// as projects are loaded into the graph.
+    pub fn set_constraints(&mut self, constraints: ConstraintsConfig) {
+        self.constraints = constraints;
+    }
+
+    /// Infer implicit dependencies for all configured projects, by matching
+    /// `package.json` dependencies against the package names of other projects,
+    /// and/or `tsconfig.json` project references against the roots of other projects.
+    /// Must be called before projects are loaded into the graph.
+    pub async fn infer_implicit_dependencies(
+        &mut self,
+        from_package_json: bool,
+        tsconfig_name: Option<&str>,
+    ) -> Result<(), ProjectError> {
+        let mut package_names = HashMap::<String, ProjectID>::new();
+        let mut package_deps = HashMap::<ProjectID, Vec<String>>::new();
+        let mut roots = HashMap::<PathBuf, ProjectID>::new();
+        let mut implicit_deps = HashMap::<ProjectID, Vec<ProjectID>>::new();
+
+        for id in self.ids() {
+            let root = path::normalize(
+                &self
+                    .workspace_root
+                    .join(path::normalize_separators(&self.projects_config[&id])),
+            );
+            let package_path = root.join("package.json");
+
+            if from_package_json && package_path.exists() {
+                let package = PackageJson::load(&package_path).await?;
+
+                if let Some(name) = &package.name {
+                    package_names.insert(name.clone(), id.clone());
+                }
+
+                package_deps.insert(
+                    id.clone(),
+                    [
+                        &package.dependencies,
+                        &package.dev_dependencies,
+                        &package.peer_dependencies,
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|deps| deps.keys().cloned())
+                    .collect(),
+                );
+            }
+
+            roots.insert(root, id);
+        }
+
+        for (id, dep_names) in package_deps {
+            let deps = implicit_deps.entry(id).or_default();
+
+            for dep_name in dep_names {
+                if let Some(dep_id) = package_names.get(&dep_name) {
+                    deps.push(dep_id.clone());
+                }
+            }
+        }
+
+        if let Some(tsconfig_name) = tsconfig_name {
+            for (root, id) in &roots {
+                let tsconfig_path = root.join(tsconfig_name);
+
+                if !tsconfig_path.exists() {
+                    continue;
+                }
+
+                let tsconfig = TsConfigJson::load(&tsconfig_path).await?;
+                let deps = implicit_deps.entry(id.clone()).or_default();
+
+                for reference in tsconfig.references.unwrap_or_default() {
+                    let mut ref_root = path::normalize(&root.join(&reference.path));
+
+                    // References may point to a specific config file
+                    if ref_root
+                        .extension()
+                        .map(|ext| ext == "json")
+                        .unwrap_or(false)
+                    {
+                        ref_root.pop();
+                    }
+
+                    if let Some(dep_id) = roots.get(&ref_root) {
+                        deps.push(dep_id.clone());
+                    }
+                }
+            }
+        }
+
+        for (id, deps) in implicit_deps.iter_mut() {
+            deps.retain(|dep_id| dep_id != id);
+            deps.sort();
+            deps.dedup();
+
+            if !deps.is_empty() {
+                trace!(
+                    target: LOG_TARGET,
+                    "Inferred implicit dependencies {} for project {}",
+                    map_list(deps, |d| color::symbol(d)),
+                    color::id(id),
+                );
+            }
+        }
+
+        self.implicit_deps = implicit_deps;
+
+        Ok(())
+    }
+
+    /// Expand a target that contains globs in the project and/or task position,
+    /// for example `app-*:build` or `lib:*`, into a list of concrete targets.
+    /// An empty project position matches all projects, like the `:task` scope.
+    /// Targets are returned in ascending order.
+    pub fn expand_target_glob(&self, pattern: &str) -> Result<Vec<Target>, ProjectError> {
+        let (project_pattern, task_pattern) = match pattern.split_once(':') {
+            Some(parts) => parts,
+            None => {
+                return Err(ProjectError::Target(TargetError::InvalidFormat(
+                    pattern.to_owned(),
+                )))
+            }
+        };
+
+        let project_patterns = vec![if project_pattern.is_empty() {
+            String::from("*")
+        } else {
+            project_pattern.to_owned()
+        }];
+        let task_patterns = vec![task_pattern.to_owned()];
+        let project_globs = GlobSet::new(&project_patterns)?;
+        let task_globs = GlobSet::new(&task_patterns)?;
+        let mut targets = vec![];
+
+        for project_id in self.ids() {
+            if !project_globs.matches(Path::new(&project_id))? {
+                continue;
+            }
+
+            let project = self.load(&project_id)?;
+            let mut task_ids = project.tasks.keys().collect::<Vec<_>>();
+            task_ids.sort();
+
+            for task_id in task_ids {
+                if task_globs.matches(Path::new(task_id))? {
+                    targets.push(Target::new(&project_id, task_id)?);
+                }
+            }
+        }
+
+        trace!(
+            target: LOG_TARGET,
+            "Expanded target glob {} to {}",
+            color::target(pattern),
+            map_list(&targets, |t| color::target(&t.id)),
+        );
+
+        Ok(targets)
+    }
+
     /// Return a project with the associated ID. If the project
     /// has not been loaded, it will be loaded and inserted into the
     /// project graph. If the project does not exist or has been
@@ -189,6 +394,68 @@ impl ProjectGraph {
         Ok(deps)
     }
 
+    /// Return a list of project IDs that require the defined project,
+    /// excluding projects that only require it as a development dependency.
+    #[track_caller]
+    pub fn get_non_dev_dependents_of(
+        &self,
+        project: &Project,
+    ) -> Result<Vec<ProjectID>, ProjectError> {
+        let indices = self.indices.read().expect(READ_ERROR);
+        let graph = self.graph.read().expect(READ_ERROR);
+
+        let deps = graph
+            .neighbors_directed(*indices.get(&project.id).unwrap(), Direction::Incoming)
+            .map(|idx| graph.node_weight(idx).unwrap())
+            .filter(|dependent| {
+                dependent.id != ROOT_NODE_ID
+                    && dependent.get_dependency_scope(&project.id)
+                        != Some(DependencyScope::Development)
+            })
+            .map(|dependent| dependent.id.clone())
+            .collect();
+
+        Ok(deps)
+    }
+
+    /// Return a list of project IDs that the defined project depends on, both directly
+    /// and transitively, in ascending order. When a depth is provided, only projects
+    /// within that many hops are returned (1 being direct dependencies only).
+    #[track_caller]
+    pub fn get_transitive_dependencies_of(
+        &self,
+        project: &Project,
+        depth: Option<usize>,
+    ) -> Result<Vec<ProjectID>, ProjectError> {
+        self.walk_transitive_edges(project, Direction::Outgoing, depth, false)
+    }
+
+    /// Return a list of project IDs that require the defined project, both directly
+    /// and transitively, in ascending order. When a depth is provided, only projects
+    /// within that many hops are returned (1 being direct dependents only).
+    /// All configured projects are loaded into the graph to determine this.
+    #[track_caller]
+    pub fn get_transitive_dependents_of(
+        &self,
+        project: &Project,
+        depth: Option<usize>,
+    ) -> Result<Vec<ProjectID>, ProjectError> {
+        self.load_all()?;
+        self.walk_transitive_edges(project, Direction::Incoming, depth, false)
+    }
+
+    /// Like `get_transitive_dependents_of`, but excludes projects that only require
+    /// the defined project (or another dependent) as a development dependency.
+    #[track_caller]
+    pub fn get_transitive_non_dev_dependents_of(
+        &self,
+        project: &Project,
+        depth: Option<usize>,
+    ) -> Result<Vec<ProjectID>, ProjectError> {
+        self.load_all()?;
+        self.walk_transitive_edges(project, Direction::Incoming, depth, true)
+    }
+
     /// Return true if global config files have been touched.
     pub fn is_globally_affected(&self, touched_files: &TouchedFilePaths) -> bool {
         let cfg_dir = self.workspace_root.join(CONFIG_DIRNAME);
@@ -242,6 +509,106 @@ impl ProjectGraph {
         format!("{:?}", dot)
     }
 
+    /// Load all configured projects (and their dependencies) into the graph.
+    fn load_all(&self) -> Result<(), ProjectError> {
+        for id in self.ids() {
+            self.load(&id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Walk the graph breadth-first from the defined project in the provided
+    /// direction, and return the IDs of all projects that were reached.
+    fn walk_transitive_edges(
+        &self,
+        project: &Project,
+        direction: Direction,
+        depth: Option<usize>,
+        skip_dev_edges: bool,
+    ) -> Result<Vec<ProjectID>, ProjectError> {
+        let indices = self.indices.read().expect(READ_ERROR);
+        let graph = self.graph.read().expect(READ_ERROR);
+        let start_index = *indices.get(&project.id).unwrap();
+
+        let mut ids = vec![];
+        let mut visited = HashSet::from([start_index]);
+        let mut queue = VecDeque::from([(start_index, 0)]);
+
+        while let Some((index, level)) = queue.pop_front() {
+            if matches!(depth, Some(max) if level >= max) {
+                continue;
+            }
+
+            let current = graph.node_weight(index).unwrap();
+
+            for neighbor_index in graph.neighbors_directed(index, direction) {
+                let neighbor = graph.node_weight(neighbor_index).unwrap();
+
+                if neighbor.id == ROOT_NODE_ID || visited.contains(&neighbor_index) {
+                    continue;
+                }
+
+                if skip_dev_edges
+                    && neighbor.get_dependency_scope(&current.id)
+                        == Some(DependencyScope::Development)
+                {
+                    continue;
+                }
+
+                visited.insert(neighbor_index);
+                ids.push(neighbor.id.clone());
+                queue.push_back((neighbor_index, level + 1));
+            }
+        }
+
+        ids.sort();
+
+        Ok(ids)
+    }
+
+    /// Verify that a project is allowed to depend on another project,
+    /// based on the forbidden, tag, and type rules of the workspace constraints.
+    fn check_constraints(&self, project: &Project, dep: &Project) -> Result<(), ProjectError> {
+        if self.constraints.forbidden_dependencies.contains(&dep.id) {
+            return Err(ProjectError::ForbiddenDependency(
+                project.id.clone(),
+                dep.id.clone(),
+            ));
+        }
+
+        if let Some(config) = &project.config {
+            for tag in &config.tags {
+                if let Some(allowed_tags) = self.constraints.tag_relationships.get(tag) {
+                    if !allowed_tags.iter().any(|t| dep.has_tag(t)) {
+                        return Err(ProjectError::InvalidTagRelationship(
+                            project.id.clone(),
+                            tag.clone(),
+                            dep.id.clone(),
+                            allowed_tags.join(", "),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let project_type = project.get_type();
+        let dep_type = dep.get_type();
+
+        if let Some(allowed_types) = self.constraints.type_relationships.get(&project_type) {
+            if !allowed_types.contains(&dep_type) {
+                return Err(ProjectError::InvalidTypeRelationship(
+                    project.id.clone(),
+                    format!("{:?}", project_type).to_lowercase(),
+                    dep.id.clone(),
+                    format!("{:?}", dep_type).to_lowercase(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Internal method for lazily loading a project and its
     /// dependencies into the graph.
     fn internal_load(
@@ -273,7 +640,14 @@ impl ProjectGraph {
             None => return Err(ProjectError::UnconfiguredID(String::from(id))),
         };
 
-        let project = Project::new(id, source, &self.workspace_root, &self.global_config)?;
+        let project = Project::new_with_implicit_deps(
+            id,
+            source,
+            &self.workspace_root,
+            &self.global_config,
+            self.implicit_deps.get(id).cloned().unwrap_or_default(),
+        )?;
+
         let depends_on = project.get_dependencies();
 
         // Insert the project into the graph
@@ -291,6 +665,7 @@ impl ProjectGraph {
 
             for dep_id in depends_on {
                 let dep_index = self.internal_load(dep_id.as_str(), indices, graph)?;
+                self.check_constraints(&graph[node_index], &graph[dep_index])?;
                 graph.add_edge(node_index, dep_index, ());
             }
         }
@@ -10,6 +10,7 @@ pub enum TargetProject {
     Deps,          // ^:task
     Id(ProjectID), // project:task
     Own,           // ~:task
+    Tag(String),   // #tag:task
 }
 
 // impl fmt::Display for TargetProject {
@@ -84,10 +85,13 @@ impl Target {
                 "" => TargetProject::All,
                 "^" => TargetProject::Deps,
                 "~" => TargetProject::Own,
-                id => {
-                    project_id = Some(id.to_owned());
-                    TargetProject::Id(id.to_owned())
-                }
+                id => match id.strip_prefix('#') {
+                    Some(tag) => TargetProject::Tag(tag.to_owned()),
+                    None => {
+                        project_id = Some(id.to_owned());
+                        TargetProject::Id(id.to_owned())
+                    }
+                },
             },
             None => TargetProject::All,
         };
@@ -207,6 +211,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_tag_project() {
+        assert_eq!(
+            Target::parse("#frontend:lint").unwrap(),
+            Target {
+                id: String::from("#frontend:lint"),
+                project: TargetProject::Tag("frontend".to_owned()),
+                project_id: None,
+                task_id: "lint".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidFormat")]
+    fn parse_empty_tag() {
+        Target::parse("#:lint").unwrap();
+    }
+
     // #[test]
     // fn parse_self_project_all_tasks() {
     //     assert_eq!(
@@ -3,16 +3,25 @@ use crate::target::{Target, TargetProject};
 use crate::token::TokenResolver;
 use crate::types::{EnvVars, ExpandedFiles, TouchedFilePaths};
 use moon_config::{
-    FilePath, FilePathOrGlob, TargetID, TaskConfig, TaskMergeStrategy, TaskOptionsConfig, TaskType,
+    FilePath, FilePathOrGlob, TargetID, TaskConfig, TaskMergeStrategy, TaskOptionEnvFile,
+    TaskOptionsConfig, TaskReadyCheckConfig, TaskType,
 };
+use moon_error::map_io_to_fs_error;
 use moon_logger::{color, debug, map_list, trace, Logable};
-use moon_utils::{glob, path, string_vec};
+use moon_utils::path::expand_root_path;
+use moon_utils::{dotenv, glob, path, string_vec};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskOptions {
+    pub env_file: Option<TaskOptionEnvFile>,
+
+    pub hash_env_vars: Vec<String>,
+
     pub merge_args: TaskMergeStrategy,
 
     pub merge_deps: TaskMergeStrategy,
@@ -23,15 +32,29 @@ pub struct TaskOptions {
 
     pub merge_outputs: TaskMergeStrategy,
 
+    pub persistent: bool,
+
+    pub ready_check: Option<TaskReadyCheckConfig>,
+
     pub retry_count: u8,
 
     pub run_in_ci: bool,
 
     pub run_from_workspace_root: bool,
+
+    pub timeout: Option<u64>,
 }
 
 impl TaskOptions {
     pub fn merge(&mut self, config: &TaskOptionsConfig) {
+        if let Some(env_file) = &config.env_file {
+            self.env_file = Some(env_file.clone());
+        }
+
+        if let Some(hash_env_vars) = &config.hash_env_vars {
+            self.hash_env_vars = hash_env_vars.clone();
+        }
+
         if let Some(merge_args) = &config.merge_args {
             self.merge_args = merge_args.clone();
         }
@@ -52,6 +75,14 @@ impl TaskOptions {
             self.merge_outputs = merge_outputs.clone();
         }
 
+        if let Some(persistent) = &config.persistent {
+            self.persistent = *persistent;
+        }
+
+        if let Some(ready_check) = &config.ready_check {
+            self.ready_check = Some(ready_check.clone());
+        }
+
         if let Some(retry_count) = &config.retry_count {
             self.retry_count = *retry_count;
         }
@@ -63,6 +94,10 @@ impl TaskOptions {
         if let Some(run_from_workspace_root) = &config.run_from_workspace_root {
             self.run_from_workspace_root = *run_from_workspace_root;
         }
+
+        if let Some(timeout) = &config.timeout {
+            self.timeout = Some(*timeout);
+        }
     }
 }
 
@@ -71,6 +106,10 @@ impl TaskOptions {
 pub struct Task {
     pub args: Vec<String>,
 
+    /// Directories resolved from `@cache` tokens, that are created before the task is ran.
+    #[serde(skip)]
+    pub cache_dirs: Vec<PathBuf>,
+
     pub command: String,
 
     pub deps: Vec<TargetID>,
@@ -114,6 +153,7 @@ impl Task {
 
         let task = Task {
             args: cloned_config.args.unwrap_or_default(),
+            cache_dirs: vec![],
             command,
             deps: cloned_config.deps.unwrap_or_default(),
             env: cloned_config.env.unwrap_or_default(),
@@ -122,14 +162,19 @@ impl Task {
             input_paths: HashSet::new(),
             log_target,
             options: TaskOptions {
+                env_file: cloned_options.env_file,
+                hash_env_vars: cloned_options.hash_env_vars.unwrap_or_default(),
                 merge_args: cloned_options.merge_args.unwrap_or_default(),
                 merge_deps: cloned_options.merge_deps.unwrap_or_default(),
                 merge_env: cloned_options.merge_env.unwrap_or_default(),
                 merge_inputs: cloned_options.merge_inputs.unwrap_or_default(),
                 merge_outputs: cloned_options.merge_outputs.unwrap_or_default(),
+                persistent: cloned_options.persistent.unwrap_or_default(),
+                ready_check: cloned_options.ready_check,
                 retry_count: cloned_options.retry_count.unwrap_or_default(),
                 run_in_ci: cloned_options.run_in_ci.unwrap_or(!is_long_running),
                 run_from_workspace_root: cloned_options.run_from_workspace_root.unwrap_or_default(),
+                timeout: cloned_options.timeout,
             },
             outputs: cloned_config.outputs.unwrap_or_default(),
             output_paths: HashSet::new(),
@@ -153,19 +198,35 @@ impl Task {
     }
 
     /// Expand the args list to resolve tokens, relative to the project root.
+    /// Environment variable tokens are resolved later with `expand_env_args`.
     pub fn expand_args(&mut self, token_resolver: TokenResolver) -> Result<(), ProjectError> {
         if self.args.is_empty() {
             return Ok(());
         }
 
         let mut args: Vec<String> = vec![];
+        let mut cache_dirs: Vec<PathBuf> = vec![];
         let run_in_project = !self.options.run_from_workspace_root;
 
         // We cant use `TokenResolver.resolve` as args are a mix of strings,
         // strings with tokens, and file paths when tokens are resolved.
         for arg in &self.args {
             if token_resolver.has_token_func(arg) {
-                for resolved_arg in token_resolver.resolve_func(arg, Some(self))? {
+                let func = token_resolver.get_token_func_name(arg);
+
+                if matches!(func, Some("env" | "envs")) {
+                    args.push(arg.clone());
+
+                    continue;
+                }
+
+                let resolved_args = token_resolver.resolve_func(arg, Some(self))?;
+
+                if func == Some("cache") {
+                    cache_dirs.extend(resolved_args.iter().cloned());
+                }
+
+                for resolved_arg in resolved_args {
                     // When running within a project:
                     //  - Project paths are relative and start with "./"
                     //  - Workspace paths are absolute
@@ -193,6 +254,7 @@ impl Task {
         }
 
         self.args = args;
+        self.cache_dirs = cache_dirs;
 
         Ok(())
     }
@@ -234,6 +296,10 @@ impl Task {
                 TargetProject::Id(_) => {
                     push_dep(dep.clone());
                 }
+                // #tag:task
+                TargetProject::Tag(_) => {
+                    target.fail_with(TargetError::NoProjectTagInTaskDeps(target.id.clone()))?;
+                }
                 _ => {
                     target.fail_with(TargetError::NoProjectAllInTaskDeps(target.id.clone()))?;
                 }
@@ -245,6 +311,84 @@ impl Task {
         Ok(())
     }
 
+    /// Load variables from the configured env files, and merge them *under*
+    /// the explicitly configured env vars, so that the latter take precedence.
+    pub fn expand_env(
+        &mut self,
+        workspace_root: &Path,
+        project_root: &Path,
+    ) -> Result<(), ProjectError> {
+        let env_files = match &self.options.env_file {
+            Some(env_file) => env_file.to_files(),
+            None => return Ok(()),
+        };
+
+        let mut env = EnvVars::new();
+
+        for env_file in env_files {
+            let env_path = expand_root_path(&env_file, workspace_root, project_root);
+
+            // Local env files are typically gitignored, so may not exist
+            if !env_path.exists() {
+                trace!(
+                    target: &self.log_target,
+                    "Env file {} does not exist, skipping",
+                    color::path(&env_path)
+                );
+
+                continue;
+            }
+
+            trace!(
+                target: &self.log_target,
+                "Loading env vars from {}",
+                color::path(&env_path)
+            );
+
+            let content =
+                fs::read_to_string(&env_path).map_err(|e| map_io_to_fs_error(e, env_path))?;
+
+            // Pass the accumulated vars so that later files (`.env.local`)
+            // can interpolate variables defined in earlier files
+            env = dotenv::parse_with_vars(&content, env);
+        }
+
+        env.extend(self.env.drain());
+        self.env = env;
+
+        Ok(())
+    }
+
+    /// Expand environment variable tokens (`@env`, `@envs`, and `$VAR`) in the args.
+    /// These are expanded right before the task is ran, instead of when the project
+    /// is loaded, so that a missing variable only fails the task being ran.
+    pub fn expand_env_args(&mut self, token_resolver: TokenResolver) -> Result<(), ProjectError> {
+        if self.args.is_empty() {
+            return Ok(());
+        }
+
+        let mut args: Vec<String> = vec![];
+
+        for arg in &self.args {
+            if matches!(
+                token_resolver.get_token_func_name(arg),
+                Some("env" | "envs")
+            ) {
+                for resolved_arg in token_resolver.resolve_func(arg, Some(self))? {
+                    args.push(String::from(resolved_arg.to_string_lossy()));
+                }
+            } else if token_resolver.has_token_var(arg) {
+                args.push(token_resolver.resolve_env_var(arg, self)?);
+            } else {
+                args.push(arg.clone());
+            }
+        }
+
+        self.args = args;
+
+        Ok(())
+    }
+
     /// Expand the inputs list to a set of absolute file paths, while resolving tokens.
     pub fn expand_inputs(&mut self, token_resolver: TokenResolver) -> Result<(), ProjectError> {
         if self.inputs.is_empty() {
@@ -547,4 +691,100 @@ mod tests {
             assert!(!task.is_affected(&set).unwrap());
         }
     }
+    mod expand_args {
+        use super::*;
+
+        #[test]
+        fn records_cache_dirs() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    args: Some(string_vec!["--cache-location", "@cache(eslint)"]),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            assert_eq!(
+                task.cache_dirs,
+                vec![workspace_root.join(".moon/cache/tasks/project/task/eslint")]
+            );
+        }
+
+        #[test]
+        fn doesnt_expand_env_tokens() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    args: Some(string_vec!["@env(MOON_TASK_MISSING)", "$MOON_TASK_MISSING"]),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            assert_eq!(
+                task.args,
+                string_vec!["@env(MOON_TASK_MISSING)", "$MOON_TASK_MISSING"]
+            );
+        }
+    }
+
+    mod expand_env_args {
+        use super::*;
+        use crate::test::create_file_groups;
+        use crate::token::{TokenResolver, TokenSharedData};
+        use std::collections::HashMap;
+
+        #[test]
+        fn expands_env_tokens() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let mut task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    args: Some(string_vec!["--mode", "@env(MODE)", "--target=$MODE-$task"]),
+                    env: Some(HashMap::from([(String::from("MODE"), String::from("ci"))])),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            task.expand_env_args(TokenResolver::for_args(&metadata))
+                .unwrap();
+
+            assert_eq!(task.args, string_vec!["--mode", "ci", "--target=ci-task"]);
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "MissingEnvVar(\"@env(MOON_TASK_MISSING)\", \"MOON_TASK_MISSING\")"
+        )]
+        fn errors_for_missing_env() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let mut task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    args: Some(string_vec!["@env(MOON_TASK_MISSING)"]),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            task.expand_env_args(TokenResolver::for_args(&metadata))
+                .unwrap();
+        }
+    }
 }
@@ -85,6 +85,7 @@ pub fn create_expanded_task(
     let file_groups = create_file_groups();
     let metadata = TokenSharedData::new(&file_groups, workspace_root, project_root);
 
+    task.expand_env(workspace_root, project_root)?;
     task.expand_inputs(TokenResolver::for_inputs(&metadata))?;
     task.expand_outputs(TokenResolver::for_outputs(&metadata))?;
     task.expand_args(TokenResolver::for_args(&metadata))?; // Must be last
@@ -2,14 +2,16 @@ use crate::errors::{ProjectError, TokenError};
 use crate::file_group::FileGroup;
 use crate::target::Target;
 use crate::task::Task;
+use moon_config::constants::CONFIG_DIRNAME;
 use moon_logger::{color, warn};
 use moon_utils::glob;
 use moon_utils::path::expand_root_path;
 use moon_utils::regex::{
-    matches_token_func, matches_token_var, TOKEN_FUNC_ANYWHERE_PATTERN, TOKEN_FUNC_PATTERN,
-    TOKEN_VAR_PATTERN,
+    matches_token_func, matches_token_var, Captures, TOKEN_FUNC_ANYWHERE_PATTERN,
+    TOKEN_FUNC_PATTERN, TOKEN_VAR_PATTERN,
 };
 use std::collections::HashMap;
+use std::env;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, PartialEq)]
@@ -33,6 +35,13 @@ impl ResolverType {
 pub enum TokenType {
     Var(String),
 
+    // Cache: token, name
+    Cache(String, String),
+
+    // Environment variables: token, name or prefix
+    Env(String, String),
+    Envs(String, String),
+
     // File groups: token, group name
     Dirs(String, String),
     Files(String, String),
@@ -47,9 +56,18 @@ pub enum TokenType {
 impl TokenType {
     pub fn check_context(&self, context: &ResolverType) -> Result<(), ProjectError> {
         let allowed = match self {
+            TokenType::Cache(_, _) => {
+                matches!(context, ResolverType::Args)
+            }
             TokenType::Dirs(_, _) => {
                 matches!(context, ResolverType::Args) || matches!(context, ResolverType::Inputs)
             }
+            TokenType::Env(_, _) => {
+                matches!(context, ResolverType::Args)
+            }
+            TokenType::Envs(_, _) => {
+                matches!(context, ResolverType::Args)
+            }
             TokenType::Files(_, _) => {
                 matches!(context, ResolverType::Args) || matches!(context, ResolverType::Inputs)
             }
@@ -82,7 +100,10 @@ impl TokenType {
 
     pub fn token_label(&self) -> String {
         String::from(match self {
+            TokenType::Cache(_, _) => "@cache",
             TokenType::Dirs(_, _) => "@dirs",
+            TokenType::Env(_, _) => "@env",
+            TokenType::Envs(_, _) => "@envs",
             TokenType::Files(_, _) => "@files",
             TokenType::Globs(_, _) => "@globs",
             TokenType::In(_, _) => "@in",
@@ -159,6 +180,14 @@ impl<'a> TokenResolver<'a> {
         false
     }
 
+    /// Return the name of the token function (without the `@`), if the value is one.
+    pub fn get_token_func_name<'v>(&self, value: &'v str) -> Option<&'v str> {
+        TOKEN_FUNC_PATTERN
+            .captures(value)
+            .and_then(|matches| matches.get(1))
+            .map(|func| func.as_str())
+    }
+
     pub fn has_token_var(&self, value: &str) -> bool {
         value.contains('$') && matches_token_var(value)
     }
@@ -210,9 +239,18 @@ impl<'a> TokenResolver<'a> {
         // );
 
         match func {
+            "cache" => {
+                self.replace_cache_token(TokenType::Cache(token.to_owned(), arg.to_owned()), task)
+            }
             "dirs" => {
                 self.replace_file_group_tokens(TokenType::Dirs(token.to_owned(), arg.to_owned()))
             }
+            "env" => {
+                self.replace_env_tokens(TokenType::Env(token.to_owned(), arg.to_owned()), task)
+            }
+            "envs" => {
+                self.replace_env_tokens(TokenType::Envs(token.to_owned(), arg.to_owned()), task)
+            }
             "files" => {
                 self.replace_file_group_tokens(TokenType::Files(token.to_owned(), arg.to_owned()))
             }
@@ -242,47 +280,63 @@ impl<'a> TokenResolver<'a> {
         }
     }
 
+    /// Resolve variables for the project and task. Environment variables are left
+    /// as-is, as they are resolved when the task is ran (`resolve_env_var`).
     pub fn resolve_var(&self, value: &str, task: &Task) -> Result<String, ProjectError> {
-        let matches = TOKEN_VAR_PATTERN.captures(value).unwrap();
-        let token = matches.get(0).unwrap().as_str(); // $var
-        let var = matches.get(1).unwrap().as_str(); // var
-
-        // trace!(
-        //     target: "moon:token",
-        //     "Resolving token variable {} for {} value {}",
-        //     color::id(token),
-        //     self.context.context_label(),
-        //     color::file(value)
-        // );
+        TokenType::Var(String::new()).check_context(&self.context)?;
 
         let (project_id, task_id) = Target::parse(&task.target)?.ids()?;
         let workspace_root = self.data.workspace_root;
         let project_root = self.data.project_root;
 
-        let var_value = match var {
-            "project" => project_id,
-            "projectRoot" => String::from(project_root.to_string_lossy()),
-            "projectSource" => String::from(
-                project_root
-                    .strip_prefix(workspace_root)
-                    .unwrap()
-                    .to_string_lossy(),
-            ),
-            "target" => task.target.clone(),
-            "task" => task_id,
-            "workspaceRoot" => String::from(workspace_root.to_string_lossy()),
-            _ => {
-                warn!(
-                    target: "moon:project:token",
-                    "Found a token variable in \"{}\" that is not supported, but this may be intentional, so leaving it.",
-                    value
-                );
+        let result = TOKEN_VAR_PATTERN.replace_all(value, |matches: &Captures| {
+            let token = matches.get(0).unwrap().as_str(); // $var
+            let var = matches.get(1).unwrap().as_str(); // var
+
+            match var {
+                "project" => project_id.clone(),
+                "projectRoot" => String::from(project_root.to_string_lossy()),
+                "projectSource" => String::from(
+                    project_root
+                        .strip_prefix(workspace_root)
+                        .unwrap()
+                        .to_string_lossy(),
+                ),
+                "target" => task.target.clone(),
+                "task" => task_id.clone(),
+                "workspaceRoot" => String::from(workspace_root.to_string_lossy()),
+                _ => token.to_owned(),
+            }
+        });
+
+        Ok(result.into_owned())
+    }
+
+    /// Resolve environment variables, with the task taking precedence over the
+    /// current process. Unknown variables are left as-is.
+    pub fn resolve_env_var(&self, value: &str, task: &Task) -> Result<String, ProjectError> {
+        TokenType::Var(String::new()).check_context(&self.context)?;
+
+        let result = TOKEN_VAR_PATTERN.replace_all(value, |matches: &Captures| {
+            let token = matches.get(0).unwrap().as_str(); // $VAR
+            let var = matches.get(1).unwrap().as_str(); // VAR
 
-                return Ok(String::from(value));
+            match task.env.get(var).cloned().or_else(|| env::var(var).ok()) {
+                Some(env_value) => env_value,
+                None => {
+                    warn!(
+                        target: "moon:project:token",
+                        "Found a token variable {} in \"{}\" that is not supported or is not an environment variable, but this may be intentional, so leaving it.",
+                        token,
+                        value
+                    );
+
+                    token.to_owned()
+                }
             }
-        };
+        });
 
-        Ok(String::from(value).replace(token, &var_value))
+        Ok(result.into_owned())
     }
 
     fn convert_string_to_u8(&self, token: &str, value: String) -> Result<u8, ProjectError> {
@@ -295,6 +349,90 @@ impl<'a> TokenResolver<'a> {
         }
     }
 
+    fn replace_cache_token(
+        &self,
+        token_type: TokenType,
+        task: Option<&Task>,
+    ) -> Result<Vec<PathBuf>, ProjectError> {
+        token_type.check_context(&self.context)?;
+
+        let mut results = vec![];
+        let task = task.expect("Expected a task for cache resolving");
+
+        if let TokenType::Cache(_, name) = token_type {
+            let (project_id, task_id) = Target::parse(&task.target)?.ids()?;
+
+            results.push(
+                self.data
+                    .workspace_root
+                    .join(CONFIG_DIRNAME)
+                    .join("cache")
+                    .join("tasks")
+                    .join(project_id)
+                    .join(task_id)
+                    .join(name),
+            );
+        }
+
+        Ok(results)
+    }
+
+    fn replace_env_tokens(
+        &self,
+        token_type: TokenType,
+        task: Option<&Task>,
+    ) -> Result<Vec<PathBuf>, ProjectError> {
+        token_type.check_context(&self.context)?;
+
+        let mut results = vec![];
+        let task = task.expect("Expected a task for env resolving");
+
+        match token_type {
+            // Value of a single variable, with the task taking precedence
+            TokenType::Env(token, name) => {
+                match task
+                    .env
+                    .get(&name)
+                    .cloned()
+                    .or_else(|| env::var(&name).ok())
+                {
+                    Some(value) => {
+                        results.push(PathBuf::from(value));
+                    }
+                    None => {
+                        return Err(ProjectError::Token(TokenError::MissingEnvVar(token, name)));
+                    }
+                };
+            }
+            // Key-value pairs of all variables with the prefix, sorted by name
+            TokenType::Envs(_, prefix) => {
+                // Variables that are not valid UTF-8 can't be passed as args, so skip them
+                let mut vars = env::vars_os()
+                    .filter_map(|(key, value)| {
+                        Some((key.into_string().ok()?, value.into_string().ok()?))
+                    })
+                    .filter(|(key, _)| key.starts_with(&prefix))
+                    .collect::<HashMap<_, _>>();
+
+                for (key, value) in &task.env {
+                    if key.starts_with(&prefix) {
+                        vars.insert(key.clone(), value.clone());
+                    }
+                }
+
+                let mut vars = vars.into_iter().collect::<Vec<_>>();
+                vars.sort();
+
+                for (key, value) in vars {
+                    results.push(PathBuf::from(format!("{}={}", key, value)));
+                }
+            }
+            _ => {}
+        };
+
+        Ok(results)
+    }
+
     fn replace_file_group_tokens(
         &self,
         token_type: TokenType,
@@ -802,6 +940,256 @@ mod tests {
                 "project:task"
             );
         }
+
+        #[test]
+        fn supports_multiple_vars() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
+
+            assert_eq!(
+                resolver.resolve_var("$project/$task", &task).unwrap(),
+                "project/task"
+            );
+        }
+
+        #[test]
+        fn supports_task_env_vars() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    env: Some(HashMap::from([(
+                        String::from("MOON_TOKEN_TASK_VAR"),
+                        String::from("task"),
+                    )])),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            assert_eq!(
+                resolver
+                    .resolve_env_var("--mode=$MOON_TOKEN_TASK_VAR", &task)
+                    .unwrap(),
+                "--mode=task"
+            );
+        }
+
+        #[test]
+        fn supports_process_env_vars() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
+
+            env::set_var("MOON_TOKEN_PROCESS_VAR", "process");
+
+            assert_eq!(
+                resolver
+                    .resolve_env_var("$MOON_TOKEN_PROCESS_VAR", &task)
+                    .unwrap(),
+                "process"
+            );
+
+            env::remove_var("MOON_TOKEN_PROCESS_VAR");
+        }
+
+        #[test]
+        fn task_env_vars_take_precedence() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    env: Some(HashMap::from([(
+                        String::from("MOON_TOKEN_PRECEDENCE_VAR"),
+                        String::from("task"),
+                    )])),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            env::set_var("MOON_TOKEN_PRECEDENCE_VAR", "process");
+
+            assert_eq!(
+                resolver
+                    .resolve_env_var("$MOON_TOKEN_PRECEDENCE_VAR", &task)
+                    .unwrap(),
+                "task"
+            );
+
+            env::remove_var("MOON_TOKEN_PRECEDENCE_VAR");
+        }
+
+        #[test]
+        fn leaves_unknown_vars() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
+
+            assert_eq!(
+                resolver
+                    .resolve_var("$MOON_TOKEN_UNKNOWN_VAR-$task", &task)
+                    .unwrap(),
+                "$MOON_TOKEN_UNKNOWN_VAR-task"
+            );
+        }
+
+        #[test]
+        fn leaves_env_vars_until_ran() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
+
+            env::set_var("MOON_TOKEN_DEFERRED_VAR", "process");
+
+            assert_eq!(
+                resolver
+                    .resolve_var("$MOON_TOKEN_DEFERRED_VAR-$task", &task)
+                    .unwrap(),
+                "$MOON_TOKEN_DEFERRED_VAR-task"
+            );
+
+            env::remove_var("MOON_TOKEN_DEFERRED_VAR");
+        }
+
+        #[test]
+        fn supports_cache() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
+
+            assert_eq!(
+                resolver
+                    .resolve(&string_vec!["@cache(eslint)"], Some(&task))
+                    .unwrap(),
+                vec![workspace_root.join(".moon/cache/tasks/project/task/eslint")],
+            );
+        }
+
+        #[test]
+        fn supports_env() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    env: Some(HashMap::from([(
+                        String::from("MOON_TOKEN_ENV_TASK"),
+                        String::from("task"),
+                    )])),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            env::set_var("MOON_TOKEN_ENV_PROCESS", "process");
+
+            assert_eq!(
+                resolver
+                    .resolve(
+                        &string_vec!["@env(MOON_TOKEN_ENV_TASK)", "@env(MOON_TOKEN_ENV_PROCESS)"],
+                        Some(&task)
+                    )
+                    .unwrap(),
+                vec![PathBuf::from("task"), PathBuf::from("process")],
+            );
+
+            env::remove_var("MOON_TOKEN_ENV_PROCESS");
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "MissingEnvVar(\"@env(MOON_TOKEN_ENV_MISSING)\", \"MOON_TOKEN_ENV_MISSING\")"
+        )]
+        fn errors_for_missing_env() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
+
+            resolver
+                .resolve(&string_vec!["@env(MOON_TOKEN_ENV_MISSING)"], Some(&task))
+                .unwrap();
+        }
+
+        #[test]
+        fn supports_envs() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    env: Some(HashMap::from([
+                        (String::from("MOON_TOKEN_ENVS_B"), String::from("task")),
+                        (String::from("OTHER"), String::from("other")),
+                    ])),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            env::set_var("MOON_TOKEN_ENVS_A", "a");
+            env::set_var("MOON_TOKEN_ENVS_B", "process");
+
+            assert_eq!(
+                resolver
+                    .resolve(&string_vec!["@envs(MOON_TOKEN_ENVS_)"], Some(&task))
+                    .unwrap(),
+                vec![
+                    PathBuf::from("MOON_TOKEN_ENVS_A=a"),
+                    PathBuf::from("MOON_TOKEN_ENVS_B=task")
+                ],
+            );
+
+            env::remove_var("MOON_TOKEN_ENVS_A");
+            env::remove_var("MOON_TOKEN_ENVS_B");
+        }
     }
 
     mod inputs {
@@ -944,6 +1332,46 @@ mod tests {
             );
         }
 
+        #[test]
+        #[should_panic(expected = "InvalidTokenContext(\"@cache\", \"inputs\")")]
+        fn doesnt_support_cache() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_inputs(&metadata);
+
+            resolver
+                .resolve(&string_vec!["@cache(eslint)"], None)
+                .unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "InvalidTokenContext(\"@env\", \"inputs\")")]
+        fn doesnt_support_env() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_inputs(&metadata);
+
+            resolver.resolve(&string_vec!["@env(HOME)"], None).unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "InvalidTokenContext(\"@envs\", \"inputs\")")]
+        fn doesnt_support_envs() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_inputs(&metadata);
+
+            resolver
+                .resolve(&string_vec!["@envs(NODE_)"], None)
+                .unwrap();
+        }
+
         #[test]
         #[should_panic(expected = "InvalidTokenContext(\"$var\", \"inputs\"))")]
         fn doesnt_support_vars() {
@@ -1040,6 +1468,46 @@ mod tests {
                 .unwrap();
         }
 
+        #[test]
+        #[should_panic(expected = "InvalidTokenContext(\"@cache\", \"outputs\")")]
+        fn doesnt_support_cache() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_outputs(&metadata);
+
+            resolver
+                .resolve(&string_vec!["@cache(eslint)"], None)
+                .unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "InvalidTokenContext(\"@env\", \"outputs\")")]
+        fn doesnt_support_env() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_outputs(&metadata);
+
+            resolver.resolve(&string_vec!["@env(HOME)"], None).unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "InvalidTokenContext(\"@envs\", \"outputs\")")]
+        fn doesnt_support_envs() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let resolver = TokenResolver::for_outputs(&metadata);
+
+            resolver
+                .resolve(&string_vec!["@envs(NODE_)"], None)
+                .unwrap();
+        }
+
         #[test]
         #[should_panic(expected = "InvalidTokenContext(\"$var\", \"outputs\"))")]
         fn doesnt_support_vars() {
@@ -0,0 +1,249 @@
+use crate::errors::ProjectError;
+use crate::project::Project;
+use crate::target::Target;
+use moon_config::ProjectID;
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{DiGraph, NodeIndex};
+use std::collections::HashMap;
+
+/// Find all cycles within a graph of IDs, and return each cycle as
+/// a sorted list of the IDs it contains. Cycles are sorted as well.
+fn find_cycles(edges: &[(String, String)]) -> Vec<Vec<String>> {
+    let mut graph = DiGraph::<String, ()>::new();
+    let mut indices = HashMap::<String, NodeIndex>::new();
+
+    let mut get_index = |graph: &mut DiGraph<String, ()>, id: &String| -> NodeIndex {
+        *indices
+            .entry(id.clone())
+            .or_insert_with(|| graph.add_node(id.clone()))
+    };
+
+    for (from, to) in edges {
+        let from_index = get_index(&mut graph, from);
+        let to_index = get_index(&mut graph, to);
+
+        graph.add_edge(from_index, to_index, ());
+    }
+
+    let mut cycles = tarjan_scc(&graph)
+        .into_iter()
+        .filter(|component| component.len() > 1 || graph.contains_edge(component[0], component[0]))
+        .map(|component| {
+            let mut ids = component
+                .into_iter()
+                .map(|index| graph[index].clone())
+                .collect::<Vec<_>>();
+            ids.sort();
+            ids
+        })
+        .collect::<Vec<_>>();
+
+    cycles.sort();
+    cycles
+}
+
+/// Validate references between projects and their tasks, which can only be
+/// checked once all projects have been loaded. This includes `dependsOn` projects
+/// that have not been configured, task `deps` that point to unknown targets,
+/// and dependency cycles between projects or tasks. Projects that failed to load
+/// are expected to be included in `ids`, but not `projects`.
+pub fn validate_project_references(ids: &[ProjectID], projects: &[Project]) -> Vec<ProjectError> {
+    let mut errors = vec![];
+    let mut project_edges = vec![];
+    let mut task_edges = vec![];
+    let projects_by_id = projects
+        .iter()
+        .map(|project| (project.id.as_str(), project))
+        .collect::<HashMap<_, _>>();
+
+    for project in projects {
+        for dep_id in project.get_dependencies() {
+            if ids.contains(&dep_id) {
+                project_edges.push((project.id.clone(), dep_id));
+            } else {
+                errors.push(ProjectError::UnknownDependency(project.id.clone(), dep_id));
+            }
+        }
+
+        let mut task_ids = project.tasks.keys().collect::<Vec<_>>();
+        task_ids.sort();
+
+        for task_id in task_ids {
+            let task = project.tasks.get(task_id).unwrap();
+
+            for dep in &task.deps {
+                let dep_project_id = match Target::parse(dep).map(|t| t.project_id) {
+                    Ok(Some(id)) => id,
+                    _ => {
+                        errors.push(ProjectError::UnknownTaskDep(
+                            task.target.clone(),
+                            dep.clone(),
+                        ));
+                        continue;
+                    }
+                };
+
+                let is_known = match projects_by_id.get(dep_project_id.as_str()) {
+                    Some(dep_project) => dep_project.tasks.values().any(|t| &t.target == dep),
+                    // Project failed to load, so its tasks are unknown
+                    None => ids.contains(&dep_project_id),
+                };
+
+                if is_known {
+                    task_edges.push((task.target.clone(), dep.clone()));
+                } else {
+                    errors.push(ProjectError::UnknownTaskDep(
+                        task.target.clone(),
+                        dep.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    for cycle in find_cycles(&project_edges) {
+        errors.push(ProjectError::ProjectCycleDetected(cycle.join(", ")));
+    }
+
+    for cycle in find_cycles(&task_edges) {
+        errors.push(ProjectError::TaskCycleDetected(cycle.join(", ")));
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Task;
+    use moon_config::{ProjectConfig, ProjectDependsOn, TaskConfig};
+    use moon_utils::string_vec;
+
+    fn create_project(
+        id: &str,
+        depends_on: Vec<String>,
+        tasks: Vec<(&str, Vec<String>)>,
+    ) -> Project {
+        Project {
+            config: Some(ProjectConfig {
+                depends_on: depends_on
+                    .into_iter()
+                    .map(ProjectDependsOn::String)
+                    .collect(),
+                ..ProjectConfig::default()
+            }),
+            id: id.to_owned(),
+            tasks: tasks
+                .into_iter()
+                .map(|(task_id, deps)| {
+                    (
+                        task_id.to_owned(),
+                        Task::from_config(
+                            Target::format(id, task_id).unwrap(),
+                            &TaskConfig {
+                                deps: Some(deps),
+                                ..TaskConfig::default()
+                            },
+                        ),
+                    )
+                })
+                .collect(),
+            ..Project::default()
+        }
+    }
+
+    fn to_strings(errors: Vec<ProjectError>) -> Vec<String> {
+        errors.into_iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn passes_for_valid_references() {
+        let projects = vec![
+            create_project(
+                "a",
+                string_vec!["b"],
+                vec![("build", string_vec!["b:build"])],
+            ),
+            create_project("b", vec![], vec![("build", vec![])]),
+        ];
+
+        assert!(validate_project_references(&string_vec!["a", "b"], &projects).is_empty());
+    }
+
+    #[test]
+    fn errors_for_unknown_depends_on() {
+        let projects = vec![create_project("a", string_vec!["unknown"], vec![])];
+
+        assert_eq!(
+            to_strings(validate_project_references(&string_vec!["a"], &projects)),
+            string_vec!["Project <id>a</id> depends on unknown project <id>unknown</id>."]
+        );
+    }
+
+    #[test]
+    fn errors_for_unknown_task_deps() {
+        let projects = vec![
+            create_project(
+                "a",
+                vec![],
+                vec![("build", string_vec!["b:unknown", "c:build"])],
+            ),
+            create_project("b", vec![], vec![("build", vec![])]),
+        ];
+
+        assert_eq!(
+            to_strings(validate_project_references(&string_vec!["a", "b"], &projects)),
+            string_vec![
+                "Task <target>a:build</target> depends on unknown target <target>b:unknown</target>.",
+                "Task <target>a:build</target> depends on unknown target <target>c:build</target>."
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_task_deps_of_projects_that_failed_to_load() {
+        let projects = vec![create_project(
+            "a",
+            vec![],
+            vec![("build", string_vec!["b:build"])],
+        )];
+
+        assert!(validate_project_references(&string_vec!["a", "b"], &projects).is_empty());
+    }
+
+    #[test]
+    fn errors_for_project_cycles() {
+        let projects = vec![
+            create_project("a", string_vec!["b"], vec![]),
+            create_project("b", string_vec!["c"], vec![]),
+            create_project("c", string_vec!["a"], vec![]),
+            create_project("d", string_vec!["a"], vec![]),
+        ];
+
+        assert_eq!(
+            to_strings(validate_project_references(
+                &string_vec!["a", "b", "c", "d"],
+                &projects
+            )),
+            string_vec!["Dependency cycle detected between projects <id>a, b, c</id>."]
+        );
+    }
+
+    #[test]
+    fn errors_for_task_cycles() {
+        let projects = vec![
+            create_project("a", vec![], vec![("build", string_vec!["b:build"])]),
+            create_project("b", vec![], vec![("build", string_vec!["a:build"])]),
+        ];
+
+        assert_eq!(
+            to_strings(validate_project_references(
+                &string_vec!["a", "b"],
+                &projects
+            )),
+            string_vec![
+                "Dependency cycle detected between tasks <target>a:build, b:build</target>."
+            ]
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use insta::assert_snapshot;
 use moon_cache::CacheEngine;
-use moon_config::GlobalProjectConfig;
-use moon_project::ProjectGraph;
+use moon_config::{ConstraintsConfig, GlobalProjectConfig, ProjectType};
+use moon_project::{DependencyScope, ProjectGraph, Target};
 use moon_utils::string_vec;
 use moon_utils::test::get_fixtures_dir;
 use std::collections::HashMap;
@@ -42,6 +42,242 @@ async fn get_dependents_graph() -> ProjectGraph {
     .unwrap()
 }
 
+async fn get_tasks_graph() -> ProjectGraph {
+    let workspace_root = get_fixtures_dir("tasks");
+
+    ProjectGraph::create(
+        &workspace_root,
+        GlobalProjectConfig::default(),
+        &HashMap::from([
+            ("basic".to_owned(), "basic".to_owned()),
+            ("build-a".to_owned(), "build-a".to_owned()),
+            ("build-b".to_owned(), "build-b".to_owned()),
+            ("build-c".to_owned(), "build-c".to_owned()),
+        ]),
+        &CacheEngine::create(&workspace_root).await.unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+async fn get_implicit_graph(from_package_json: bool, tsconfig_name: Option<&str>) -> ProjectGraph {
+    let workspace_root = get_fixtures_dir("project-graph/implicit");
+
+    let mut graph = ProjectGraph::create(
+        &workspace_root,
+        GlobalProjectConfig::default(),
+        &HashMap::from([
+            ("a".to_owned(), "a".to_owned()),
+            ("b".to_owned(), "b".to_owned()),
+            ("c".to_owned(), "c".to_owned()),
+            ("d".to_owned(), "d".to_owned()),
+        ]),
+        &CacheEngine::create(&workspace_root).await.unwrap(),
+    )
+    .await
+    .unwrap();
+
+    graph
+        .infer_implicit_dependencies(from_package_json, tsconfig_name)
+        .await
+        .unwrap();
+
+    graph
+}
+
+async fn get_scopes_graph() -> ProjectGraph {
+    let workspace_root = get_fixtures_dir("project-graph/scopes");
+
+    ProjectGraph::create(
+        &workspace_root,
+        GlobalProjectConfig::default(),
+        &HashMap::from([
+            ("a".to_owned(), "a".to_owned()),
+            ("b".to_owned(), "b".to_owned()),
+            ("c".to_owned(), "c".to_owned()),
+            ("d".to_owned(), "d".to_owned()),
+        ]),
+        &CacheEngine::create(&workspace_root).await.unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+mod constraints {
+    use super::*;
+
+    async fn get_constraints_graph(constraints: ConstraintsConfig) -> ProjectGraph {
+        let workspace_root = get_fixtures_dir("project-graph/constraints");
+
+        let mut graph = ProjectGraph::create(
+            &workspace_root,
+            GlobalProjectConfig::default(),
+            &HashMap::from([
+                ("app".to_owned(), "app".to_owned()),
+                ("ui".to_owned(), "ui".to_owned()),
+                ("utils".to_owned(), "utils".to_owned()),
+            ]),
+            &CacheEngine::create(&workspace_root).await.unwrap(),
+        )
+        .await
+        .unwrap();
+
+        graph.set_constraints(constraints);
+        graph
+    }
+
+    #[tokio::test]
+    async fn passes_when_not_configured() {
+        let graph = get_constraints_graph(ConstraintsConfig::default()).await;
+
+        assert!(graph.load("app").is_ok());
+    }
+
+    #[tokio::test]
+    async fn passes_when_all_rules_are_satisfied() {
+        let graph = get_constraints_graph(ConstraintsConfig {
+            forbidden_dependencies: string_vec!["app"],
+            tag_relationships: HashMap::from([(
+                "frontend".to_owned(),
+                string_vec!["frontend", "shared"],
+            )]),
+            type_relationships: HashMap::from([
+                (ProjectType::Application, vec![ProjectType::Library]),
+                (ProjectType::Library, vec![ProjectType::Library]),
+            ]),
+        })
+        .await;
+
+        assert!(graph.load("app").is_ok());
+    }
+
+    #[tokio::test]
+    async fn errors_for_forbidden_dependency() {
+        let graph = get_constraints_graph(ConstraintsConfig {
+            forbidden_dependencies: string_vec!["utils"],
+            ..ConstraintsConfig::default()
+        })
+        .await;
+
+        assert_eq!(
+            graph.load("ui").unwrap_err().to_string(),
+            "Project <id>ui</id> cannot depend on project <id>utils</id>, as it has been forbidden by workspace constraints."
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_for_invalid_tag_relationship() {
+        let graph = get_constraints_graph(ConstraintsConfig {
+            tag_relationships: HashMap::from([(
+                "frontend".to_owned(),
+                string_vec!["frontend", "react"],
+            )]),
+            ..ConstraintsConfig::default()
+        })
+        .await;
+
+        assert_eq!(
+            graph.load("ui").unwrap_err().to_string(),
+            "Project <id>ui</id> with tag <symbol>frontend</symbol> cannot depend on project <id>utils</id>, as it does not have any of the tags <symbol>frontend, react</symbol>."
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_for_invalid_type_relationship() {
+        let graph = get_constraints_graph(ConstraintsConfig {
+            type_relationships: HashMap::from([(ProjectType::Library, vec![ProjectType::Tool])]),
+            ..ConstraintsConfig::default()
+        })
+        .await;
+
+        assert_eq!(
+            graph.load("ui").unwrap_err().to_string(),
+            "Project <id>ui</id> of type <symbol>library</symbol> cannot depend on project <id>utils</id> of type <symbol>library</symbol>."
+        );
+    }
+
+    #[tokio::test]
+    async fn ignores_unmapped_types_and_tags() {
+        let graph = get_constraints_graph(ConstraintsConfig {
+            tag_relationships: HashMap::from([("backend".to_owned(), string_vec!["backend"])]),
+            type_relationships: HashMap::from([(ProjectType::Tool, vec![ProjectType::Tool])]),
+            ..ConstraintsConfig::default()
+        })
+        .await;
+
+        assert!(graph.load("app").is_ok());
+    }
+}
+
+mod expand_target_glob {
+    use super::*;
+
+    fn to_ids(targets: Vec<Target>) -> Vec<String> {
+        targets.into_iter().map(|t| t.id).collect()
+    }
+
+    #[tokio::test]
+    async fn expands_task_position() {
+        let graph = get_tasks_graph().await;
+
+        assert_eq!(
+            to_ids(graph.expand_target_glob("basic:*").unwrap()),
+            string_vec!["basic:build", "basic:lint", "basic:test"]
+        );
+        assert_eq!(
+            to_ids(graph.expand_target_glob("basic:{lint,test}").unwrap()),
+            string_vec!["basic:lint", "basic:test"]
+        );
+    }
+
+    #[tokio::test]
+    async fn expands_project_position() {
+        let graph = get_tasks_graph().await;
+
+        assert_eq!(
+            to_ids(graph.expand_target_glob("build-*:build").unwrap()),
+            string_vec!["build-a:build", "build-b:build", "build-c:build"]
+        );
+    }
+
+    #[tokio::test]
+    async fn expands_both_positions() {
+        let graph = get_tasks_graph().await;
+
+        assert_eq!(
+            to_ids(graph.expand_target_glob("*:t*").unwrap()),
+            string_vec!["basic:test"]
+        );
+        assert_eq!(
+            to_ids(graph.expand_target_glob(":b*").unwrap()),
+            string_vec![
+                "basic:build",
+                "build-a:build",
+                "build-b:build",
+                "build-c:build"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_empty_for_no_matches() {
+        let graph = get_tasks_graph().await;
+
+        assert!(graph
+            .expand_target_glob("unknown-*:build")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Target(InvalidFormat(\"build-*\"))")]
+    async fn errors_for_invalid_format() {
+        let graph = get_tasks_graph().await;
+
+        graph.expand_target_glob("build-*").unwrap();
+    }
+}
+
 mod get_dependencies_of {
     use super::*;
 
@@ -64,6 +300,85 @@ mod get_dependencies_of {
     }
 }
 
+mod infer_implicit_dependencies {
+    use super::*;
+
+    #[tokio::test]
+    async fn doesnt_infer_when_disabled() {
+        let graph = get_implicit_graph(false, None).await;
+
+        assert_eq!(graph.load("a").unwrap().get_dependencies(), string_vec![]);
+        assert_eq!(graph.load("b").unwrap().get_dependencies(), string_vec![]);
+        assert_eq!(
+            graph.load("d").unwrap().get_dependencies(),
+            string_vec!["c"]
+        );
+    }
+
+    #[tokio::test]
+    async fn infers_from_package_json() {
+        let graph = get_implicit_graph(true, None).await;
+
+        assert_eq!(
+            graph.load("a").unwrap().get_dependencies(),
+            string_vec!["b"]
+        );
+        assert_eq!(graph.load("b").unwrap().get_dependencies(), string_vec![]);
+        assert_eq!(graph.load("c").unwrap().get_dependencies(), string_vec![]);
+        assert_eq!(
+            graph.load("d").unwrap().get_dependencies(),
+            string_vec!["c"]
+        );
+    }
+
+    #[tokio::test]
+    async fn infers_from_tsconfig_references() {
+        let graph = get_implicit_graph(false, Some("tsconfig.json")).await;
+
+        assert_eq!(graph.load("a").unwrap().get_dependencies(), string_vec![]);
+        assert_eq!(
+            graph.load("b").unwrap().get_dependencies(),
+            string_vec!["c"]
+        );
+        assert_eq!(
+            graph.load("d").unwrap().get_dependencies(),
+            string_vec!["a", "c"]
+        );
+    }
+
+    #[tokio::test]
+    async fn merges_with_depends_on() {
+        let graph = get_implicit_graph(true, Some("tsconfig.json")).await;
+
+        let a = graph.load("a").unwrap();
+        let d = graph.load("d").unwrap();
+
+        assert_eq!(a.implicit_deps, string_vec!["b"]);
+        assert_eq!(d.implicit_deps, string_vec!["a", "c"]);
+        assert_eq!(d.get_dependencies(), string_vec!["a", "c"]);
+        assert!(d.is_implicit_dependency("a"));
+        assert!(!d.is_implicit_dependency("c"));
+        assert_eq!(graph.get_dependents_of(&a).unwrap(), string_vec!["d"]);
+    }
+
+    #[tokio::test]
+    async fn expands_parent_task_deps() {
+        let graph = get_implicit_graph(false, None).await;
+
+        assert_eq!(
+            graph.load("d").unwrap().get_task("build").unwrap().deps,
+            string_vec!["c:build"]
+        );
+
+        let graph = get_implicit_graph(false, Some("tsconfig.json")).await;
+
+        assert_eq!(
+            graph.load("d").unwrap().get_task("build").unwrap().deps,
+            string_vec!["c:build", "a:build"]
+        );
+    }
+}
+
 mod get_dependents_of {
     use super::*;
 
@@ -86,6 +401,185 @@ mod get_dependents_of {
     }
 }
 
+mod get_ids_with_tag {
+    use super::*;
+
+    async fn get_tags_graph() -> ProjectGraph {
+        let workspace_root = get_fixtures_dir("project-graph/tags");
+
+        ProjectGraph::create(
+            &workspace_root,
+            GlobalProjectConfig::default(),
+            &HashMap::from([
+                ("a".to_owned(), "a".to_owned()),
+                ("b".to_owned(), "b".to_owned()),
+                ("c".to_owned(), "c".to_owned()),
+                ("d".to_owned(), "d".to_owned()),
+                ("e".to_owned(), "e".to_owned()),
+            ]),
+            &CacheEngine::create(&workspace_root).await.unwrap(),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn returns_tagged_ids() {
+        let graph = get_tags_graph().await;
+
+        assert_eq!(
+            graph.get_ids_with_tag("frontend").unwrap(),
+            string_vec!["a", "b", "d"]
+        );
+        assert_eq!(graph.get_ids_with_tag("react").unwrap(), string_vec!["b"]);
+        assert_eq!(graph.get_ids_with_tag("unknown").unwrap(), string_vec![]);
+    }
+
+    #[tokio::test]
+    async fn doesnt_load_projects_into_graph() {
+        let graph = get_tags_graph().await;
+
+        graph.get_ids_with_tag("frontend").unwrap();
+
+        assert!(!graph.to_dot().contains("\"a\""));
+    }
+
+    #[tokio::test]
+    async fn doesnt_expand_tasks() {
+        let graph = get_tags_graph().await;
+
+        // Project "e" has a task that fails to expand
+        assert_eq!(
+            graph.get_ids_with_tag("backend").unwrap(),
+            string_vec!["c", "e"]
+        );
+        assert!(graph.load("e").is_err());
+    }
+}
+
+mod get_non_dev_dependents_of {
+    use super::*;
+
+    #[tokio::test]
+    async fn excludes_development_dependents() {
+        let graph = get_scopes_graph().await;
+
+        let a = graph.load("a").unwrap();
+        let c = graph.load("c").unwrap();
+
+        graph.load("b").unwrap();
+        graph.load("d").unwrap();
+
+        assert_eq!(
+            a.get_dependency_scope("c"),
+            Some(DependencyScope::Development)
+        );
+
+        let mut dependents = graph.get_dependents_of(&c).unwrap();
+        dependents.sort();
+
+        assert_eq!(dependents, string_vec!["a", "b", "d"]);
+
+        let mut dependents = graph.get_non_dev_dependents_of(&c).unwrap();
+        dependents.sort();
+
+        assert_eq!(dependents, string_vec!["b", "d"]);
+    }
+}
+
+mod get_transitive {
+    use super::*;
+
+    async fn get_transitive_graph() -> ProjectGraph {
+        let workspace_root = get_fixtures_dir("project-graph/transitive");
+
+        ProjectGraph::create(
+            &workspace_root,
+            GlobalProjectConfig::default(),
+            &HashMap::from([
+                ("a".to_owned(), "a".to_owned()),
+                ("b".to_owned(), "b".to_owned()),
+                ("c".to_owned(), "c".to_owned()),
+                ("d".to_owned(), "d".to_owned()),
+                ("e".to_owned(), "e".to_owned()),
+                ("f".to_owned(), "f".to_owned()),
+            ]),
+            &CacheEngine::create(&workspace_root).await.unwrap(),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn returns_all_dependencies() {
+        let graph = get_transitive_graph().await;
+        let d = graph.load("d").unwrap();
+
+        assert_eq!(
+            graph.get_transitive_dependencies_of(&d, None).unwrap(),
+            string_vec!["a", "b", "c"]
+        );
+    }
+
+    #[tokio::test]
+    async fn limits_dependencies_by_depth() {
+        let graph = get_transitive_graph().await;
+        let d = graph.load("d").unwrap();
+
+        assert_eq!(
+            graph.get_transitive_dependencies_of(&d, Some(0)).unwrap(),
+            string_vec![]
+        );
+        assert_eq!(
+            graph.get_transitive_dependencies_of(&d, Some(1)).unwrap(),
+            string_vec!["c"]
+        );
+        assert_eq!(
+            graph.get_transitive_dependencies_of(&d, Some(2)).unwrap(),
+            string_vec!["b", "c"]
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_all_dependents() {
+        let graph = get_transitive_graph().await;
+        let a = graph.load("a").unwrap();
+
+        assert_eq!(
+            graph.get_transitive_dependents_of(&a, None).unwrap(),
+            string_vec!["b", "c", "d", "e", "f"]
+        );
+    }
+
+    #[tokio::test]
+    async fn limits_dependents_by_depth() {
+        let graph = get_transitive_graph().await;
+        let a = graph.load("a").unwrap();
+
+        assert_eq!(
+            graph.get_transitive_dependents_of(&a, Some(1)).unwrap(),
+            string_vec!["b", "e"]
+        );
+        assert_eq!(
+            graph.get_transitive_dependents_of(&a, Some(2)).unwrap(),
+            string_vec!["b", "c", "e", "f"]
+        );
+    }
+
+    #[tokio::test]
+    async fn excludes_development_dependents() {
+        let graph = get_transitive_graph().await;
+        let a = graph.load("a").unwrap();
+
+        assert_eq!(
+            graph
+                .get_transitive_non_dev_dependents_of(&a, None)
+                .unwrap(),
+            string_vec!["b", "c", "d"]
+        );
+    }
+}
+
 mod to_dot {
     use super::*;
 
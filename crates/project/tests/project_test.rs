@@ -1,7 +1,8 @@
 use moon_config::package::PackageJson;
 use moon_config::{
-    GlobalProjectConfig, ProjectConfig, ProjectLanguage, ProjectMetadataConfig, ProjectType,
-    TargetID, TaskConfig, TaskMergeStrategy, TaskOptionsConfig, TaskType,
+    GlobalProjectConfig, ProjectConfig, ProjectDependsOn, ProjectLanguage, ProjectMetadataConfig,
+    ProjectType, TargetID, TaskConfig, TaskMergeStrategy, TaskOptionEnvFile, TaskOptionsConfig,
+    TaskType,
 };
 use moon_project::{EnvVars, FileGroup, Project, ProjectError, Target, Task};
 use moon_utils::string_vec;
@@ -110,7 +111,7 @@ fn basic_config() {
         Project {
             id: String::from("basic"),
             config: Some(ProjectConfig {
-                depends_on: string_vec!["noConfig"],
+                depends_on: vec![ProjectDependsOn::String(String::from("noConfig"))],
                 file_groups: HashMap::from([(String::from("tests"), string_vec!["**/*_test.rs"])]),
                 language: ProjectLanguage::JavaScript,
                 ..ProjectConfig::default()
@@ -178,7 +179,7 @@ fn overrides_global_file_groups() {
         Project {
             id: String::from("basic"),
             config: Some(ProjectConfig {
-                depends_on: string_vec!["noConfig"],
+                depends_on: vec![ProjectDependsOn::String(String::from("noConfig"))],
                 file_groups: HashMap::from([(String::from("tests"), string_vec!["**/*_test.rs"])]),
                 language: ProjectLanguage::JavaScript,
                 ..ProjectConfig::default()
@@ -238,40 +239,55 @@ mod tasks {
 
     fn mock_merged_task_options_config(strategy: TaskMergeStrategy) -> TaskOptionsConfig {
         TaskOptionsConfig {
+            env_file: None,
+            hash_env_vars: None,
             merge_args: Some(strategy.clone()),
             merge_deps: Some(strategy.clone()),
             merge_env: Some(strategy.clone()),
             merge_inputs: Some(strategy.clone()),
             merge_outputs: Some(strategy),
+            persistent: None,
+            ready_check: None,
             retry_count: Some(1),
             run_in_ci: Some(true),
             run_from_workspace_root: None,
+            timeout: None,
         }
     }
 
     fn mock_local_task_options_config(strategy: TaskMergeStrategy) -> TaskOptionsConfig {
         TaskOptionsConfig {
+            env_file: None,
+            hash_env_vars: None,
             merge_args: Some(strategy.clone()),
             merge_deps: Some(strategy.clone()),
             merge_env: Some(strategy.clone()),
             merge_inputs: Some(strategy.clone()),
             merge_outputs: Some(strategy),
+            persistent: None,
+            ready_check: None,
             retry_count: None,
             run_in_ci: None,
             run_from_workspace_root: None,
+            timeout: None,
         }
     }
 
     fn stub_global_task_options_config() -> TaskOptionsConfig {
         TaskOptionsConfig {
+            env_file: None,
+            hash_env_vars: None,
             merge_args: None,
             merge_deps: None,
             merge_env: None,
             merge_inputs: None,
             merge_outputs: None,
+            persistent: None,
+            ready_check: None,
             retry_count: Some(1),
             run_in_ci: Some(true),
             run_from_workspace_root: None,
+            timeout: None,
         }
     }
 
@@ -676,14 +692,19 @@ mod tasks {
                             inputs: Some(string_vec!["b.*"]),
                             outputs: Some(string_vec!["b.ts"]),
                             options: TaskOptionsConfig {
+                                env_file: None,
+                                hash_env_vars: None,
                                 merge_args: Some(TaskMergeStrategy::Append),
                                 merge_deps: Some(TaskMergeStrategy::Prepend),
                                 merge_env: Some(TaskMergeStrategy::Replace),
                                 merge_inputs: Some(TaskMergeStrategy::Replace),
                                 merge_outputs: Some(TaskMergeStrategy::Append),
+                                persistent: None,
+                                ready_check: None,
                                 retry_count: None,
                                 run_in_ci: None,
                                 run_from_workspace_root: None,
+                                timeout: None,
                             },
                             type_of: TaskType::Node,
                         }
@@ -705,14 +726,19 @@ mod tasks {
                             inputs: Some(string_vec!["b.*"]),
                             outputs: Some(string_vec!["a.ts", "b.ts"]),
                             options: TaskOptionsConfig {
+                                env_file: None,
+                                hash_env_vars: None,
                                 merge_args: Some(TaskMergeStrategy::Append),
                                 merge_deps: Some(TaskMergeStrategy::Prepend),
                                 merge_env: Some(TaskMergeStrategy::Replace),
                                 merge_inputs: Some(TaskMergeStrategy::Replace),
                                 merge_outputs: Some(TaskMergeStrategy::Append),
+                                persistent: None,
+                                ready_check: None,
                                 retry_count: Some(1),
                                 run_in_ci: Some(true),
                                 run_from_workspace_root: None,
+                                timeout: None,
                             },
                             type_of: TaskType::Node,
                         },
@@ -805,6 +831,18 @@ mod tasks {
             )
             .unwrap();
         }
+
+        #[test]
+        #[should_panic(expected = "Target(NoProjectTagInTaskDeps(\"#frontend:build\"))")]
+        fn errors_for_tag_scope() {
+            Project::new(
+                "id",
+                "tag",
+                &get_fixtures_dir("task-deps"),
+                &mock_global_project_config(),
+            )
+            .unwrap();
+        }
     }
 
     mod tokens {
@@ -905,6 +943,7 @@ mod tasks {
                             command: Some(String::from("test")),
                             options: TaskOptionsConfig {
                                 run_from_workspace_root: Some(true),
+                                timeout: None,
                                 ..TaskOptionsConfig::default()
                             },
                             ..TaskConfig::default()
@@ -994,6 +1033,71 @@ mod tasks {
             )
         }
 
+        #[test]
+        fn expands_env_from_env_files() {
+            let workspace_root = get_fixtures_root();
+            let project = Project::new(
+                "id",
+                "base/env-file",
+                &workspace_root,
+                &GlobalProjectConfig {
+                    tasks: HashMap::from([(
+                        String::from("test"),
+                        TaskConfig {
+                            command: Some(String::from("test")),
+                            env: Some(HashMap::from([(
+                                String::from("FOO"),
+                                String::from("explicit"),
+                            )])),
+                            options: TaskOptionsConfig {
+                                env_file: Some(TaskOptionEnvFile::Files(string_vec![
+                                    ".env",
+                                    ".env.local",
+                                    ".env.missing"
+                                ])),
+                                ..TaskOptionsConfig::default()
+                            },
+                            ..TaskConfig::default()
+                        },
+                    )]),
+                    ..GlobalProjectConfig::default()
+                },
+            )
+            .unwrap();
+
+            assert_eq!(
+                project.tasks.get("test").unwrap().env,
+                HashMap::from([
+                    ("FOO".to_owned(), "explicit".to_owned()),
+                    ("BAR".to_owned(), "local".to_owned()),
+                    ("URL".to_owned(), "http://env.com".to_owned()),
+                ])
+            );
+        }
+
+        #[test]
+        fn doesnt_load_env_files_by_default() {
+            let workspace_root = get_fixtures_root();
+            let project = Project::new(
+                "id",
+                "base/env-file",
+                &workspace_root,
+                &GlobalProjectConfig {
+                    tasks: HashMap::from([(
+                        String::from("test"),
+                        TaskConfig {
+                            command: Some(String::from("test")),
+                            ..TaskConfig::default()
+                        },
+                    )]),
+                    ..GlobalProjectConfig::default()
+                },
+            )
+            .unwrap();
+
+            assert!(project.tasks.get("test").unwrap().env.is_empty());
+        }
+
         #[test]
         fn expands_inputs() {
             let workspace_root = get_fixtures_dir("base");
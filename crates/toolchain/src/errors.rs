@@ -7,6 +7,9 @@ pub enum ToolchainError {
     #[error("Internet connection required, unable to download and install tools.")]
     InternetConnectionRequired,
 
+    #[error("Deno has not been configured. Add a <symbol>deno</symbol> setting to <file>.moon/workspace.yml</file> to enable it.")]
+    MissingDenoConfig,
+
     #[error("Unable to determine your home directory.")]
     MissingHomeDir,
 
@@ -1,5 +1,6 @@
 use crate::errors::ToolchainError;
 use crate::helpers::LOG_TARGET;
+use crate::tools::deno::DenoTool;
 use crate::tools::node::NodeTool;
 use crate::traits::Tool;
 use moon_config::constants::CONFIG_DIRNAME;
@@ -44,6 +45,8 @@ pub struct Toolchain {
     pub workspace_root: PathBuf,
 
     // Tool instances are private, as we want to lazy load them.
+    deno: Option<DenoTool>,
+
     node: Option<NodeTool>,
 }
 
@@ -72,9 +75,14 @@ impl Toolchain {
             temp_dir,
             tools_dir,
             workspace_root: root_dir.to_path_buf(),
+            deno: None,
             node: None,
         };
 
+        if let Some(deno_config) = &config.deno {
+            toolchain.deno = Some(DenoTool::new(&toolchain, deno_config)?);
+        }
+
         toolchain.node = Some(NodeTool::new(&toolchain, &config.node)?);
 
         Ok(toolchain)
@@ -105,6 +113,12 @@ impl Toolchain {
             self.node = Some(node);
         }
 
+        if self.deno.is_some() {
+            let mut deno = self.deno.take().unwrap();
+            installed += deno.run_setup(self, check_versions).await?;
+            self.deno = Some(deno);
+        }
+
         Ok(installed)
     }
 
@@ -120,9 +134,19 @@ impl Toolchain {
             node.run_teardown(self).await?;
         }
 
+        if self.deno.is_some() {
+            let mut deno = self.deno.take().unwrap();
+            deno.run_teardown(self).await?;
+        }
+
         Ok(())
     }
 
+    /// Return the Deno tool. Errors if Deno has not been configured in the workspace.
+    pub fn get_deno(&self) -> Result<&DenoTool, ToolchainError> {
+        self.deno.as_ref().ok_or(ToolchainError::MissingDenoConfig)
+    }
+
     /// Return the Node.js tool.
     pub fn get_node(&self) -> &NodeTool {
         self.node.as_ref().unwrap()
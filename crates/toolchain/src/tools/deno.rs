@@ -0,0 +1,136 @@
+use crate::errors::ToolchainError;
+use crate::helpers::{download_file_from_url, get_path_env_var, unpack};
+use crate::traits::{Downloadable, Executable, Installable, Lifecycle, Tool};
+use crate::Toolchain;
+use async_trait::async_trait;
+use moon_config::DenoConfig;
+use moon_lang_deno::deno;
+use moon_logger::{color, debug, Logable};
+use moon_utils::process::{output_to_string, Command};
+use std::path::PathBuf;
+
+pub struct DenoTool {
+    bin_path: PathBuf,
+
+    pub config: DenoConfig,
+
+    download_path: PathBuf,
+
+    install_dir: PathBuf,
+
+    log_target: String,
+}
+
+impl DenoTool {
+    pub fn new(toolchain: &Toolchain, config: &DenoConfig) -> Result<DenoTool, ToolchainError> {
+        let install_dir = toolchain.tools_dir.join("deno").join(&config.version);
+
+        Ok(DenoTool {
+            bin_path: install_dir.join(deno::get_bin_name()),
+            config: config.to_owned(),
+            download_path: toolchain.temp_dir.join("deno").join(format!(
+                "{}-{}",
+                config.version,
+                deno::get_download_file()?
+            )),
+            install_dir,
+            log_target: String::from("moon:toolchain:deno"),
+        })
+    }
+}
+
+impl Logable for DenoTool {
+    fn get_log_target(&self) -> &str {
+        &self.log_target
+    }
+}
+
+#[async_trait]
+impl Downloadable<Toolchain> for DenoTool {
+    fn get_download_path(&self) -> Result<&PathBuf, ToolchainError> {
+        Ok(&self.download_path)
+    }
+
+    async fn is_downloaded(&self) -> Result<bool, ToolchainError> {
+        Ok(self.get_download_path()?.exists())
+    }
+
+    async fn download(
+        &self,
+        _toolchain: &Toolchain,
+        base_host: Option<&str>,
+    ) -> Result<(), ToolchainError> {
+        let host = base_host.unwrap_or("https://github.com");
+        let download_url =
+            deno::get_deno_url(&self.config.version, host, &deno::get_download_file()?);
+
+        download_file_from_url(&download_url, self.get_download_path()?).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Installable<Toolchain> for DenoTool {
+    fn get_install_dir(&self) -> Result<&PathBuf, ToolchainError> {
+        Ok(&self.install_dir)
+    }
+
+    async fn get_installed_version(&self) -> Result<String, ToolchainError> {
+        let bin_path = self.get_bin_path();
+        let output = Command::new(bin_path)
+            .arg("--version")
+            .env("PATH", get_path_env_var(bin_path.parent().unwrap()))
+            .exec_capture_output()
+            .await?;
+
+        Ok(
+            deno::parse_version_output(&output_to_string(&output.stdout))
+                .unwrap_or_else(|| String::from("0.0.0")),
+        )
+    }
+
+    async fn is_installed(
+        &self,
+        _toolchain: &Toolchain,
+        _check_version: bool,
+    ) -> Result<bool, ToolchainError> {
+        Ok(self.get_install_dir()?.exists())
+    }
+
+    async fn install(&self, _toolchain: &Toolchain) -> Result<(), ToolchainError> {
+        let download_path = self.get_download_path()?;
+        let install_dir = self.get_install_dir()?;
+
+        // The archive only contains the binary, so there is no prefix to remove
+        unpack(download_path, install_dir, "").await?;
+
+        debug!(
+            target: self.get_log_target(),
+            "Unpacked and installed to {}",
+            color::path(install_dir)
+        );
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Executable<Toolchain> for DenoTool {
+    async fn find_bin_path(&mut self, _toolchain: &Toolchain) -> Result<(), ToolchainError> {
+        Ok(())
+    }
+
+    fn get_bin_path(&self) -> &PathBuf {
+        &self.bin_path
+    }
+
+    fn is_executable(&self) -> bool {
+        true
+    }
+}
+
+#[async_trait]
+impl Lifecycle<Toolchain> for DenoTool {}
+
+impl Tool for DenoTool {}
@@ -1 +1,2 @@
+pub mod deno;
 pub mod node;
@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::env;
+
+/// Parse the contents of a `.env` file into a map of environment variables,
+/// following the semantics of the popular dotenv libraries:
+///
+/// - Empty lines and lines starting with `#` are ignored.
+/// - An optional `export ` prefix is stripped.
+/// - Single quoted values are used literally.
+/// - Double quoted values support escape sequences and may span multiple lines.
+/// - Unquoted values are trimmed, and support trailing ` #` comments.
+/// - Unquoted and double quoted values interpolate `$VAR` and `${VAR}`, from
+///   variables previously defined in the file, or from the process environment.
+pub fn parse(content: &str) -> HashMap<String, String> {
+    parse_with_vars(content, HashMap::new())
+}
+
+/// Parse the contents of a `.env` file on top of previously loaded variables,
+/// so that values may interpolate variables from earlier files. Returns the
+/// previous variables merged with the newly parsed ones.
+pub fn parse_with_vars(
+    content: &str,
+    mut vars: HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim_start()),
+            None => continue,
+        };
+
+        if !is_valid_key(key) {
+            continue;
+        }
+
+        let value = if let Some(raw) = value.strip_prefix('\'') {
+            match raw.find('\'') {
+                Some(end) => raw[0..end].to_owned(),
+                None => raw.to_owned(),
+            }
+        } else if let Some(raw) = value.strip_prefix('"') {
+            let mut raw = raw.to_owned();
+
+            // Consume following lines until the closing quote is found
+            while find_closing_quote(&raw).is_none() {
+                match lines.next() {
+                    Some(next_line) => {
+                        raw.push('\n');
+                        raw.push_str(next_line);
+                    }
+                    None => break,
+                }
+            }
+
+            let end = find_closing_quote(&raw).unwrap_or(raw.len());
+
+            expand(&raw[0..end], &vars, true)
+        } else {
+            let raw = match value.find(" #") {
+                Some(end) => &value[0..end],
+                None => value,
+            };
+
+            expand(raw.trim_end(), &vars, false)
+        };
+
+        vars.insert(key.to_owned(), value);
+    }
+
+    vars
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    };
+
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-')
+}
+
+fn find_closing_quote(value: &str) -> Option<usize> {
+    let mut escaped = false;
+
+    for (index, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            return Some(index);
+        }
+    }
+
+    None
+}
+
+fn lookup_var(name: &str, vars: &HashMap<String, String>) -> String {
+    vars.get(name)
+        .cloned()
+        .or_else(|| env::var(name).ok())
+        .unwrap_or_default()
+}
+
+/// Interpolate variables, and when `escapes` is true, unescape sequences.
+fn expand(value: &str, vars: &HashMap<String, String>, escapes: bool) -> String {
+    let mut result = String::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if escapes => match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('r') => result.push('\r'),
+                Some('t') => result.push('\t'),
+                Some(next @ ('"' | '\\' | '$')) => result.push(next),
+                Some(next) => {
+                    result.push('\\');
+                    result.push(next);
+                }
+                None => result.push('\\'),
+            },
+            '$' => {
+                let mut name = String::new();
+
+                if chars.peek() == Some(&'{') {
+                    chars.next();
+
+                    for next in chars.by_ref() {
+                        if next == '}' {
+                            break;
+                        }
+
+                        name.push(next);
+                    }
+
+                    result.push_str(&lookup_var(&name, vars));
+                } else {
+                    while let Some(next) = chars.peek() {
+                        if next.is_ascii_alphanumeric() || *next == '_' {
+                            name.push(*next);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+
+                    if name.is_empty() {
+                        result.push('$');
+                    } else {
+                        result.push_str(&lookup_var(&name, vars));
+                    }
+                }
+            }
+            _ => result.push(c),
+        };
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_basic_values() {
+        let vars = parse("FOO=bar\nBAZ = qux \n\n# comment\nexport EXPORTED=yes");
+
+        assert_eq!(vars.get("FOO").unwrap(), "bar");
+        assert_eq!(vars.get("BAZ").unwrap(), "qux");
+        assert_eq!(vars.get("EXPORTED").unwrap(), "yes");
+        assert_eq!(vars.len(), 3);
+    }
+
+    #[test]
+    fn ignores_invalid_lines() {
+        let vars = parse("FOO\n1FOO=bar\n=bar\nBAR=baz");
+
+        assert_eq!(vars.len(), 1);
+        assert_eq!(vars.get("BAR").unwrap(), "baz");
+    }
+
+    #[test]
+    fn strips_inline_comments() {
+        let vars = parse("FOO=bar # comment\nBAR=baz#notcomment");
+
+        assert_eq!(vars.get("FOO").unwrap(), "bar");
+        assert_eq!(vars.get("BAR").unwrap(), "baz#notcomment");
+    }
+
+    #[test]
+    fn supports_single_quotes_literally() {
+        let vars = parse("FOO=bar\nBAR='$FOO # \\n'");
+
+        assert_eq!(vars.get("BAR").unwrap(), "$FOO # \\n");
+    }
+
+    #[test]
+    fn supports_double_quotes() {
+        let vars = parse("FOO=\"bar # baz\"\nBAR=\"line\\nbreak \\\"quoted\\\" \\$FOO\"");
+
+        assert_eq!(vars.get("FOO").unwrap(), "bar # baz");
+        assert_eq!(vars.get("BAR").unwrap(), "line\nbreak \"quoted\" $FOO");
+    }
+
+    #[test]
+    fn supports_multiline_double_quotes() {
+        let vars = parse("KEY=\"-----BEGIN-----\nabc\n-----END-----\"\nNEXT=value");
+
+        assert_eq!(
+            vars.get("KEY").unwrap(),
+            "-----BEGIN-----\nabc\n-----END-----"
+        );
+        assert_eq!(vars.get("NEXT").unwrap(), "value");
+    }
+
+    #[test]
+    fn interpolates_vars() {
+        let vars =
+            parse("HOST=localhost\nPORT=3000\nURL=http://$HOST:${PORT}/api\nQUOTED=\"$HOST\"");
+
+        assert_eq!(vars.get("URL").unwrap(), "http://localhost:3000/api");
+        assert_eq!(vars.get("QUOTED").unwrap(), "localhost");
+    }
+
+    #[test]
+    fn interpolates_process_env_vars() {
+        env::set_var("MOON_DOTENV_PROCESS_VAR", "process");
+
+        let vars = parse("FOO=${MOON_DOTENV_PROCESS_VAR}-value\nBAR=$MOON_DOTENV_UNKNOWN_VAR");
+
+        assert_eq!(vars.get("FOO").unwrap(), "process-value");
+        assert_eq!(vars.get("BAR").unwrap(), "");
+
+        env::remove_var("MOON_DOTENV_PROCESS_VAR");
+    }
+
+    #[test]
+    fn interpolates_previously_loaded_vars() {
+        let base = parse("HOST=localhost\nPORT=3000");
+        let vars = parse_with_vars("PORT=8080\nURL=http://$HOST:$PORT", base);
+
+        assert_eq!(vars.get("HOST").unwrap(), "localhost");
+        assert_eq!(vars.get("PORT").unwrap(), "8080");
+        assert_eq!(vars.get("URL").unwrap(), "http://localhost:8080");
+    }
+
+    #[test]
+    fn later_values_override() {
+        let vars = parse("FOO=first\nFOO=second");
+
+        assert_eq!(vars.get("FOO").unwrap(), "second");
+    }
+}
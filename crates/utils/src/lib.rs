@@ -1,3 +1,4 @@
+pub mod dotenv;
 pub mod fs;
 pub mod glob;
 pub mod path;
@@ -1,13 +1,20 @@
 use crate::path;
+use crate::regex::Regex;
+use lazy_static::lazy_static;
 use moon_error::{map_io_to_process_error, MoonError};
 use moon_logger::{color, logging_enabled, trace};
 use std::env;
 use std::ffi::OsStr;
+use std::future;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::Command as TokioCommand;
-use tokio::task;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command as TokioCommand};
+use tokio::sync::{oneshot, Notify};
+use tokio::task::JoinHandle;
+use tokio::{task, time};
 
 pub use shell_words::{join as join_args, split as split_args};
 pub use std::process::{ExitStatus, Output, Stdio};
@@ -36,6 +43,206 @@ fn create_windows_cmd() -> (String, TokioCommand) {
     )
 }
 
+// How long to wait for a process to exit after sending SIGTERM,
+// before forcefully killing it with SIGKILL.
+const TERMINATE_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+static KILLED: AtomicBool = AtomicBool::new(false);
+
+type ReadySignal = Arc<(Regex, Mutex<Option<oneshot::Sender<()>>>)>;
+
+lazy_static! {
+    static ref CANCEL_NOTIFIER: Notify = Notify::new();
+
+    static ref KILL_NOTIFIER: Notify = Notify::new();
+
+    // Persistent processes that are kept alive until explicitly terminated
+    static ref PERSISTENT_PROCESSES: Mutex<Vec<PersistentHandle>> = Mutex::new(vec![]);
+}
+
+struct PersistentHandle {
+    /// Foreground processes are waited on until they exit on their own.
+    foreground: bool,
+
+    handle: JoinHandle<()>,
+
+    stop: oneshot::Sender<()>,
+}
+
+/// Terminate all running child processes, and any that are spawned afterwards.
+/// This is primarily used to propagate Ctrl+C to child processes.
+pub fn cancel_all_processes() {
+    CANCELLED.store(true, Ordering::SeqCst);
+    CANCEL_NOTIFIER.notify_waiters();
+}
+
+/// Like `cancel_all_processes`, but processes that are being terminated are
+/// killed immediately, instead of waiting for them to exit gracefully.
+/// This is primarily used when Ctrl+C is pressed a second time.
+pub fn kill_all_processes() {
+    KILLED.store(true, Ordering::SeqCst);
+    KILL_NOTIFIER.notify_waiters();
+    cancel_all_processes();
+}
+
+/// Reset the cancelled state, so that processes can be spawned again
+/// after a previous run was cancelled (for example, when watching).
+pub fn reset_cancellation() {
+    CANCELLED.store(false, Ordering::SeqCst);
+    KILLED.store(false, Ordering::SeqCst);
+}
+
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Send a SIGTERM to the child's process group so that it (and any processes
+/// it spawned) can exit gracefully, and if it hasn't exited after a grace period
+/// (or all processes are killed in the meantime), send a SIGKILL.
+async fn terminate_child(child: &mut Child) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        let pid = pid as libc::pid_t;
+
+        // SAFETY: The process has not been waited on yet, so the ID is still ours.
+        // Children that are not a group leader are signaled directly instead.
+        unsafe {
+            if libc::kill(-pid, libc::SIGTERM) != 0 {
+                libc::kill(pid, libc::SIGTERM);
+            }
+        }
+
+        // Create the listener *before* checking, so that we don't miss a notification
+        let killed = KILL_NOTIFIER.notified();
+
+        if !KILLED.load(Ordering::SeqCst) {
+            tokio::select! {
+                result = time::timeout(TERMINATE_GRACE_PERIOD, child.wait()) => {
+                    if result.is_ok() {
+                        return Ok(());
+                    }
+                }
+                _ = killed => {}
+            };
+        }
+
+        // SAFETY: Same as above, the child has still not been waited on
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+
+    // Windows has no equivalent of SIGTERM, so always kill
+    child.kill().await
+}
+
+/// Terminate all persistent processes that are still running, and wait for them to exit.
+/// When `wait_for_foreground` is enabled, foreground processes are waited on until they
+/// exit on their own (or are cancelled), and background processes are kept alive until then.
+pub async fn terminate_persistent_processes(wait_for_foreground: bool) {
+    let (foreground, background): (Vec<_>, Vec<_>) = PERSISTENT_PROCESSES
+        .lock()
+        .unwrap()
+        .drain(..)
+        .partition(|process| process.foreground);
+
+    for process in foreground {
+        if !wait_for_foreground {
+            let _ = process.stop.send(());
+        }
+
+        let _ = process.handle.await;
+    }
+
+    for process in background {
+        // The process may have already exited, in which case nothing is listening
+        let _ = process.stop.send(());
+        let _ = process.handle.await;
+    }
+}
+
+/// A process spawned with `Command::spawn_persistent`.
+pub struct PersistentProcess {
+    /// Resolves with the exit status once the process has exited,
+    /// or `None` if it was terminated.
+    pub exited: oneshot::Receiver<Option<ExitStatus>>,
+
+    /// Resolves once a line of output matches the ready pattern.
+    pub ready: Option<oneshot::Receiver<()>>,
+}
+
+/// Stream each line of a persistent process's output to the parent,
+/// while checking it against the ready pattern.
+fn stream_persistent_output<R>(
+    reader: R,
+    is_stderr: bool,
+    prefix: Arc<str>,
+    ready: Option<ReadySignal>,
+) where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    task::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+
+        while let Some(line) = lines.next_line().await.unwrap_or_default() {
+            if is_stderr {
+                eprintln!("{} {}", prefix, line);
+            } else {
+                println!("{} {}", prefix, line);
+            }
+
+            if let Some(ready) = &ready {
+                if ready.0.is_match(&line) {
+                    if let Some(sender) = ready.1.lock().unwrap().take() {
+                        let _ = sender.send(());
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Wait for a task reading the output of a terminated process. Processes it spawned
+/// may have escaped termination and still hold the pipes open, so only wait for
+/// the grace period, and return whatever the task produced (if anything).
+async fn join_partial_output<T>(handle: JoinHandle<T>) -> Option<T> {
+    time::timeout(TERMINATE_GRACE_PERIOD, handle)
+        .await
+        .ok()?
+        .ok()
+}
+
+/// Attach the output captured before a process was terminated to the error,
+/// so that it's not lost when debugging timeouts and cancellations.
+fn attach_partial_output(error: MoonError, stdout: Vec<u8>, stderr: Vec<u8>) -> MoonError {
+    match error {
+        MoonError::ProcessCancelled(bin, _, _) => MoonError::ProcessCancelled(bin, stdout, stderr),
+        MoonError::ProcessTimeout(bin, secs, _, _) => {
+            MoonError::ProcessTimeout(bin, secs, stdout, stderr)
+        }
+        error => error,
+    }
+}
+
+enum WaitResult {
+    Cancelled,
+    Exited(std::io::Result<ExitStatus>),
+    TimedOut(Duration),
+}
+
+fn is_stdin_terminal() -> bool {
+    #[cfg(unix)]
+    // SAFETY: `isatty` only inspects the file descriptor
+    unsafe {
+        libc::isatty(libc::STDIN_FILENO) == 1
+    }
+
+    #[cfg(not(unix))]
+    false
+}
+
 pub fn is_windows_script(bin: &str) -> bool {
     bin.ends_with(".cmd") || bin.ends_with(".bat")
 }
@@ -55,6 +262,9 @@ pub struct Command {
 
     /// Convert non-zero exits to errors.
     error: bool,
+
+    /// Terminate the process if it runs longer than this duration.
+    timeout: Option<Duration>,
 }
 
 // This is rather annoying that we have to re-implement all these methods,
@@ -82,6 +292,7 @@ impl Command {
             bin: bin_name,
             cmd,
             error: true,
+            timeout: None,
         }
     }
 
@@ -125,12 +336,18 @@ impl Command {
 
     pub async fn exec_capture_output(&mut self) -> Result<Output, MoonError> {
         self.log_command_info(None);
+        self.isolate_process_group();
 
-        let output = self.cmd.output();
-        let output = output
-            .await
+        let child = self
+            .cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
             .map_err(|e| map_io_to_process_error(e, &self.bin))?;
 
+        let output = self.wait_with_output(child).await?;
+
         self.handle_nonzero_status(&output)?;
 
         Ok(output)
@@ -142,6 +359,7 @@ impl Command {
         input: &str,
     ) -> Result<Output, MoonError> {
         self.log_command_info(Some(input));
+        self.isolate_process_group();
 
         let mut child = self
             .cmd
@@ -155,10 +373,7 @@ impl Command {
         stdin.write_all(input.as_bytes()).await.unwrap();
         drop(stdin);
 
-        let output = child
-            .wait_with_output()
-            .await
-            .map_err(|e| map_io_to_process_error(e, &self.bin))?;
+        let output = self.wait_with_output(child).await?;
 
         self.handle_nonzero_status(&output)?;
 
@@ -168,14 +383,13 @@ impl Command {
     pub async fn exec_stream_output(&mut self) -> Result<ExitStatus, MoonError> {
         self.log_command_info(None);
 
-        let status = self
+        let mut child = self
             .cmd
             .spawn()
-            .map_err(|e| map_io_to_process_error(e, &self.bin))?
-            .wait()
-            .await
             .map_err(|e| map_io_to_process_error(e, &self.bin))?;
 
+        let status = self.wait_for_child(&mut child).await?;
+
         if self.error && !status.success() {
             return Err(MoonError::ProcessNonZero(
                 self.bin.clone(),
@@ -193,6 +407,13 @@ impl Command {
     ) -> Result<Output, MoonError> {
         self.log_command_info(None);
 
+        // The child inherits stdin, and reading a terminal from a background
+        // process group stops the child (SIGTTIN), so only isolate when the
+        // terminal is not attached (CI, pipes, etc)
+        if !is_stdin_terminal() {
+            self.isolate_process_group();
+        }
+
         let mut child = self
             .cmd
             .stderr(Stdio::piped())
@@ -262,27 +483,123 @@ impl Command {
                 .extend(captured_lines);
         }));
 
+        // Wait for the child to exit *before* the output has been fully read,
+        // so that a timeout or cancellation can terminate it
+        let status = match self.wait_for_child(&mut child).await {
+            Ok(status) => status,
+            Err(error) => {
+                for handle in handles {
+                    join_partial_output(handle).await;
+                }
+
+                return Err(attach_partial_output(
+                    error,
+                    captured_stdout.read().unwrap().join("\n").into_bytes(),
+                    captured_stderr.read().unwrap().join("\n").into_bytes(),
+                ));
+            }
+        };
+
         for handle in handles {
             handle.await.expect("Failed to capture stdout/stderr");
         }
 
-        // Attempt to capture the child output
-        let mut output = child
-            .wait_with_output()
-            .await
+        let output = Output {
+            status,
+            stderr: captured_stderr.read().unwrap().join("\n").into_bytes(),
+            stdout: captured_stdout.read().unwrap().join("\n").into_bytes(),
+        };
+
+        self.handle_nonzero_status(&output)?;
+
+        Ok(output)
+    }
+
+    /// Spawn a long-running process in the background, whose output is streamed
+    /// to the parent with the provided prefix. The process is kept alive until
+    /// `terminate_persistent_processes` is called, or all processes are cancelled.
+    /// Foreground processes may instead be waited on until they exit on their own.
+    pub fn spawn_persistent(
+        &mut self,
+        prefix: &str,
+        ready_pattern: Option<Regex>,
+        foreground: bool,
+    ) -> Result<PersistentProcess, MoonError> {
+        self.log_command_info(None);
+        self.isolate_process_group();
+
+        let mut child = self
+            .cmd
+            .stdin(Stdio::null())
+            .stderr(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
             .map_err(|e| map_io_to_process_error(e, &self.bin))?;
 
-        if output.stderr.is_empty() {
-            output.stderr = captured_stderr.read().unwrap().join("\n").into_bytes();
-        }
+        let prefix: Arc<str> = color::muted(&format!("[{}]", prefix)).into();
+        let mut ready_receiver = None;
+        let ready_signal = ready_pattern.map(|pattern| {
+            let (sender, receiver) = oneshot::channel();
 
-        if output.stdout.is_empty() {
-            output.stdout = captured_stdout.read().unwrap().join("\n").into_bytes();
-        }
+            ready_receiver = Some(receiver);
 
-        self.handle_nonzero_status(&output)?;
+            Arc::new((pattern, Mutex::new(Some(sender))))
+        });
 
-        Ok(output)
+        // Both streams are checked, as some tools log their status to stderr
+        stream_persistent_output(
+            child.stderr.take().unwrap(),
+            true,
+            Arc::clone(&prefix),
+            ready_signal.clone(),
+        );
+
+        stream_persistent_output(child.stdout.take().unwrap(), false, prefix, ready_signal);
+
+        let bin = self.bin.clone();
+        let (exit_sender, exit_receiver) = oneshot::channel();
+        let (stop_sender, stop_receiver) = oneshot::channel::<()>();
+
+        let handle = task::spawn(async move {
+            let cancelled = CANCEL_NOTIFIER.notified();
+
+            let status = if is_cancelled() {
+                None
+            } else {
+                tokio::select! {
+                    status = child.wait() => Some(status),
+                    _ = stop_receiver => None,
+                    _ = cancelled => None,
+                }
+            };
+
+            match status {
+                Some(status) => {
+                    let _ = exit_sender.send(status.ok());
+                }
+                None => {
+                    trace!(
+                        target: "moon:utils",
+                        "Terminating persistent process {}",
+                        color::shell(&bin)
+                    );
+
+                    let _ = terminate_child(&mut child).await;
+                    let _ = exit_sender.send(None);
+                }
+            };
+        });
+
+        PERSISTENT_PROCESSES.lock().unwrap().push(PersistentHandle {
+            foreground,
+            handle,
+            stop: stop_sender,
+        });
+
+        Ok(PersistentProcess {
+            exited: exit_receiver,
+            ready: ready_receiver,
+        })
     }
 
     pub fn get_command_line(&self) -> (String, Option<&Path>) {
@@ -303,6 +620,26 @@ impl Command {
         (path::replace_home_dir(&line), cmd.get_current_dir())
     }
 
+    /// Return all environment variables explicitly set on the command, sorted by name.
+    pub fn get_env_vars(&self) -> Vec<(String, String)> {
+        let mut vars = self
+            .cmd
+            .as_std()
+            .get_envs()
+            .filter_map(|(key, value)| {
+                value.map(|v| {
+                    (
+                        key.to_string_lossy().to_string(),
+                        v.to_string_lossy().to_string(),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        vars.sort();
+        vars
+    }
+
     pub fn inherit_colors(&mut self) -> &mut Command {
         let level = color::supports_color().to_string();
 
@@ -318,11 +655,33 @@ impl Command {
         self
     }
 
+    /// Spawn the child in its own process group, so that terminating it also
+    /// terminates the processes it spawns (jest workers, `npm run` scripts, etc).
+    /// Not used for commands that may read from the terminal, as they must stay
+    /// in the foreground process group.
+    fn isolate_process_group(&mut self) -> &mut Command {
+        #[cfg(unix)]
+        // SAFETY: `setpgid` is async-signal-safe and doesn't allocate
+        unsafe {
+            self.cmd.pre_exec(|| {
+                libc::setpgid(0, 0);
+                Ok(())
+            });
+        }
+
+        self
+    }
+
     pub fn no_error_on_failure(&mut self) -> &mut Command {
         self.error = false;
         self
     }
 
+    pub fn timeout(&mut self, duration: Duration) -> &mut Command {
+        self.timeout = Some(duration);
+        self
+    }
+
     pub fn output_to_error(&self, output: &Output, with_message: bool) -> MoonError {
         let code = output.status.code().unwrap_or(-1);
 
@@ -343,6 +702,114 @@ impl Command {
         Ok(())
     }
 
+    /// Wait for the child process to exit, while terminating it if the
+    /// timeout has been reached, or all processes have been cancelled.
+    async fn wait_for_child(&self, child: &mut Child) -> Result<ExitStatus, MoonError> {
+        // Create the listener *before* checking, so that we don't miss a notification
+        let cancelled = CANCEL_NOTIFIER.notified();
+
+        let result = if is_cancelled() {
+            WaitResult::Cancelled
+        } else {
+            let timeout = self.timeout;
+            let timed_out = async move {
+                match timeout {
+                    Some(duration) => {
+                        time::sleep(duration).await;
+                        duration
+                    }
+                    None => future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                status = child.wait() => WaitResult::Exited(status),
+                duration = timed_out => WaitResult::TimedOut(duration),
+                _ = cancelled => WaitResult::Cancelled,
+            }
+        };
+
+        let handle_error = |e| map_io_to_process_error(e, &self.bin);
+
+        match result {
+            WaitResult::Exited(status) => status.map_err(handle_error),
+            WaitResult::TimedOut(duration) => {
+                trace!(
+                    target: "moon:utils",
+                    "Process {} timed out, terminating",
+                    color::shell(&self.bin)
+                );
+
+                terminate_child(child).await.map_err(handle_error)?;
+
+                Err(MoonError::ProcessTimeout(
+                    self.bin.clone(),
+                    duration.as_secs(),
+                    vec![],
+                    vec![],
+                ))
+            }
+            WaitResult::Cancelled => {
+                trace!(
+                    target: "moon:utils",
+                    "Process {} cancelled, terminating",
+                    color::shell(&self.bin)
+                );
+
+                terminate_child(child).await.map_err(handle_error)?;
+
+                Err(MoonError::ProcessCancelled(
+                    self.bin.clone(),
+                    vec![],
+                    vec![],
+                ))
+            }
+        }
+    }
+
+    /// Wait for the child process to exit while reading its piped output.
+    async fn wait_with_output(&self, mut child: Child) -> Result<Output, MoonError> {
+        let mut stderr = child.stderr.take().unwrap();
+        let mut stdout = child.stdout.take().unwrap();
+
+        let stderr_handle = task::spawn(async move {
+            let mut buffer = vec![];
+            stderr.read_to_end(&mut buffer).await.map(|_| buffer)
+        });
+
+        let stdout_handle = task::spawn(async move {
+            let mut buffer = vec![];
+            stdout.read_to_end(&mut buffer).await.map(|_| buffer)
+        });
+
+        let status = match self.wait_for_child(&mut child).await {
+            Ok(status) => status,
+            Err(error) => {
+                let stderr = join_partial_output(stderr_handle).await;
+                let stdout = join_partial_output(stdout_handle).await;
+
+                return Err(attach_partial_output(
+                    error,
+                    stdout.and_then(|r| r.ok()).unwrap_or_default(),
+                    stderr.and_then(|r| r.ok()).unwrap_or_default(),
+                ));
+            }
+        };
+        let handle_error = |e| map_io_to_process_error(e, &self.bin);
+
+        Ok(Output {
+            status,
+            stderr: stderr_handle
+                .await
+                .expect("Failed to capture stderr")
+                .map_err(handle_error)?,
+            stdout: stdout_handle
+                .await
+                .expect("Failed to capture stdout")
+                .map_err(handle_error)?,
+        })
+    }
+
     #[track_caller]
     fn log_command_info(&self, input: Option<&str>) {
         // Avoid all this overhead if we're not logging
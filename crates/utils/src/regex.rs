@@ -1,21 +1,27 @@
 use lazy_static::lazy_static;
-use regex::Regex;
+
+pub use regex::{Captures, Error as RegexError, Regex};
 
 lazy_static! {
+    // ANSI escape sequences: CSI (colors, cursor), OSC (titles, links), and single character
+    pub static ref ANSI_ESCAPE_PATTERN: Regex = Regex::new(
+        "\\x1b\\[[0-?]*[ -/]*[@-~]|\\x1b\\][^\\x07\\x1b]*(?:\\x07|\\x1b\\\\)|\\x1b[@-_]").unwrap();
+
     // Capture group for IDs/names/etc
     static ref ID_GROUP: &'static str = "([A-Za-z]{1}[0-9A-Za-z_-]*)";
     static ref ID_CLEAN: Regex = Regex::new("[^a-z0-9_-]+").unwrap();
 
     pub static ref ID_PATTERN: Regex = Regex::new(&format!("^{}$", *ID_GROUP)).unwrap();
     pub static ref TARGET_PATTERN: Regex = Regex::new(
-        "^(?P<project>(?:[A-Za-z]{1}[0-9A-Za-z_-]*|\\^|~))?:(?P<task>[A-Za-z]{1}[0-9A-Za-z_-]*)$").unwrap();
+        "^(?P<project>(?:[A-Za-z]{1}[0-9A-Za-z_-]*|#[A-Za-z]{1}[0-9A-Za-z_-]*|\\^|~))?:(?P<task>[A-Za-z]{1}[0-9A-Za-z_-]*)$").unwrap();
 
     // Token function: `@func(arg)`
     static ref TOKEN_GROUP: &'static str = "([0-9A-Za-z_-]+)";
 
     pub static ref TOKEN_FUNC_PATTERN: Regex = Regex::new(&format!("^@([a-z]+)\\({}\\)$", *TOKEN_GROUP)).unwrap();
     pub static ref TOKEN_FUNC_ANYWHERE_PATTERN: Regex = Regex::new(&format!("@([a-z]+)\\({}\\)", *TOKEN_GROUP)).unwrap();
-    pub static ref TOKEN_VAR_PATTERN: Regex = Regex::new("\\$([a-zA-Z]+)").unwrap();
+    // Token variable: `$var` or `$ENV_VAR`
+    pub static ref TOKEN_VAR_PATTERN: Regex = Regex::new("\\$([a-zA-Z_][a-zA-Z0-9_]*)").unwrap();
 }
 
 pub fn clean_id(id: &str) -> String {
@@ -37,3 +43,7 @@ pub fn matches_token_func(token: &str) -> bool {
 pub fn matches_token_var(token: &str) -> bool {
     TOKEN_VAR_PATTERN.is_match(token)
 }
+
+pub fn strip_ansi(value: &str) -> String {
+    ANSI_ESCAPE_PATTERN.replace_all(value, "").to_string()
+}
@@ -1,20 +1,32 @@
+use moon_utils::time;
 use petgraph::graph::NodeIndex;
+use serde::Serialize;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 pub struct Attempt {
     pub duration: Option<Duration>,
 
+    pub exit_code: Option<i32>,
+
     pub index: u8,
 
     pub start_time: Instant,
+
+    pub stderr: String,
+
+    pub stdout: String,
 }
 
 impl Attempt {
     pub fn new(index: u8) -> Self {
         Attempt {
             duration: None,
+            exit_code: None,
             index,
             start_time: Instant::now(),
+            stderr: String::new(),
+            stdout: String::new(),
         }
     }
 
@@ -23,24 +35,55 @@ impl Attempt {
     }
 }
 
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum ActionStatus {
     Cached,
-    // CachedFromRemote, // TODO
+    CachedFromRemote,
     Failed,
     FailedAndAbort,
     Invalid,
     Passed,
     Running,
-    Skipped, // When nothing happened
+    Skipped,            // When nothing happened
+    SkippedFromFailure, // When a dependency failed
+}
+
+impl ActionStatus {
+    pub fn label(&self) -> &str {
+        match self {
+            ActionStatus::Cached => "cached",
+            ActionStatus::CachedFromRemote => "cached from remote",
+            ActionStatus::Failed => "failed",
+            ActionStatus::FailedAndAbort => "failed (aborted)",
+            ActionStatus::Invalid => "invalid",
+            ActionStatus::Passed => "passed",
+            ActionStatus::Running => "running",
+            ActionStatus::Skipped => "skipped",
+            ActionStatus::SkippedFromFailure => "skipped (dependency failed)",
+        }
+    }
 }
 
 pub struct Action {
     pub attempts: Option<Vec<Attempt>>,
 
+    /// Command line of the process that was ran, if applicable.
+    pub command: Option<String>,
+
     pub duration: Option<Duration>,
 
+    /// Environment variables explicitly passed to the process.
+    pub env_vars: Vec<(String, String)>,
+
     pub error: Option<String>,
 
+    /// Label of the dependency that failed, when skipped because of it.
+    pub failed_dependency: Option<String>,
+
+    /// Hash generated for the target, if applicable.
+    pub hash: Option<String>,
+
     pub label: Option<String>,
 
     pub node_index: NodeIndex,
@@ -48,18 +91,26 @@ pub struct Action {
     pub start_time: Instant,
 
     pub status: ActionStatus,
+
+    /// Working directory of the process that was ran, if applicable.
+    pub working_dir: Option<PathBuf>,
 }
 
 impl Action {
     pub fn new(node_index: NodeIndex) -> Self {
         Action {
             attempts: None,
+            command: None,
             duration: None,
+            env_vars: vec![],
             error: None,
+            failed_dependency: None,
+            hash: None,
             label: None,
             node_index,
             start_time: Instant::now(),
             status: ActionStatus::Running,
+            working_dir: None,
         }
     }
 
@@ -83,7 +134,157 @@ impl Action {
         self.duration = Some(self.start_time.elapsed());
     }
 
+    pub fn skip_from_failure(&mut self, dependency_label: String) {
+        self.failed_dependency = Some(dependency_label);
+        self.status = ActionStatus::SkippedFromFailure;
+        self.duration = Some(Duration::new(0, 0));
+    }
+
     pub fn should_abort(&self) -> bool {
         matches!(self.status, ActionStatus::FailedAndAbort)
     }
+
+    /// Render the action's command, environment, timing, and the output
+    /// of every attempt, for writing to a run log.
+    pub fn to_log(&self) -> String {
+        let mut lines = vec![];
+
+        if let Some(label) = &self.label {
+            lines.push(format!("Action: {}", label));
+        }
+
+        lines.push(format!("Status: {}", self.status.label()));
+
+        if let Some(duration) = self.duration {
+            lines.push(format!("Duration: {}", time::elapsed(duration)));
+        }
+
+        if let Some(command) = &self.command {
+            lines.push(format!("Command: {}", command));
+        }
+
+        if let Some(working_dir) = &self.working_dir {
+            lines.push(format!(
+                "Working directory: {}",
+                working_dir.to_string_lossy()
+            ));
+        }
+
+        if !self.env_vars.is_empty() {
+            lines.push(String::from("Environment:"));
+
+            // Values may be secrets (from env files for example), so only
+            // include the values of variables that moon itself has set
+            for (key, value) in &self.env_vars {
+                if key.starts_with("MOON_") {
+                    lines.push(format!("  {}={}", key, value));
+                } else {
+                    lines.push(format!("  {}=<redacted>", key));
+                }
+            }
+        }
+
+        if let Some(error) = &self.error {
+            lines.push(format!("Error: {}", error));
+        }
+
+        if let Some(attempts) = &self.attempts {
+            let total = attempts.len();
+
+            for attempt in attempts {
+                let mut comments = vec![];
+
+                if let Some(code) = attempt.exit_code {
+                    comments.push(format!("exit code {}", code));
+                }
+
+                if let Some(duration) = attempt.duration {
+                    comments.push(time::elapsed(duration));
+                }
+
+                lines.push(String::new());
+                lines.push(format!(
+                    "Attempt {}/{} ({})",
+                    attempt.index,
+                    total,
+                    comments.join(", ")
+                ));
+                lines.push(String::from("--- stdout ---"));
+                lines.push(attempt.stdout.trim_end().to_owned());
+                lines.push(String::from("--- stderr ---"));
+                lines.push(attempt.stderr.trim_end().to_owned());
+            }
+        }
+
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod to_log {
+        use super::*;
+
+        #[test]
+        fn includes_details() {
+            let mut action = Action::new(NodeIndex::new(0));
+            action.label = Some(String::from("RunTarget(app:build)"));
+            action.command = Some(String::from("tsc --build"));
+            action.working_dir = Some(PathBuf::from("/workspace/app"));
+            action.env_vars = vec![
+                (String::from("MOON_PROJECT_ID"), String::from("app")),
+                (String::from("SECRET"), String::from("hunter2")),
+            ];
+            action.pass(ActionStatus::Passed);
+
+            let log = action.to_log();
+
+            assert!(log.contains("Action: RunTarget(app:build)"));
+            assert!(log.contains("Status: passed"));
+            assert!(log.contains("Command: tsc --build"));
+            assert!(log.contains("Working directory: /workspace/app"));
+            assert!(log.contains("Environment:\n  MOON_PROJECT_ID=app"));
+            assert!(log.contains("  SECRET=<redacted>"));
+            assert!(!log.contains("hunter2"));
+        }
+
+        #[test]
+        fn includes_all_attempts() {
+            let mut action = Action::new(NodeIndex::new(0));
+
+            let mut attempt1 = Attempt::new(1);
+            attempt1.exit_code = Some(1);
+            attempt1.stderr = String::from("flaky failure");
+            attempt1.done();
+
+            let mut attempt2 = Attempt::new(2);
+            attempt2.exit_code = Some(0);
+            attempt2.stdout = String::from("it worked");
+            attempt2.done();
+
+            action.attempts = Some(vec![attempt1, attempt2]);
+            action.pass(ActionStatus::Passed);
+
+            let log = action.to_log();
+
+            assert!(log.contains("Attempt 1/2 (exit code 1"));
+            assert!(log.contains("flaky failure"));
+            assert!(log.contains("Attempt 2/2 (exit code 0"));
+            assert!(log.contains("it worked"));
+        }
+
+        #[test]
+        fn includes_error() {
+            let mut action = Action::new(NodeIndex::new(0));
+            action.fail(String::from("Process failed"));
+
+            let log = action.to_log();
+
+            assert!(log.contains("Status: failed"));
+            assert!(log.contains("Error: Process failed"));
+        }
+    }
 }
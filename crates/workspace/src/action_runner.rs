@@ -2,36 +2,98 @@ use crate::action::{Action, ActionStatus};
 use crate::actions::{install_node_deps, run_target, setup_toolchain, sync_project};
 use crate::dep_graph::{DepGraph, Node};
 use crate::errors::WorkspaceError;
+use crate::report::RunReport;
+use crate::scheduler::Scheduler;
 use crate::workspace::Workspace;
-use moon_logger::{color, debug, error, trace};
-use std::sync::Arc;
+use futures::stream::{FuturesUnordered, StreamExt};
+use moon_cache::DAY_MILLIS;
+use moon_logger::{color, debug, error, trace, warn};
+use moon_project::TargetID;
+use moon_utils::process;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Once};
+use std::thread;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tokio::task;
 
 const LOG_TARGET: &str = "moon:action-runner";
 
+// Whether actions are currently running, which determines how Ctrl+C is handled
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+static LISTEN_FOR_INTERRUPTS: Once = Once::new();
+
+struct RunningGuard;
+
+impl RunningGuard {
+    fn new() -> Self {
+        RUNNING.store(true, Ordering::SeqCst);
+        RunningGuard
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Listen for Ctrl+C for the remainder of the process, as Tokio's signal handler
+/// can't be uninstalled once registered. While running, the first interrupt cancels
+/// all actions (terminating their processes), and the second kills their processes
+/// immediately. Otherwise the process exits, like it would without a handler.
+fn listen_for_interrupts() {
+    LISTEN_FOR_INTERRUPTS.call_once(|| {
+        task::spawn(async {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                if !RUNNING.load(Ordering::SeqCst) {
+                    std::process::exit(130);
+                }
+
+                if process::is_cancelled() {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Received another interrupt, killing running processes"
+                    );
+
+                    process::kill_all_processes();
+                } else {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Received interrupt, cancelling running actions"
+                    );
+
+                    process::cancel_all_processes();
+                }
+            }
+        });
+    });
+}
+
 async fn run_action(
     workspace: Arc<RwLock<Workspace>>,
     action: &mut Action,
     action_node: &Node,
-    primary_target: &str,
+    primary_targets: &HashSet<TargetID>,
     passthrough_args: &[String],
 ) -> Result<(), WorkspaceError> {
     let result = match action_node {
-        Node::InstallNodeDeps => install_node_deps(workspace).await,
+        Node::InstallNodeDeps => install_node_deps(Arc::clone(&workspace)).await,
         Node::RunTarget(target_id) => {
             run_target(
-                workspace,
+                Arc::clone(&workspace),
                 action,
                 target_id,
-                primary_target,
+                primary_targets,
                 passthrough_args,
             )
             .await
         }
-        Node::SetupToolchain => setup_toolchain(workspace).await,
-        Node::SyncProject(project_id) => sync_project(workspace, project_id).await,
+        Node::SetupToolchain => setup_toolchain(Arc::clone(&workspace)).await,
+        Node::SyncProject(project_id) => sync_project(Arc::clone(&workspace), project_id).await,
     };
 
     match result {
@@ -50,17 +112,39 @@ async fn run_action(
         }
     }
 
+    // Persist the action's output, as the run state is overwritten on every run
+    let workspace = workspace.read().await;
+
+    if let Err(error) = workspace
+        .cache
+        .create_run_log(&action_node.log_name(), &action.to_log())
+        .await
+    {
+        warn!(
+            target: LOG_TARGET,
+            "Failed to write run log for {}: {}",
+            color::muted_light(&action_node.label()),
+            error
+        );
+    }
+
     Ok(())
 }
 
 pub struct ActionRunner {
     bail: bool,
 
+    concurrency: usize,
+
     pub duration: Option<Duration>,
 
     passthrough_args: Vec<String>,
 
-    primary_target: String,
+    primary_targets: HashSet<TargetID>,
+
+    report_path: Option<PathBuf>,
+
+    skip_failed_dependents: bool,
 
     workspace: Arc<RwLock<Workspace>>,
 }
@@ -71,9 +155,14 @@ impl ActionRunner {
 
         ActionRunner {
             bail: false,
+            concurrency: thread::available_parallelism()
+                .map(|count| count.get())
+                .unwrap_or(1),
             duration: None,
             passthrough_args: Vec::new(),
-            primary_target: String::new(),
+            primary_targets: HashSet::new(),
+            report_path: None,
+            skip_failed_dependents: false,
             workspace: Arc::new(RwLock::new(workspace)),
         }
     }
@@ -91,17 +180,30 @@ impl ActionRunner {
 
         workspace.cache.delete_runfiles().await?;
 
+        // Prune stale cache entries at most once a day, as it requires
+        // reading every hash manifest in the cache
+        if let Some(prune_config) = &workspace.config.cache.prune {
+            let mut state = workspace.cache.cache_workspace_state().await?;
+            let now = state.now_millis();
+
+            if state.item.last_prune_time + DAY_MILLIS <= now {
+                workspace.cache.prune(prune_config).await?;
+
+                state.item.last_prune_time = now;
+                state.save().await?;
+            }
+        }
+
         Ok(())
     }
 
     pub async fn run(&mut self, graph: DepGraph) -> Result<Vec<Action>, WorkspaceError> {
         let start = Instant::now();
         let node_count = graph.graph.node_count();
-        let batches = graph.sort_batched_topological()?;
-        let batches_count = batches.len();
+        let mut scheduler = Scheduler::new(&graph)?;
         let graph = Arc::new(RwLock::new(graph));
         let passthrough_args = Arc::new(self.passthrough_args.clone());
-        let primary_target = Arc::new(self.primary_target.clone());
+        let primary_targets = Arc::new(self.primary_targets.clone());
 
         // Clean the runner state *before* running actions instead of after,
         // so that failing or broken builds can dig into and debug the state!
@@ -109,30 +211,40 @@ impl ActionRunner {
 
         debug!(
             target: LOG_TARGET,
-            "Running {} actions across {} batches", node_count, batches_count
+            "Running {} actions with a concurrency of {}", node_count, self.concurrency
         );
 
-        let mut results: Vec<Action> = vec![];
+        // A previous run may have been cancelled within the same process (when watching)
+        process::reset_cancellation();
 
-        for (b, batch) in batches.into_iter().enumerate() {
-            let batch_count = b + 1;
-            let batch_target_name = format!("{}:batch:{}", LOG_TARGET, batch_count);
-            let actions_count = batch.len();
+        listen_for_interrupts();
 
-            trace!(
-                target: &batch_target_name,
-                "Running {} actions",
-                actions_count
-            );
+        let _running = RunningGuard::new();
 
-            let mut action_handles = vec![];
+        let mut results: Vec<Action> = vec![];
+        let mut run_error = None;
+        let mut action_handles = FuturesUnordered::new();
+        let mut action_count = 0;
+
+        loop {
+            // Start as many ready actions as the concurrency limit allows. Once cancelled,
+            // stop scheduling, but wait for running actions so their processes are terminated.
+            if run_error.is_none() && process::is_cancelled() {
+                run_error = Some(WorkspaceError::ActionRunnerCancelled);
+            }
+
+            while run_error.is_none() && action_handles.len() < self.concurrency {
+                let node_index = match scheduler.next_ready() {
+                    Some(index) => index,
+                    None => break,
+                };
+
+                action_count += 1;
 
-            for (i, node_index) in batch.into_iter().enumerate() {
-                let action_count = i + 1;
                 let workspace_clone = Arc::clone(&self.workspace);
                 let graph_clone = Arc::clone(&graph);
                 let passthrough_args_clone = Arc::clone(&passthrough_args);
-                let primary_target_clone = Arc::clone(&primary_target);
+                let primary_targets_clone = Arc::clone(&primary_targets);
 
                 action_handles.push(task::spawn(async move {
                     let mut action = Action::new(node_index);
@@ -141,8 +253,7 @@ impl ActionRunner {
                     if let Some(node) = own_graph.get_node_from_index(node_index) {
                         action.label = Some(node.label());
 
-                        let log_target_name =
-                            format!("{}:batch:{}:{}", LOG_TARGET, batch_count, action_count);
+                        let log_target_name = format!("{}:{}", LOG_TARGET, action_count);
                         let log_action_label = color::muted_light(&node.label());
 
                         trace!(
@@ -155,7 +266,7 @@ impl ActionRunner {
                             workspace_clone,
                             &mut action,
                             node,
-                            &primary_target_clone,
+                            &primary_targets_clone,
                             &passthrough_args_clone,
                         )
                         .await?;
@@ -185,36 +296,97 @@ impl ActionRunner {
                 }));
             }
 
-            // Wait for all actions in this batch to complete,
-            // while also handling and propagating errors
-            for handle in action_handles {
-                match handle.await {
-                    Ok(Ok(result)) => {
-                        if result.should_abort() {
-                            error!(
-                                target: &batch_target_name,
-                                "Encountered a critical error, aborting the action runner"
-                            );
-                        }
+            // Wait for the next action to complete, while also handling
+            // and propagating errors, and unblocking its dependents
+            let handle = match action_handles.next().await {
+                Some(handle) => handle,
+                None => break,
+            };
+
+            match handle {
+                Ok(Ok(result)) => {
+                    if result.should_abort() {
+                        error!(
+                            target: LOG_TARGET,
+                            "Encountered a critical error, aborting the action runner"
+                        );
+                    }
+
+                    let should_bail = self.bail && result.error.is_some() || result.should_abort();
+                    let error = result.error.clone();
+                    let mut skipped = vec![];
+
+                    // When enabled, dependents of a failed action are skipped,
+                    // while unrelated branches of the graph continue to run
+                    if result.has_failed() && self.skip_failed_dependents {
+                        let failed_label = result.label.clone().unwrap_or_default();
+                        let own_graph = graph.read().await;
 
-                        if self.bail && result.error.is_some() || result.should_abort() {
-                            return Err(WorkspaceError::ActionRunnerFailure(result.error.unwrap()));
+                        for node_index in scheduler.fail(result.node_index) {
+                            let mut action = Action::new(node_index);
+
+                            if let Some(node) = own_graph.get_node_from_index(node_index) {
+                                action.label = Some(node.label());
+
+                                debug!(
+                                    target: LOG_TARGET,
+                                    "Skipping action {} as its dependency {} failed",
+                                    color::muted_light(&node.label()),
+                                    color::muted_light(&failed_label)
+                                );
+                            }
+
+                            action.skip_from_failure(failed_label.clone());
+                            skipped.push(action);
                         }
+                    } else {
+                        scheduler.complete(result.node_index);
+                    }
+
+                    results.push(result);
+                    results.extend(skipped);
 
-                        results.push(result);
+                    if should_bail && run_error.is_none() {
+                        run_error = Some(WorkspaceError::ActionRunnerFailure(error.unwrap()));
                     }
-                    Ok(Err(e)) => {
-                        return Err(e);
+                }
+                Ok(Err(e)) => {
+                    if run_error.is_none() {
+                        run_error = Some(e);
                     }
-                    Err(e) => {
-                        return Err(WorkspaceError::ActionRunnerFailure(e.to_string()));
+                }
+                Err(e) => {
+                    if run_error.is_none() {
+                        run_error = Some(WorkspaceError::ActionRunnerFailure(e.to_string()));
                     }
                 }
             }
+
+            // When bailing, stop scheduling and terminate the processes of in-flight
+            // actions, but keep waiting on them (like cancellation), so that they don't
+            // outlive the runner and are included in the results
+            if run_error.is_some() && !action_handles.is_empty() && !process::is_cancelled() {
+                process::cancel_all_processes();
+            }
         }
 
+        // Persistent targets are kept alive until the run has ended, while primary
+        // persistent targets are waited on until they exit (or are cancelled)
+        process::terminate_persistent_processes(run_error.is_none()).await;
+
         self.duration = Some(start.elapsed());
 
+        // Write the report before bailing, so that failures are also reported
+        if let Some(report_path) = &self.report_path {
+            RunReport::new(&results, self.duration.unwrap())
+                .write(report_path)
+                .await?;
+        }
+
+        if let Some(error) = run_error {
+            return Err(error);
+        }
+
         debug!(
             target: LOG_TARGET,
             "Finished running {} actions in {:?}",
@@ -225,13 +397,28 @@ impl ActionRunner {
         Ok(results)
     }
 
+    pub fn set_concurrency(&mut self, concurrency: usize) -> &mut Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
     pub fn set_passthrough_args(&mut self, args: Vec<String>) -> &mut Self {
         self.passthrough_args = args;
         self
     }
 
-    pub fn set_primary_target(&mut self, target: &str) -> &mut Self {
-        self.primary_target = target.to_owned();
+    pub fn set_primary_targets(&mut self, targets: HashSet<TargetID>) -> &mut Self {
+        self.primary_targets = targets;
+        self
+    }
+
+    pub fn skip_failed_dependents(&mut self) -> &mut Self {
+        self.skip_failed_dependents = true;
+        self
+    }
+
+    pub fn set_report_path(&mut self, path: Option<PathBuf>) -> &mut Self {
+        self.report_path = path;
         self
     }
 }
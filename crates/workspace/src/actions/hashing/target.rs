@@ -1,3 +1,4 @@
+use crate::platforms::get_platform;
 use crate::{Workspace, WorkspaceError};
 use moon_hasher::TargetHasher;
 use moon_project::{ExpandedFiles, Project, Task};
@@ -42,27 +43,20 @@ pub async fn create_target_hasher(
     hasher.hash_task(task);
     hasher.hash_args(passthrough_args);
 
-    // Hash root configs first
-    hasher.hash_package_json(&workspace.package_json);
+    // Hash process env vars from both the workspace and the task
+    let mut env_var_patterns = workspace.config.action_runner.hash_env_vars.clone();
+    env_var_patterns.extend(task.options.hash_env_vars.clone());
 
-    if let Some(root_tsconfig) = &workspace.tsconfig_json {
-        hasher.hash_tsconfig_json(root_tsconfig);
-    }
+    hasher.hash_process_env_vars(
+        &env_var_patterns,
+        &workspace.config.action_runner.redact_env_vars,
+    );
 
-    // Hash project configs second so they can override
-    project.load_package_json().await?;
-    project
-        .load_tsconfig_json(&workspace.config.typescript.project_config_file_name)
+    // Hash inputs specific to the platform the task runs on
+    get_platform(&task.type_of)
+        .hash_run_target(workspace, project, &mut hasher)
         .await?;
 
-    if let Some(package) = project.package_json.get() {
-        hasher.hash_package_json(package);
-    }
-
-    if let Some(tsconfig) = project.tsconfig_json.get() {
-        hasher.hash_tsconfig_json(tsconfig);
-    }
-
     // For input files, hash them with the vcs layer first
     if !task.input_paths.is_empty() {
         let files = convert_paths_to_strings(&task.input_paths, &workspace.root)?;
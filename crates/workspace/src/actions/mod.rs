@@ -1,5 +1,6 @@
 pub mod hashing;
 mod install_node_deps;
+mod ready_check;
 mod run_target;
 mod setup_toolchain;
 mod sync_project;
@@ -0,0 +1,86 @@
+use crate::errors::WorkspaceError;
+use moon_config::TaskReadyCheckConfig;
+use moon_logger::{color, trace};
+use moon_utils::process::PersistentProcess;
+use std::future;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout};
+
+const LOG_TARGET: &str = "moon:action:ready-check";
+
+const DEFAULT_TIMEOUT: u64 = 60;
+
+// How often to check whether a port or URL is available
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+async fn wait_for_port(port: u16) {
+    trace!(
+        target: LOG_TARGET,
+        "Waiting for port {} to accept connections",
+        color::symbol(&port.to_string())
+    );
+
+    while TcpStream::connect(("127.0.0.1", port)).await.is_err() {
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+async fn wait_for_url(url: &str) {
+    trace!(
+        target: LOG_TARGET,
+        "Waiting for {} to respond",
+        color::url(url)
+    );
+
+    let client = reqwest::Client::new();
+
+    loop {
+        if let Ok(response) = client.get(url).send().await {
+            if !response.status().is_server_error() {
+                return;
+            }
+        }
+
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Wait for a persistent process to pass all of its configured ready checks.
+/// Fails if the process exits beforehand, or the checks do not pass in time.
+pub async fn wait_for_ready(
+    target_id: &str,
+    process: &mut PersistentProcess,
+    check: &TaskReadyCheckConfig,
+) -> Result<(), WorkspaceError> {
+    let seconds = check.timeout.unwrap_or(DEFAULT_TIMEOUT);
+    let ready_output = process.ready.take();
+
+    let checks = async {
+        if let Some(ready_output) = ready_output {
+            // The output streams have closed without a match, so wait for the exit instead
+            if ready_output.await.is_err() {
+                future::pending::<()>().await;
+            }
+        }
+
+        if let Some(port) = check.port {
+            wait_for_port(port).await;
+        }
+
+        if let Some(url) = &check.url {
+            wait_for_url(url).await;
+        }
+    };
+
+    tokio::select! {
+        biased;
+
+        result = timeout(Duration::from_secs(seconds), checks) => {
+            result.map_err(|_| WorkspaceError::PersistentTargetNotReady(target_id.to_owned(), seconds))
+        }
+        _ = &mut process.exited => {
+            Err(WorkspaceError::PersistentTargetExited(target_id.to_owned()))
+        }
+    }
+}
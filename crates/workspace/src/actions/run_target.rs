@@ -1,18 +1,20 @@
 use crate::action::{Action, ActionStatus, Attempt};
 use crate::actions::hashing::create_target_hasher;
+use crate::actions::ready_check::wait_for_ready;
 use crate::errors::WorkspaceError;
+use crate::platforms::get_platform;
 use crate::workspace::Workspace;
 use moon_cache::RunTargetState;
-use moon_config::TaskType;
+use moon_error::MoonError;
 use moon_logger::{color, debug, trace, warn};
-use moon_project::{Project, Target, Task};
+use moon_project::{Project, Target, TargetID, Task};
 use moon_terminal::output::{label_checkpoint, Checkpoint};
-use moon_toolchain::{get_path_env_var, Executable};
 use moon_utils::process::{join_args, output_to_string, Command, Output};
-use moon_utils::{is_ci, is_test_env, path, string_vec, time};
-use std::collections::HashMap;
-use std::path::Path;
+use moon_utils::regex::Regex;
+use moon_utils::{fs, is_ci, is_test_env, path, time};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 const LOG_TARGET: &str = "moon:action:run-target";
@@ -65,174 +67,152 @@ async fn create_env_vars(
     Ok(env_vars)
 }
 
-fn create_node_options(task: &Task) -> Vec<String> {
-    string_vec![
-        // "--inspect", // Enable node inspector
-        "--preserve-symlinks",
-        "--title",
-        &task.target,
-        "--unhandled-rejections",
-        "throw",
-    ]
-}
-
-/// Runs a task command through our toolchain's installed Node.js instance.
-/// We accomplish this by executing the Node.js binary as a child process,
-/// while passing a file path to a package's node module binary (this is the file
-/// being executed). We then also pass arguments defined in the task.
-/// This would look something like the following:
-///
-/// ~/.moon/tools/node/1.2.3/bin/node --inspect /path/to/node_modules/.bin/eslint
-///     --cache --color --fix --ext .ts,.tsx,.js,.jsx
-#[cfg(not(windows))]
-#[track_caller]
-fn create_node_target_command(
+async fn create_target_command(
     workspace: &Workspace,
     project: &Project,
     task: &Task,
 ) -> Result<Command, WorkspaceError> {
-    let node = workspace.toolchain.get_node();
-    let mut cmd = node.get_bin_path();
-    let mut args = vec![];
+    let working_dir = if task.options.run_from_workspace_root {
+        &workspace.root
+    } else {
+        &project.root
+    };
 
-    match task.command.as_str() {
-        "node" => {
-            args.extend(create_node_options(task));
-        }
-        "npm" => {
-            cmd = node.get_npm().get_bin_path();
-        }
-        "pnpm" => {
-            cmd = node.get_pnpm().unwrap().get_bin_path();
-        }
-        "yarn" => {
-            cmd = node.get_yarn().unwrap().get_bin_path();
-        }
-        bin => {
-            let bin_path = node.find_package_bin(bin, &project.root)?;
+    debug!(
+        target: LOG_TARGET,
+        "Creating {} command (in working directory {})",
+        color::target(&task.target),
+        color::path(working_dir)
+    );
 
-            args.extend(create_node_options(task));
-            args.push(path::path_to_string(&bin_path)?);
-        }
-    };
+    let mut command = get_platform(&task.type_of).create_run_target_command(
+        workspace,
+        project,
+        task,
+        working_dir,
+    )?;
 
-    // Create the command
-    let mut command = Command::new(cmd);
+    let env_vars = create_env_vars(workspace, project, task).await?;
 
-    command.args(&args).args(&task.args).envs(&task.env).env(
-        "PATH",
-        get_path_env_var(node.get_bin_path().parent().unwrap()),
-    );
+    // Directories from `@cache` tokens are not created during expansion,
+    // so ensure they exist before the tool attempts to write to them
+    for cache_dir in &task.cache_dirs {
+        fs::create_dir_all(cache_dir).await?;
+    }
+
+    command
+        .cwd(working_dir)
+        .envs(env_vars)
+        // We need to handle non-zero's manually
+        .no_error_on_failure();
 
     Ok(command)
 }
 
-/// Windows works quite differently than other systems, so we cannot do the above.
-/// On Windows, the package binary is a ".cmd" file, which means it needs to run
-/// through "cmd.exe" and not "node.exe". Because of this, the order of operations
-/// is switched, and "node.exe" is detected through the `PATH` env var.
-#[cfg(windows)]
-#[track_caller]
-fn create_node_target_command(
+/// Rehydrate the task's outputs from the `.moon/cache/out` folder back into
+/// the project. If any output has not been archived, we abort without touching
+/// the project, so that the target can be ran again.
+async fn hydrate_outputs(
     workspace: &Workspace,
     project: &Project,
     task: &Task,
-) -> Result<Command, WorkspaceError> {
-    use moon_lang_node::node;
+    hash: &str,
+) -> Result<bool, WorkspaceError> {
+    let cache = &workspace.cache;
+
+    if !task
+        .output_paths
+        .iter()
+        .all(|output_path| cache.has_task_output_in_out(hash, &project.root, output_path))
+    {
+        return Ok(false);
+    }
 
-    let node = workspace.toolchain.get_node();
+    for output_path in &task.output_paths {
+        cache
+            .hydrate_task_output_from_out(hash, &project.root, output_path)
+            .await?;
+    }
 
-    let cmd = match task.command.as_str() {
-        "node" => node.get_bin_path().clone(),
-        "npm" => node.get_npm().get_bin_path().clone(),
-        "pnpm" => node.get_pnpm().unwrap().get_bin_path().clone(),
-        "yarn" => node.get_yarn().unwrap().get_bin_path().clone(),
-        bin => node.find_package_bin(bin, &project.root)?,
-    };
+    Ok(true)
+}
 
-    // Create the command
-    let mut command = Command::new(cmd);
+/// Persistent tasks (servers, watchers, etc) never exit on their own, so instead
+/// of waiting for the process to complete, we wait for it to be ready, and keep it
+/// running in the background until the action runner has finished. As such,
+/// persistent tasks are never cached.
+async fn run_persistent_target(
+    workspace: &Workspace,
+    action: &mut Action,
+    project: &Project,
+    task: &Task,
+    is_primary: bool,
+    passthrough_args: &[String],
+) -> Result<ActionStatus, WorkspaceError> {
+    let target_id = &task.target;
+    let mut command = create_target_command(workspace, project, task).await?;
+    command.args(passthrough_args);
 
-    command
-        .args(&task.args)
-        .envs(&task.env)
-        .env(
-            "PATH",
-            get_path_env_var(node.get_bin_path().parent().unwrap()),
-        )
-        .env(
-            "NODE_OPTIONS",
-            node::extend_node_options_env_var(&create_node_options(task).join(" ")),
-        );
+    if workspace
+        .config
+        .action_runner
+        .inherit_colors_for_piped_tasks
+    {
+        command.inherit_colors();
+    }
 
-    Ok(command)
-}
+    let (command_line, working_dir) = command.get_command_line();
 
-#[cfg(not(windows))]
-fn create_system_target_command(task: &Task, _cwd: &Path) -> Command {
-    let mut cmd = Command::new(&task.command);
-    cmd.args(&task.args).envs(&task.env);
-    cmd
-}
+    action.command = Some(command_line);
+    action.working_dir = working_dir.map(|dir| dir.to_path_buf());
+    action.env_vars = command.get_env_vars();
 
-#[cfg(windows)]
-fn create_system_target_command(task: &Task, cwd: &Path) -> Command {
-    use moon_utils::process::is_windows_script;
+    let ready_check = task.options.ready_check.clone().unwrap_or_default();
+    let ready_pattern = match &ready_check.output {
+        Some(pattern) => Some(Regex::new(pattern)?),
+        None => None,
+    };
 
-    let mut cmd = Command::new(&task.command);
+    let mut attempt = Attempt::new(1);
 
-    for arg in &task.args {
-        // cmd.exe requires an absolute path to batch files
-        if is_windows_script(arg) {
-            cmd.arg(cwd.join(arg));
-        } else {
-            cmd.arg(arg);
-        }
-    }
+    print_target_label(target_id, &attempt, 1, Checkpoint::Start);
+    print_target_command(workspace, project, task, passthrough_args);
 
-    cmd.envs(&task.env);
-    cmd
-}
+    // Primary targets are kept alive until they exit, instead of terminated when the run ends
+    let mut process = command.spawn_persistent(target_id, ready_pattern, is_primary)?;
+    let result = wait_for_ready(target_id, &mut process, &ready_check).await;
 
-async fn create_target_command(
-    workspace: &Workspace,
-    project: &Project,
-    task: &Task,
-) -> Result<Command, WorkspaceError> {
-    let working_dir = if task.options.run_from_workspace_root {
-        &workspace.root
-    } else {
-        &project.root
-    };
+    attempt.done();
+
+    if let Err(error) = result {
+        print_target_label(target_id, &attempt, 1, Checkpoint::Fail);
+        action.attempts = Some(vec![attempt]);
+
+        return Err(error);
+    }
+
+    action.attempts = Some(vec![attempt]);
 
     debug!(
         target: LOG_TARGET,
-        "Creating {} command (in working directory {})",
-        color::target(&task.target),
-        color::path(working_dir)
+        "Persistent target {} is ready",
+        color::id(target_id)
     );
 
-    let mut command = match task.type_of {
-        TaskType::Node => create_node_target_command(workspace, project, task)?,
-        _ => create_system_target_command(task, working_dir),
-    };
-
-    let env_vars = create_env_vars(workspace, project, task).await?;
-
-    command
-        .cwd(working_dir)
-        .envs(env_vars)
-        // We need to handle non-zero's manually
-        .no_error_on_failure();
+    println!(
+        "{} {}",
+        label_checkpoint(target_id, Checkpoint::Pass),
+        color::muted("(ready)")
+    );
 
-    Ok(command)
+    Ok(ActionStatus::Passed)
 }
 
 pub async fn run_target(
     workspace: Arc<RwLock<Workspace>>,
     action: &mut Action,
     target_id: &str,
-    primary_target: &str,
+    primary_targets: &HashSet<TargetID>,
     passthrough_args: &[String],
 ) -> Result<ActionStatus, WorkspaceError> {
     debug!(
@@ -245,15 +225,29 @@ pub async fn run_target(
     let mut cache = workspace.cache.cache_run_target_state(target_id).await?;
 
     // Gather the project and task
-    let is_primary = primary_target == target_id;
+    let is_primary = primary_targets.contains(target_id);
     let (project_id, task_id) = Target::parse(target_id)?.ids()?;
     let project = workspace.projects.load(&project_id)?;
-    let task = project.get_task(&task_id)?;
+    let task = &project.get_runnable_task(&task_id, &workspace.root)?;
+
+    if task.options.persistent {
+        return run_persistent_target(
+            &workspace,
+            action,
+            &project,
+            task,
+            is_primary,
+            passthrough_args,
+        )
+        .await;
+    }
 
     // Abort early if this build has already been cached/hashed
     let hasher = create_target_hasher(&workspace, &project, task, passthrough_args).await?;
     let hash = hasher.to_hash();
 
+    action.hash = Some(hash.clone());
+
     debug!(
         target: LOG_TARGET,
         "Generated hash {} for target {}",
@@ -261,22 +255,60 @@ pub async fn run_target(
         color::id(target_id)
     );
 
+    // Hash doesn't exist locally, so check the remote cache
+    let mut is_remote_hit = false;
+
+    if cache.item.hash != hash {
+        match workspace.cache.download_from_remote(&hash).await {
+            Ok(Some(state)) => {
+                cache.item = state;
+                cache.save().await?;
+                is_remote_hit = true;
+            }
+            Ok(None) => {}
+            Err(error) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to download {} from remote cache: {}",
+                    color::target(target_id),
+                    error
+                );
+            }
+        }
+    }
+
     if cache.item.hash == hash {
+        if hydrate_outputs(&workspace, &project, task, &hash).await? {
+            debug!(
+                target: LOG_TARGET,
+                "Hash exists for {}, aborting run",
+                color::id(target_id),
+            );
+
+            println!(
+                "{} {}",
+                label_checkpoint(target_id, Checkpoint::Pass),
+                color::muted(if is_remote_hit {
+                    "(cached from remote)"
+                } else {
+                    "(cached)"
+                })
+            );
+
+            print_cache_item(&cache.item);
+
+            return Ok(if is_remote_hit {
+                ActionStatus::CachedFromRemote
+            } else {
+                ActionStatus::Cached
+            });
+        }
+
         debug!(
             target: LOG_TARGET,
-            "Hash exists for {}, aborting run",
+            "Hash exists for {} but its outputs are missing from the cache, running target",
             color::id(target_id),
         );
-
-        println!(
-            "{} {}",
-            label_checkpoint(target_id, Checkpoint::Pass),
-            color::muted("(cached)")
-        );
-
-        print_cache_item(&cache.item);
-
-        return Ok(ActionStatus::Cached);
     }
 
     // Build the command to run based on the task
@@ -291,6 +323,17 @@ pub async fn run_target(
         command.inherit_colors();
     }
 
+    // Timed out processes are terminated and fail the action without retrying
+    if let Some(timeout) = task.options.timeout.filter(|t| *t > 0) {
+        command.timeout(Duration::from_secs(timeout));
+    }
+
+    let (command_line, working_dir) = command.get_command_line();
+
+    action.command = Some(command_line);
+    action.working_dir = working_dir.map(|dir| dir.to_path_buf());
+    action.env_vars = command.get_env_vars();
+
     // Run the command as a child process and capture its output.
     // If the process fails and `retry_count` is greater than 0,
     // attempt the process again in case it passes.
@@ -310,7 +353,7 @@ pub async fn run_target(
             print_target_label(target_id, &attempt, attempt_total, Checkpoint::Pass);
             print_target_command(&workspace, &project, task, passthrough_args);
 
-            // If this target matches the primary target (the last task to run),
+            // If this target matches a primary target (one that was requested to run),
             // then we want to stream the output directly to the parent (inherit mode).
             command
                 .exec_stream_and_capture_output(if is_real_ci { Some(target_id) } else { None })
@@ -329,6 +372,10 @@ pub async fn run_target(
         match possible_output {
             // zero and non-zero exit codes
             Ok(out) => {
+                attempt.exit_code = out.status.code();
+                attempt.stderr = output_to_string(&out.stderr);
+                attempt.stdout = output_to_string(&out.stdout);
+
                 if stream_output {
                     handle_streamed_output(target_id, &attempt, attempt_total, &out);
                 } else {
@@ -341,6 +388,8 @@ pub async fn run_target(
                     output = out;
                     break;
                 } else if attempt_index >= attempt_total {
+                    action.attempts = Some(attempts);
+
                     return Err(WorkspaceError::Moon(command.output_to_error(&out, false)));
                 } else {
                     attempt_index += 1;
@@ -355,12 +404,23 @@ pub async fn run_target(
             }
             // process itself failed
             Err(error) => {
+                // Keep the output of terminated processes for debugging
+                if let MoonError::ProcessCancelled(_, stdout, stderr)
+                | MoonError::ProcessTimeout(_, _, stdout, stderr) = &error
+                {
+                    attempt.stderr = output_to_string(stderr);
+                    attempt.stdout = output_to_string(stdout);
+                }
+
+                attempts.push(attempt);
+                action.attempts = Some(attempts);
+
                 return Err(WorkspaceError::Moon(error));
             }
         }
     }
 
-    // Hard link outputs to the `.moon/cache/out` folder and to the cloud,
+    // Hard link outputs to the `.moon/cache/out` folder (and later the remote),
     // so that subsequent builds are faster, and any local outputs
     // can be rehydrated easily.
     for output_path in &task.output_paths {
@@ -372,16 +432,38 @@ pub async fn run_target(
 
     // Save the new hash
     workspace.cache.save_hash(&hash, &hasher).await?;
+
+    let duration: u128 = attempts
+        .iter()
+        .filter_map(|attempt| attempt.duration)
+        .map(|duration| duration.as_millis())
+        .sum();
+
     action.attempts = Some(attempts);
 
     // Write the cache with the result and output
     cache.item.exit_code = output.status.code().unwrap_or(0);
     cache.item.hash = hash;
+    cache.item.last_run_duration = duration;
     cache.item.last_run_time = cache.now_millis();
     cache.item.stderr = output_to_string(&output.stderr);
     cache.item.stdout = output_to_string(&output.stdout);
     cache.save().await?;
 
+    // Share the result with other machines
+    if let Err(error) = workspace
+        .cache
+        .upload_to_remote(&cache.item.hash, &cache.item)
+        .await
+    {
+        warn!(
+            target: LOG_TARGET,
+            "Failed to upload {} to remote cache: {}",
+            color::target(target_id),
+            error
+        );
+    }
+
     Ok(ActionStatus::Passed)
 }
 
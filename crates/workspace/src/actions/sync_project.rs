@@ -1,7 +1,7 @@
 use crate::action::ActionStatus;
 use crate::errors::WorkspaceError;
 use crate::workspace::Workspace;
-use moon_config::{tsconfig::TsConfigJson, TypeScriptConfig};
+use moon_config::{tsconfig::TsConfigJson, DependencyScope, TypeScriptConfig};
 use moon_logger::{color, debug};
 use moon_project::Project;
 use moon_utils::is_ci;
@@ -63,25 +63,44 @@ pub async fn sync_project(
         for dep_id in project.get_dependencies() {
             let dep_project = workspace.projects.load(&dep_id)?;
 
-            // Update `dependencies` within this project's `package.json`
-            if node_config.sync_project_workspace_dependencies {
+            // Update `dependencies` within this project's `package.json`,
+            // based on the scope. Build dependencies are not synced, nor are
+            // implicit dependencies, as their scope is unknown.
+            if node_config.sync_project_workspace_dependencies
+                && !project.is_implicit_dependency(&dep_id)
+            {
+                let dep_scope = project.get_dependency_scope(&dep_id).unwrap_or_default();
+
                 if let Some(package_json) = project.package_json.get_mut() {
                     let dep_package_name =
                         dep_project.get_package_name().await?.unwrap_or_default();
+                    let dep_range = package_manager.get_workspace_dependency_range();
 
                     // Only add if the dependent project has a `package.json`,
-                    // and this `package.json` has not already declared the dep.
+                    // and this `package.json` has not already declared the dep
+                    // in any of the dependency fields.
                     if !dep_package_name.is_empty()
-                        && package_json.add_dependency(
-                            &dep_package_name,
-                            &package_manager.get_workspace_dependency_range(),
-                            true,
-                        )
+                        && !package_json.has_dependency(&dep_package_name)
+                        && match dep_scope {
+                            DependencyScope::Production => {
+                                package_json.add_dependency(&dep_package_name, &dep_range, true)
+                            }
+                            DependencyScope::Development => {
+                                package_json.add_dev_dependency(&dep_package_name, &dep_range, true)
+                            }
+                            DependencyScope::Peer => package_json.add_peer_dependency(
+                                &dep_package_name,
+                                &dep_range,
+                                true,
+                            ),
+                            DependencyScope::Build => false,
+                        }
                     {
                         debug!(
                             target: LOG_TARGET,
-                            "Syncing {} as a dependency to {}'s {}",
+                            "Syncing {} as a {} dependency to {}'s {}",
                             color::id(&dep_id),
+                            format!("{:?}", dep_scope).to_lowercase(),
                             color::id(project_id),
                             color::file("package.json")
                         );
@@ -29,6 +29,18 @@ impl Node {
             Node::SyncProject(id) => format!("SyncProject({})", id),
         }
     }
+
+    /// Name of the action's log files within `.moon/cache/runs`. Since IDs must
+    /// start with a letter, non-target actions are prefixed with an underscore
+    /// to avoid collisions with projects and tasks.
+    pub fn log_name(&self) -> String {
+        match self {
+            Node::InstallNodeDeps => String::from("_installNodeDeps"),
+            Node::RunTarget(id) => id.clone(),
+            Node::SetupToolchain => String::from("_setupToolchain"),
+            Node::SyncProject(id) => format!("{}:_syncProject", id),
+        }
+    }
 }
 
 type GraphType = DiGraph<Node, ()>;
@@ -187,6 +199,20 @@ impl DepGraph {
             TargetProject::Own => {
                 target.fail_with(TargetError::NoProjectSelfInRunContext)?;
             }
+            // #tag:task
+            TargetProject::Tag(tag) => {
+                for project_id in projects.get_ids_with_tag(tag)? {
+                    let project = projects.load(&project_id)?;
+
+                    if project.tasks.contains_key(task_id)
+                        && self
+                            .insert_target(&project_id, task_id, projects, touched_files)?
+                            .is_some()
+                    {
+                        inserted_count += 1;
+                    }
+                }
+            }
         };
 
         Ok(inserted_count)
@@ -196,6 +222,7 @@ impl DepGraph {
         &mut self,
         target: &Target,
         projects: &ProjectGraph,
+        ignore_dev_dependents: bool,
     ) -> Result<(), WorkspaceError> {
         trace!(
             target: TARGET,
@@ -205,7 +232,13 @@ impl DepGraph {
 
         let (project_id, task_id) = target.ids()?;
         let project = projects.load(&project_id)?;
-        let dependents = projects.get_dependents_of(&project)?;
+
+        // Include consumers of consumers, so that downstream projects still work
+        let dependents = if ignore_dev_dependents {
+            projects.get_transitive_non_dev_dependents_of(&project, None)?
+        } else {
+            projects.get_transitive_dependents_of(&project, None)?
+        };
 
         for dependent_id in dependents {
             let dependent = projects.load(&dependent_id)?;
@@ -649,6 +682,36 @@ mod tests {
             );
         }
 
+        #[tokio::test]
+        async fn runs_tagged_projects_for_target_tag_scope() {
+            let workspace_root = get_fixtures_dir("project-graph/tags");
+            let projects = ProjectGraph::create(
+                &workspace_root,
+                GlobalProjectConfig::default(),
+                &HashMap::from([
+                    ("a".to_owned(), "a".to_owned()),
+                    ("b".to_owned(), "b".to_owned()),
+                    ("c".to_owned(), "c".to_owned()),
+                    ("d".to_owned(), "d".to_owned()),
+                ]),
+                &CacheEngine::create(&workspace_root).await.unwrap(),
+            )
+            .await
+            .unwrap();
+
+            let mut graph = DepGraph::default();
+            let inserted_count = graph
+                .run_target(&Target::parse("#frontend:lint").unwrap(), &projects, None)
+                .unwrap();
+
+            let dot = graph.to_dot();
+
+            assert_eq!(inserted_count, 2);
+            assert!(dot.contains("a:lint"));
+            assert!(dot.contains("b:lint"));
+            assert!(!dot.contains("c:lint"));
+        }
+
         #[tokio::test]
         #[should_panic(expected = "Project(Target(NoProjectDepsInRunContext))")]
         async fn errors_for_target_deps_scope() {
@@ -698,6 +761,69 @@ mod tests {
         }
     }
 
+    mod run_target_dependents {
+        use super::*;
+
+        async fn create_transitive_project_graph() -> ProjectGraph {
+            let workspace_root = get_fixtures_dir("project-graph/transitive");
+
+            ProjectGraph::create(
+                &workspace_root,
+                GlobalProjectConfig::default(),
+                &HashMap::from([
+                    ("a".to_owned(), "a".to_owned()),
+                    ("b".to_owned(), "b".to_owned()),
+                    ("c".to_owned(), "c".to_owned()),
+                    ("d".to_owned(), "d".to_owned()),
+                    ("e".to_owned(), "e".to_owned()),
+                    ("f".to_owned(), "f".to_owned()),
+                ]),
+                &CacheEngine::create(&workspace_root).await.unwrap(),
+            )
+            .await
+            .unwrap()
+        }
+
+        #[tokio::test]
+        async fn runs_transitive_dependents() {
+            let projects = create_transitive_project_graph().await;
+            let target = Target::parse("a:test").unwrap();
+
+            let mut graph = DepGraph::default();
+            graph.run_target(&target, &projects, None).unwrap();
+            graph
+                .run_target_dependents(&target, &projects, false)
+                .unwrap();
+
+            let dot = graph.to_dot();
+
+            assert!(dot.contains("a:test"));
+            assert!(dot.contains("c:test"));
+            assert!(dot.contains("d:test"));
+            assert!(dot.contains("e:test"));
+            assert!(dot.contains("f:test"));
+        }
+
+        #[tokio::test]
+        async fn skips_transitive_dev_dependents() {
+            let projects = create_transitive_project_graph().await;
+            let target = Target::parse("a:test").unwrap();
+
+            let mut graph = DepGraph::default();
+            graph.run_target(&target, &projects, None).unwrap();
+            graph
+                .run_target_dependents(&target, &projects, true)
+                .unwrap();
+
+            let dot = graph.to_dot();
+
+            assert!(dot.contains("c:test"));
+            assert!(dot.contains("d:test"));
+            assert!(!dot.contains("e:test"));
+            assert!(!dot.contains("f:test"));
+        }
+    }
+
     mod run_target_if_touched {
         use super::*;
 
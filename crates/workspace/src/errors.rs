@@ -2,6 +2,7 @@ use moon_config::constants;
 use moon_error::MoonError;
 use moon_project::ProjectError;
 use moon_toolchain::ToolchainError;
+use moon_utils::regex::RegexError;
 use moon_vcs::VcsError;
 use thiserror::Error;
 
@@ -13,6 +14,9 @@ pub enum WorkspaceError {
     #[error("Unknown node {0} found in dependency graph. How did this get here?")]
     DepGraphUnknownNode(usize),
 
+    #[error("Action runner was cancelled.")]
+    ActionRunnerCancelled,
+
     #[error("{0}")]
     ActionRunnerFailure(String),
 
@@ -49,15 +53,27 @@ pub enum WorkspaceError {
     )]
     InvalidGlobalProjectConfigFile(String),
 
+    #[error("Persistent target <target>{0}</target> exited before it was ready.")]
+    PersistentTargetExited(String),
+
+    #[error("Persistent target <target>{0}</target> was not ready after <symbol>{1}s</symbol>.")]
+    PersistentTargetNotReady(String, u64),
+
     #[error(transparent)]
     Moon(#[from] MoonError),
 
     #[error(transparent)]
     Project(#[from] ProjectError),
 
+    #[error(transparent)]
+    Regex(#[from] RegexError),
+
     #[error(transparent)]
     Toolchain(#[from] ToolchainError),
 
     #[error(transparent)]
     Vcs(#[from] VcsError),
+
+    #[error(transparent)]
+    Watch(#[from] notify::Error),
 }
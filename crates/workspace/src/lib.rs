@@ -3,10 +3,17 @@ mod action_runner;
 mod actions;
 mod dep_graph;
 mod errors;
+pub mod platforms;
+mod report;
+mod scheduler;
+mod watcher;
 mod workspace;
 
 pub use action::{Action, ActionStatus};
 pub use action_runner::ActionRunner;
-pub use dep_graph::DepGraph;
+pub use actions::hashing::create_target_hasher;
+pub use dep_graph::{DepGraph, Node};
 pub use errors::WorkspaceError;
+pub use report::RunReport;
+pub use watcher::Watcher;
 pub use workspace::Workspace;
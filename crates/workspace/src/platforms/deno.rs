@@ -0,0 +1,53 @@
+use crate::errors::WorkspaceError;
+use crate::platforms::system::create_system_command;
+use crate::platforms::Platform;
+use crate::workspace::Workspace;
+use async_trait::async_trait;
+use moon_hasher::TargetHasher;
+use moon_project::{Project, Task};
+use moon_toolchain::{get_path_env_var, Executable};
+use moon_utils::process::Command;
+use std::path::Path;
+
+/// Runs tasks through the toolchain's Deno binary. Commands other than `deno`
+/// are ran as system commands, but still have access to Deno on `PATH`.
+pub struct DenoPlatform;
+
+#[async_trait]
+impl Platform for DenoPlatform {
+    fn create_run_target_command(
+        &self,
+        workspace: &Workspace,
+        _project: &Project,
+        task: &Task,
+        working_dir: &Path,
+    ) -> Result<Command, WorkspaceError> {
+        let deno = workspace.toolchain.get_deno()?;
+        let bin_path = deno.get_bin_path();
+
+        let mut command = if task.command == "deno" {
+            let mut command = Command::new(bin_path);
+            command.args(&task.args).envs(&task.env);
+            command
+        } else {
+            create_system_command(task, working_dir)
+        };
+
+        command.env("PATH", get_path_env_var(bin_path.parent().unwrap()));
+
+        Ok(command)
+    }
+
+    async fn hash_run_target(
+        &self,
+        workspace: &Workspace,
+        _project: &Project,
+        hasher: &mut TargetHasher,
+    ) -> Result<(), WorkspaceError> {
+        let deno = workspace.toolchain.get_deno()?;
+
+        hasher.hash_tool_version("deno", &deno.config.version);
+
+        Ok(())
+    }
+}
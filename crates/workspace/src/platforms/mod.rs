@@ -0,0 +1,52 @@
+mod deno;
+mod node;
+mod system;
+
+use crate::errors::WorkspaceError;
+use crate::workspace::Workspace;
+use async_trait::async_trait;
+use moon_config::TaskType;
+use moon_hasher::TargetHasher;
+use moon_project::{Project, Task};
+use moon_utils::process::Command;
+use std::path::Path;
+
+pub use deno::DenoPlatform;
+pub use node::NodePlatform;
+pub use system::SystemPlatform;
+
+/// A platform represents the runtime that a task is ran with (configured by
+/// the task `type`), and is in charge of resolving the binary, arguments,
+/// and environment of the task's command, and any inputs that must be hashed.
+#[async_trait]
+pub trait Platform: Send + Sync {
+    /// Create a command that will run the task through the platform's runtime.
+    /// The working directory and `MOON_*` environment variables are applied afterwards.
+    fn create_run_target_command(
+        &self,
+        workspace: &Workspace,
+        project: &Project,
+        task: &Task,
+        working_dir: &Path,
+    ) -> Result<Command, WorkspaceError>;
+
+    /// Hash platform specific inputs (manifests, configs, tool versions, etc)
+    /// that should bust the cache of the task when changed.
+    async fn hash_run_target(
+        &self,
+        _workspace: &Workspace,
+        _project: &Project,
+        _hasher: &mut TargetHasher,
+    ) -> Result<(), WorkspaceError> {
+        Ok(())
+    }
+}
+
+/// Return the platform for the provided task type.
+pub fn get_platform(task_type: &TaskType) -> &'static dyn Platform {
+    match task_type {
+        TaskType::Deno => &DenoPlatform,
+        TaskType::Node => &NodePlatform,
+        TaskType::System => &SystemPlatform,
+    }
+}
@@ -0,0 +1,166 @@
+use crate::errors::WorkspaceError;
+use crate::platforms::Platform;
+use crate::workspace::Workspace;
+use async_trait::async_trait;
+use moon_hasher::TargetHasher;
+use moon_project::{Project, Task};
+use moon_toolchain::{get_path_env_var, Executable};
+use moon_utils::process::Command;
+use moon_utils::{path, string_vec};
+use std::path::Path;
+
+fn create_node_options(task: &Task) -> Vec<String> {
+    string_vec![
+        // "--inspect", // Enable node inspector
+        "--preserve-symlinks",
+        "--title",
+        &task.target,
+        "--unhandled-rejections",
+        "throw",
+    ]
+}
+
+/// Runs a task command through our toolchain's installed Node.js instance.
+/// We accomplish this by executing the Node.js binary as a child process,
+/// while passing a file path to a package's node module binary (this is the file
+/// being executed). We then also pass arguments defined in the task.
+/// This would look something like the following:
+///
+/// ~/.moon/tools/node/1.2.3/bin/node --inspect /path/to/node_modules/.bin/eslint
+///     --cache --color --fix --ext .ts,.tsx,.js,.jsx
+#[cfg(not(windows))]
+fn create_node_command(
+    workspace: &Workspace,
+    project: &Project,
+    task: &Task,
+) -> Result<Command, WorkspaceError> {
+    let node = workspace.toolchain.get_node();
+    let mut cmd = node.get_bin_path();
+    let mut args = vec![];
+
+    match task.command.as_str() {
+        "node" => {
+            args.extend(create_node_options(task));
+        }
+        "npm" => {
+            cmd = node.get_npm().get_bin_path();
+        }
+        "pnpm" => {
+            cmd = node.get_pnpm().unwrap().get_bin_path();
+        }
+        "yarn" => {
+            cmd = node.get_yarn().unwrap().get_bin_path();
+        }
+        bin => {
+            let bin_path = node.find_package_bin(bin, &project.root)?;
+
+            args.extend(create_node_options(task));
+            args.push(path::path_to_string(&bin_path)?);
+        }
+    };
+
+    // Create the command
+    let mut command = Command::new(cmd);
+
+    command.args(&args).args(&task.args).envs(&task.env).env(
+        "PATH",
+        get_path_env_var(node.get_bin_path().parent().unwrap()),
+    );
+
+    Ok(command)
+}
+
+/// Windows works quite differently than other systems, so we cannot do the above.
+/// On Windows, the package binary is a ".cmd" file, which means it needs to run
+/// through "cmd.exe" and not "node.exe". Because of this, the order of operations
+/// is switched, and "node.exe" is detected through the `PATH` env var.
+#[cfg(windows)]
+fn create_node_command(
+    workspace: &Workspace,
+    project: &Project,
+    task: &Task,
+) -> Result<Command, WorkspaceError> {
+    use moon_lang_node::node;
+
+    let node = workspace.toolchain.get_node();
+
+    let cmd = match task.command.as_str() {
+        "node" => node.get_bin_path().clone(),
+        "npm" => node.get_npm().get_bin_path().clone(),
+        "pnpm" => node.get_pnpm().unwrap().get_bin_path().clone(),
+        "yarn" => node.get_yarn().unwrap().get_bin_path().clone(),
+        bin => node.find_package_bin(bin, &project.root)?,
+    };
+
+    // Create the command
+    let mut command = Command::new(cmd);
+
+    command
+        .args(&task.args)
+        .envs(&task.env)
+        .env(
+            "PATH",
+            get_path_env_var(node.get_bin_path().parent().unwrap()),
+        )
+        .env(
+            "NODE_OPTIONS",
+            node::extend_node_options_env_var(&create_node_options(task).join(" ")),
+        );
+
+    Ok(command)
+}
+
+/// Hash the root and project `package.json` and `tsconfig.json` files.
+pub async fn hash_node_configs(
+    workspace: &Workspace,
+    project: &Project,
+    hasher: &mut TargetHasher,
+) -> Result<(), WorkspaceError> {
+    // Hash root configs first
+    hasher.hash_package_json(&workspace.package_json);
+
+    if let Some(root_tsconfig) = &workspace.tsconfig_json {
+        hasher.hash_tsconfig_json(root_tsconfig);
+    }
+
+    // Hash project configs second so they can override
+    project.load_package_json().await?;
+    project
+        .load_tsconfig_json(&workspace.config.typescript.project_config_file_name)
+        .await?;
+
+    if let Some(package) = project.package_json.get() {
+        hasher.hash_package_json(package);
+    }
+
+    if let Some(tsconfig) = project.tsconfig_json.get() {
+        hasher.hash_tsconfig_json(tsconfig);
+    }
+
+    Ok(())
+}
+
+/// Runs tasks through the toolchain's Node.js and package manager binaries.
+pub struct NodePlatform;
+
+#[async_trait]
+impl Platform for NodePlatform {
+    fn create_run_target_command(
+        &self,
+        workspace: &Workspace,
+        project: &Project,
+        task: &Task,
+        _working_dir: &Path,
+    ) -> Result<Command, WorkspaceError> {
+        create_node_command(workspace, project, task)
+    }
+
+    async fn hash_run_target(
+        &self,
+        workspace: &Workspace,
+        project: &Project,
+        hasher: &mut TargetHasher,
+    ) -> Result<(), WorkspaceError> {
+        hash_node_configs(workspace, project, hasher).await
+    }
+}
@@ -0,0 +1,62 @@
+use crate::errors::WorkspaceError;
+use crate::platforms::node::hash_node_configs;
+use crate::platforms::Platform;
+use crate::workspace::Workspace;
+use async_trait::async_trait;
+use moon_hasher::TargetHasher;
+use moon_project::{Project, Task};
+use moon_utils::process::Command;
+use std::path::Path;
+
+#[cfg(not(windows))]
+pub fn create_system_command(task: &Task, _cwd: &Path) -> Command {
+    let mut cmd = Command::new(&task.command);
+    cmd.args(&task.args).envs(&task.env);
+    cmd
+}
+
+#[cfg(windows)]
+pub fn create_system_command(task: &Task, cwd: &Path) -> Command {
+    use moon_utils::process::is_windows_script;
+
+    let mut cmd = Command::new(&task.command);
+
+    for arg in &task.args {
+        // cmd.exe requires an absolute path to batch files
+        if is_windows_script(arg) {
+            cmd.arg(cwd.join(arg));
+        } else {
+            cmd.arg(arg);
+        }
+    }
+
+    cmd.envs(&task.env);
+    cmd
+}
+
+/// Runs task commands as-is, using binaries found on `PATH`.
+pub struct SystemPlatform;
+
+#[async_trait]
+impl Platform for SystemPlatform {
+    fn create_run_target_command(
+        &self,
+        _workspace: &Workspace,
+        _project: &Project,
+        task: &Task,
+        working_dir: &Path,
+    ) -> Result<Command, WorkspaceError> {
+        Ok(create_system_command(task, working_dir))
+    }
+
+    async fn hash_run_target(
+        &self,
+        workspace: &Workspace,
+        project: &Project,
+        hasher: &mut TargetHasher,
+    ) -> Result<(), WorkspaceError> {
+        // System tasks commonly run scripts and binaries from `node_modules`,
+        // so these configs have always been part of their hash
+        hash_node_configs(workspace, project, hasher).await
+    }
+}
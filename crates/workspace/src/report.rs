@@ -0,0 +1,339 @@
+use crate::action::{Action, ActionStatus, Attempt};
+use crate::errors::WorkspaceError;
+use moon_logger::{color, debug};
+use moon_utils::fs;
+use moon_utils::regex::strip_ansi;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+const LOG_TARGET: &str = "moon:action-runner:report";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttemptReport {
+    /// Duration in milliseconds.
+    pub duration: Option<u128>,
+
+    pub exit_code: Option<i32>,
+
+    pub index: u8,
+
+    pub stderr: String,
+
+    pub stdout: String,
+}
+
+impl AttemptReport {
+    pub fn new(attempt: &Attempt) -> Self {
+        AttemptReport {
+            duration: attempt.duration.map(|d| d.as_millis()),
+            exit_code: attempt.exit_code,
+            index: attempt.index,
+            stderr: attempt.stderr.clone(),
+            stdout: attempt.stdout.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionReport {
+    pub attempts: Vec<AttemptReport>,
+
+    pub cached: bool,
+
+    pub cached_from_remote: bool,
+
+    /// Duration in milliseconds.
+    pub duration: Option<u128>,
+
+    pub error: Option<String>,
+
+    pub failed_dependency: Option<String>,
+
+    pub hash: Option<String>,
+
+    pub label: Option<String>,
+
+    pub status: ActionStatus,
+}
+
+impl ActionReport {
+    pub fn new(action: &Action) -> Self {
+        ActionReport {
+            attempts: action
+                .attempts
+                .as_ref()
+                .map(|attempts| attempts.iter().map(AttemptReport::new).collect())
+                .unwrap_or_default(),
+            cached: matches!(
+                action.status,
+                ActionStatus::Cached | ActionStatus::CachedFromRemote
+            ),
+            cached_from_remote: matches!(action.status, ActionStatus::CachedFromRemote),
+            duration: action.duration.map(|d| d.as_millis()),
+            error: action.error.clone(),
+            failed_dependency: action.failed_dependency.clone(),
+            hash: action.hash.clone(),
+            label: action.label.clone(),
+            status: action.status.clone(),
+        }
+    }
+}
+
+/// A machine-readable summary of all actions processed by the action runner.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReport {
+    pub actions: Vec<ActionReport>,
+
+    /// Duration in milliseconds.
+    pub duration: u128,
+}
+
+impl RunReport {
+    pub fn new(actions: &[Action], duration: Duration) -> Self {
+        RunReport {
+            actions: actions.iter().map(ActionReport::new).collect(),
+            duration: duration.as_millis(),
+        }
+    }
+
+    /// Write the report to the provided path. Paths with an `.xml` extension
+    /// are written in JUnit format, otherwise JSON.
+    pub async fn write(&self, path: &Path) -> Result<(), WorkspaceError> {
+        debug!(
+            target: LOG_TARGET,
+            "Writing run report to {}",
+            color::path(path)
+        );
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        if path.extension().unwrap_or_default() == "xml" {
+            fs::write(path, self.to_junit()).await?;
+        } else {
+            fs::write_json(path, self, true).await?;
+        }
+
+        Ok(())
+    }
+
+    pub fn to_junit(&self) -> String {
+        let mut failures = 0;
+        let mut skipped = 0;
+        let mut cases = vec![];
+
+        for action in &self.actions {
+            let name = escape_xml(action.label.as_deref().unwrap_or("unknown"));
+            let time = to_seconds(action.duration.unwrap_or(0));
+            let mut children = vec![];
+
+            match action.status {
+                ActionStatus::Failed | ActionStatus::FailedAndAbort => {
+                    failures += 1;
+
+                    let message = escape_xml(action.error.as_deref().unwrap_or("Action failed"));
+
+                    children.push(format!(
+                        "      <failure message=\"{}\">{}</failure>",
+                        message, message
+                    ));
+                }
+                ActionStatus::Skipped => {
+                    skipped += 1;
+                    children.push(String::from("      <skipped />"));
+                }
+                ActionStatus::SkippedFromFailure => {
+                    skipped += 1;
+                    children.push(format!(
+                        "      <skipped message=\"Dependency {} failed\" />",
+                        escape_xml(action.failed_dependency.as_deref().unwrap_or("unknown"))
+                    ));
+                }
+                _ => {}
+            };
+
+            if let Some(attempt) = action.attempts.last() {
+                if !attempt.stdout.is_empty() {
+                    children.push(format!(
+                        "      <system-out>{}</system-out>",
+                        escape_xml(&attempt.stdout)
+                    ));
+                }
+
+                if !attempt.stderr.is_empty() {
+                    children.push(format!(
+                        "      <system-err>{}</system-err>",
+                        escape_xml(&attempt.stderr)
+                    ));
+                }
+            }
+
+            if children.is_empty() {
+                cases.push(format!(
+                    "    <testcase name=\"{}\" classname=\"moon\" time=\"{}\" />",
+                    name, time
+                ));
+            } else {
+                cases.push(format!(
+                    "    <testcase name=\"{}\" classname=\"moon\" time=\"{}\">\n{}\n    </testcase>",
+                    name,
+                    time,
+                    children.join("\n")
+                ));
+            }
+        }
+
+        let total = self.actions.len();
+        let time = to_seconds(self.duration);
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"moon\" tests=\"{total}\" failures=\"{failures}\" time=\"{time}\">\n  <testsuite name=\"moon\" tests=\"{total}\" failures=\"{failures}\" skipped=\"{skipped}\" time=\"{time}\">\n{cases}\n  </testsuite>\n</testsuites>\n",
+            total = total,
+            failures = failures,
+            skipped = skipped,
+            time = time,
+            cases = cases.join("\n")
+        )
+    }
+}
+
+// XML 1.0 only allows tab, newline, and carriage return from the C0 control
+// characters, so colored process output must be stripped before it's escaped.
+fn is_valid_xml_char(c: char) -> bool {
+    matches!(
+        c,
+        '\t' | '\n'
+            | '\r'
+            | '\u{20}'..='\u{D7FF}'
+            | '\u{E000}'..='\u{FFFD}'
+            | '\u{10000}'..='\u{10FFFF}'
+    )
+}
+
+fn escape_xml(value: &str) -> String {
+    strip_ansi(value)
+        .chars()
+        .filter(|c| is_valid_xml_char(*c))
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn to_seconds(millis: u128) -> String {
+    format!("{:.3}", millis as f64 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::graph::NodeIndex;
+
+    fn create_actions() -> Vec<Action> {
+        let mut passed = Action::new(NodeIndex::new(0));
+        passed.label = Some(String::from("RunTarget(app:build)"));
+        passed.hash = Some(String::from("abc123"));
+        passed.attempts = Some(vec![Attempt::new(1)]);
+        passed.pass(ActionStatus::Passed);
+
+        let mut cached = Action::new(NodeIndex::new(1));
+        cached.label = Some(String::from("RunTarget(app:lint)"));
+        cached.pass(ActionStatus::CachedFromRemote);
+
+        let mut failed = Action::new(NodeIndex::new(2));
+        failed.label = Some(String::from("RunTarget(app:test)"));
+        failed.fail(String::from("Process <shell>jest</shell> failed"));
+
+        let mut skipped = Action::new(NodeIndex::new(3));
+        skipped.label = Some(String::from("RunTarget(app:deploy)"));
+        skipped.skip_from_failure(String::from("RunTarget(app:test)"));
+
+        vec![passed, cached, failed, skipped]
+    }
+
+    mod json {
+        use super::*;
+
+        #[test]
+        fn serializes_actions() {
+            let report = RunReport::new(&create_actions(), Duration::from_millis(1500));
+            let json = serde_json::to_value(&report).unwrap();
+
+            assert_eq!(json["duration"], 1500);
+            assert_eq!(json["actions"][0]["label"], "RunTarget(app:build)");
+            assert_eq!(json["actions"][0]["status"], "passed");
+            assert_eq!(json["actions"][0]["hash"], "abc123");
+            assert_eq!(json["actions"][0]["attempts"][0]["index"], 1);
+            assert_eq!(json["actions"][1]["status"], "cached-from-remote");
+            assert_eq!(json["actions"][1]["cached"], true);
+            assert_eq!(json["actions"][1]["cachedFromRemote"], true);
+            assert_eq!(json["actions"][2]["status"], "failed");
+            assert_eq!(
+                json["actions"][2]["error"],
+                "Process <shell>jest</shell> failed"
+            );
+            assert_eq!(json["actions"][3]["status"], "skipped-from-failure");
+            assert_eq!(
+                json["actions"][3]["failedDependency"],
+                "RunTarget(app:test)"
+            );
+        }
+    }
+
+    mod junit {
+        use super::*;
+
+        #[test]
+        fn renders_test_cases() {
+            let report = RunReport::new(&create_actions(), Duration::from_millis(1500));
+            let xml = report.to_junit();
+
+            assert!(xml
+                .contains("<testsuites name=\"moon\" tests=\"4\" failures=\"1\" time=\"1.500\">"));
+            assert!(xml.contains("<testcase name=\"RunTarget(app:build)\" classname=\"moon\""));
+            assert!(xml
+                .contains("<failure message=\"Process &lt;shell&gt;jest&lt;/shell&gt; failed\">"));
+            assert!(xml.contains("<skipped message=\"Dependency RunTarget(app:test) failed\" />"));
+        }
+
+        #[test]
+        fn strips_ansi_and_control_chars() {
+            assert_eq!(
+                escape_xml("\x1b[31mFAIL\x1b[39m src/a.test.ts\x07\x08\n\t\x1b]0;title\x07done"),
+                "FAIL src/a.test.ts\n\tdone"
+            );
+        }
+
+        #[test]
+        fn renders_colored_output() {
+            let mut action = Action::new(NodeIndex::new(0));
+            let mut attempt = Attempt::new(1);
+            attempt.stdout = String::from("\x1b[1m\x1b[32mPASS\x1b[39m\x1b[22m tests/a.test.ts");
+            attempt.stderr = String::from("\x1b[31m<error>\x1b[0m\x1b");
+            action.label = Some(String::from("RunTarget(app:test)"));
+            action.attempts = Some(vec![attempt]);
+            action.pass(ActionStatus::Passed);
+
+            let xml = RunReport::new(&[action], Duration::from_millis(10)).to_junit();
+
+            assert!(!xml.contains('\x1b'));
+            assert!(xml.contains("<system-out>PASS tests/a.test.ts</system-out>"));
+            assert!(xml.contains("<system-err>&lt;error&gt;</system-err>"));
+        }
+
+        #[test]
+        fn escapes_xml() {
+            assert_eq!(
+                escape_xml("<a href=\"b\">&'</a>"),
+                "&lt;a href=&quot;b&quot;&gt;&amp;&apos;&lt;/a&gt;"
+            );
+        }
+    }
+}
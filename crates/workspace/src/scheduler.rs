@@ -0,0 +1,246 @@
+use crate::dep_graph::{DepGraph, NodeIndex};
+use crate::errors::WorkspaceError;
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// A ready queue of dependency graph nodes. A node becomes ready as soon as
+/// all of its dependencies have completed, instead of waiting for an entire
+/// topological batch to complete.
+pub struct Scheduler {
+    /// Nodes that depend on the key node.
+    dependents: HashMap<NodeIndex, Vec<NodeIndex>>,
+
+    /// Amount of dependencies that have not completed for each node.
+    pending: HashMap<NodeIndex, usize>,
+
+    /// Nodes whose dependencies have all completed.
+    ready: VecDeque<NodeIndex>,
+}
+
+impl Scheduler {
+    pub fn new(dep_graph: &DepGraph) -> Result<Self, WorkspaceError> {
+        // Fail early if the graph contains a cycle, as nodes would never become ready
+        dep_graph.sort_topological()?;
+
+        let graph = &dep_graph.graph;
+        let mut dependents = HashMap::new();
+        let mut pending = HashMap::new();
+        let mut ready = VecDeque::new();
+
+        // Edges point from a dependent to its dependency
+        for index in graph.node_indices() {
+            let deps: HashSet<NodeIndex> = graph.neighbors(index).collect();
+
+            if deps.is_empty() {
+                ready.push_back(index);
+            } else {
+                pending.insert(index, deps.len());
+            }
+
+            let mut node_dependents: Vec<NodeIndex> = graph
+                .neighbors_directed(index, Direction::Incoming)
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+
+            node_dependents.sort();
+            dependents.insert(index, node_dependents);
+        }
+
+        Ok(Scheduler {
+            dependents,
+            pending,
+            ready,
+        })
+    }
+
+    /// Mark the node as completed, and queue any dependents that are now ready.
+    pub fn complete(&mut self, index: NodeIndex) {
+        let dependents = match self.dependents.remove(&index) {
+            Some(dependents) => dependents,
+            None => return,
+        };
+
+        for dependent in dependents {
+            if let Some(count) = self.pending.get_mut(&dependent) {
+                *count -= 1;
+
+                if *count == 0 {
+                    self.pending.remove(&dependent);
+                    self.ready.push_back(dependent);
+                }
+            }
+        }
+    }
+
+    /// Mark the node as failed, and remove all of its transitive dependents from
+    /// the queue, as they can no longer run. Returns the removed dependents.
+    pub fn fail(&mut self, index: NodeIndex) -> Vec<NodeIndex> {
+        let mut skipped = vec![];
+        let mut queue: VecDeque<NodeIndex> =
+            self.dependents.remove(&index).unwrap_or_default().into();
+
+        while let Some(dependent) = queue.pop_front() {
+            // Already removed through another failed dependency
+            if self.pending.remove(&dependent).is_none() {
+                continue;
+            }
+
+            skipped.push(dependent);
+
+            if let Some(dependents) = self.dependents.remove(&dependent) {
+                queue.extend(dependents);
+            }
+        }
+
+        skipped
+    }
+
+    /// Return the next node that is ready to run, if any.
+    pub fn next_ready(&mut self) -> Option<NodeIndex> {
+        self.ready.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dep_graph::Node;
+
+    fn create_graph() -> (DepGraph, Vec<NodeIndex>) {
+        let mut dep_graph = DepGraph::default();
+        let graph = &mut dep_graph.graph;
+
+        // SetupToolchain = 0, InstallNodeDeps = 1
+        let setup = NodeIndex::new(0);
+        let install = NodeIndex::new(1);
+
+        let a = graph.add_node(Node::RunTarget(String::from("a:build")));
+        let b = graph.add_node(Node::RunTarget(String::from("b:build")));
+        let c = graph.add_node(Node::RunTarget(String::from("c:build")));
+
+        // a and b depend on install, while c depends on both of them
+        graph.add_edge(a, install, ());
+        graph.add_edge(b, install, ());
+        graph.add_edge(c, a, ());
+        graph.add_edge(c, b, ());
+
+        (dep_graph, vec![setup, install, a, b, c])
+    }
+
+    #[test]
+    fn starts_with_roots() {
+        let (dep_graph, nodes) = create_graph();
+        let mut scheduler = Scheduler::new(&dep_graph).unwrap();
+
+        assert_eq!(scheduler.next_ready(), Some(nodes[0]));
+        assert_eq!(scheduler.next_ready(), None);
+    }
+
+    #[test]
+    fn queues_nodes_when_deps_complete() {
+        let (dep_graph, nodes) = create_graph();
+        let mut scheduler = Scheduler::new(&dep_graph).unwrap();
+
+        scheduler.next_ready();
+        scheduler.complete(nodes[0]);
+
+        assert_eq!(scheduler.next_ready(), Some(nodes[1]));
+
+        scheduler.complete(nodes[1]);
+
+        assert_eq!(scheduler.next_ready(), Some(nodes[2]));
+        assert_eq!(scheduler.next_ready(), Some(nodes[3]));
+        assert_eq!(scheduler.next_ready(), None);
+    }
+
+    #[test]
+    fn waits_for_all_deps() {
+        let (dep_graph, nodes) = create_graph();
+        let mut scheduler = Scheduler::new(&dep_graph).unwrap();
+
+        scheduler.complete(nodes[0]);
+        scheduler.complete(nodes[1]);
+        scheduler.complete(nodes[2]);
+
+        // c is still waiting on b
+        let mut ready = vec![];
+
+        while let Some(index) = scheduler.next_ready() {
+            ready.push(index);
+        }
+
+        assert_eq!(ready, nodes[0..4]);
+
+        scheduler.complete(nodes[3]);
+
+        assert_eq!(scheduler.next_ready(), Some(nodes[4]));
+    }
+
+    #[test]
+    fn ignores_duplicate_completions() {
+        let (dep_graph, nodes) = create_graph();
+        let mut scheduler = Scheduler::new(&dep_graph).unwrap();
+
+        scheduler.complete(nodes[0]);
+        scheduler.complete(nodes[0]);
+
+        assert_eq!(scheduler.next_ready(), Some(nodes[0]));
+        assert_eq!(scheduler.next_ready(), Some(nodes[1]));
+        assert_eq!(scheduler.next_ready(), None);
+    }
+
+    #[test]
+    fn skips_transitive_dependents_on_failure() {
+        let (dep_graph, nodes) = create_graph();
+        let mut scheduler = Scheduler::new(&dep_graph).unwrap();
+
+        scheduler.complete(nodes[0]);
+        scheduler.complete(nodes[1]);
+
+        assert_eq!(scheduler.fail(nodes[2]), vec![nodes[4]]);
+
+        // b is unrelated to a, so can still run, but c never becomes ready
+        scheduler.complete(nodes[3]);
+
+        let mut ready = vec![];
+
+        while let Some(index) = scheduler.next_ready() {
+            ready.push(index);
+        }
+
+        assert_eq!(ready, nodes[0..4]);
+    }
+
+    #[test]
+    fn skips_dependents_once() {
+        let (dep_graph, nodes) = create_graph();
+        let mut scheduler = Scheduler::new(&dep_graph).unwrap();
+
+        scheduler.complete(nodes[0]);
+        scheduler.complete(nodes[1]);
+
+        assert_eq!(scheduler.fail(nodes[2]), vec![nodes[4]]);
+        assert_eq!(scheduler.fail(nodes[3]), vec![]);
+    }
+
+    #[test]
+    fn skips_entire_chain_on_failure() {
+        let (dep_graph, nodes) = create_graph();
+        let mut scheduler = Scheduler::new(&dep_graph).unwrap();
+
+        scheduler.complete(nodes[0]);
+
+        assert_eq!(scheduler.fail(nodes[1]), vec![nodes[2], nodes[3], nodes[4]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "DepGraphCycleDetected")]
+    fn errors_for_cycles() {
+        let (mut dep_graph, nodes) = create_graph();
+
+        dep_graph.graph.add_edge(nodes[0], nodes[4], ());
+
+        Scheduler::new(&dep_graph).unwrap();
+    }
+}
@@ -0,0 +1,271 @@
+use crate::errors::WorkspaceError;
+use moon_logger::{color, debug, trace};
+use moon_project::{Task, TouchedFilePaths};
+use notify::{raw_watcher, RawEvent, RecommendedWatcher, RecursiveMode, Watcher as _};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::time::timeout;
+
+const LOG_TARGET: &str = "moon:watcher";
+
+/// Return the longest leading directory of a glob that does not contain
+/// glob syntax, as that is the directory that must be watched.
+fn get_glob_root(glob: &str) -> PathBuf {
+    let mut root = PathBuf::new();
+
+    for component in Path::new(glob).components() {
+        if let Component::Normal(part) = component {
+            if part
+                .to_string_lossy()
+                .contains(|c| matches!(c, '*' | '?' | '[' | '{' | '!'))
+            {
+                break;
+            }
+        }
+
+        root.push(component);
+    }
+
+    root
+}
+
+/// Watches the inputs of tasks for changes, and batches changed paths
+/// together until no changes have occurred for the debounce duration.
+pub struct Watcher {
+    debounce: Duration,
+
+    /// Directories where a change to any file within triggers a change.
+    dirs: Vec<PathBuf>,
+
+    /// Individual files that trigger a change. Their parent directory is
+    /// watched instead, so that files replaced by editors (rename on save)
+    /// and files that do not exist yet are detected.
+    files: HashSet<PathBuf>,
+
+    /// Paths that never trigger a change, like task outputs.
+    ignored: Vec<PathBuf>,
+
+    receiver: UnboundedReceiver<PathBuf>,
+
+    /// Paths currently being watched, and whether they are recursive.
+    watched: Vec<(PathBuf, bool)>,
+
+    // Dropping the watcher stops watching, so keep a handle to it
+    watcher: RecommendedWatcher,
+}
+
+impl Watcher {
+    pub fn new(debounce: Duration) -> Result<Self, WorkspaceError> {
+        let (sender, receiver) = unbounded_channel();
+        let (raw_sender, raw_receiver) = mpsc::channel::<RawEvent>();
+        let watcher = raw_watcher(raw_sender)?;
+
+        // Notify uses a synchronous channel, so forward events to the async runtime
+        thread::spawn(move || {
+            for event in raw_receiver {
+                if let Some(path) = event.path {
+                    if sender.send(path).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Watcher {
+            debounce,
+            dirs: vec![],
+            files: HashSet::new(),
+            ignored: vec![],
+            receiver,
+            watched: vec![],
+            watcher,
+        })
+    }
+
+    /// Ignore changes to the provided path and everything within it.
+    pub fn ignore(&mut self, path: &Path) {
+        self.ignored.push(path.to_path_buf());
+    }
+
+    /// Watch the input files and globs of a task. Outputs of the task are ignored,
+    /// so that writing them does not trigger another run.
+    pub fn watch_task(&mut self, task: &Task) -> Result<(), WorkspaceError> {
+        for input_path in &task.input_paths {
+            if input_path.is_dir() {
+                self.watch_path(input_path, true)?;
+                self.dirs.push(input_path.to_path_buf());
+
+                continue;
+            }
+
+            // Files are filtered from events of their parent directory
+            if let Some(parent) = input_path.parent() {
+                if parent.exists() {
+                    self.watch_path(parent, false)?;
+                    self.files.insert(input_path.to_path_buf());
+                }
+            }
+        }
+
+        for input_glob in &task.input_globs {
+            let root = get_glob_root(input_glob);
+
+            if root.exists() {
+                self.watch_path(&root, true)?;
+                self.dirs.push(root);
+            }
+        }
+
+        for output_path in &task.output_paths {
+            self.ignore(output_path);
+        }
+
+        Ok(())
+    }
+
+    fn watch_path(&mut self, path: &Path, recursive: bool) -> Result<(), WorkspaceError> {
+        // Already covered by another watched path
+        if self
+            .watched
+            .iter()
+            .any(|(p, r)| (*r && path.starts_with(p)) || (p == path && !recursive))
+        {
+            return Ok(());
+        }
+
+        trace!(target: LOG_TARGET, "Watching {}", color::path(path));
+
+        self.watcher.watch(
+            path,
+            if recursive {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            },
+        )?;
+
+        self.watched.push((path.to_path_buf(), recursive));
+
+        Ok(())
+    }
+
+    fn is_relevant(&self, path: &Path) -> bool {
+        if self.ignored.iter().any(|ignored| path.starts_with(ignored)) {
+            return false;
+        }
+
+        self.files.contains(path) || self.dirs.iter().any(|dir| path.starts_with(dir))
+    }
+
+    /// Wait for files to change, and return all paths that changed
+    /// until no more changes occurred within the debounce duration.
+    /// Returns `None` if the watcher has stopped.
+    pub async fn next_changes(&mut self) -> Option<TouchedFilePaths> {
+        let mut changes = HashSet::new();
+
+        // Wait indefinitely for the first change
+        while changes.is_empty() {
+            let path = self.receiver.recv().await?;
+
+            if self.is_relevant(&path) {
+                changes.insert(path);
+            }
+        }
+
+        // Then collect changes until they settle
+        while let Ok(Some(path)) = timeout(self.debounce, self.receiver.recv()).await {
+            if self.is_relevant(&path) {
+                changes.insert(path);
+            }
+        }
+
+        debug!(
+            target: LOG_TARGET,
+            "Detected {} changed files",
+            changes.len()
+        );
+
+        Some(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod is_relevant {
+        use super::*;
+
+        fn create_watcher() -> Watcher {
+            let mut watcher = Watcher::new(Duration::from_millis(0)).unwrap();
+            watcher.dirs.push(PathBuf::from("/workspace/project/src"));
+            watcher
+                .files
+                .insert(PathBuf::from("/workspace/project/package.json"));
+            watcher.ignore(Path::new("/workspace/project/src/generated"));
+            watcher
+        }
+
+        #[test]
+        fn matches_watched_files() {
+            let watcher = create_watcher();
+
+            assert!(watcher.is_relevant(Path::new("/workspace/project/package.json")));
+        }
+
+        #[test]
+        fn skips_siblings_of_watched_files() {
+            let watcher = create_watcher();
+
+            assert!(!watcher.is_relevant(Path::new("/workspace/project/README.md")));
+            assert!(!watcher.is_relevant(Path::new("/workspace/project/.package.json.swp")));
+        }
+
+        #[test]
+        fn matches_files_within_dirs() {
+            let watcher = create_watcher();
+
+            assert!(watcher.is_relevant(Path::new("/workspace/project/src/index.ts")));
+            assert!(watcher.is_relevant(Path::new("/workspace/project/src/nested/new.ts")));
+        }
+
+        #[test]
+        fn skips_ignored_paths() {
+            let watcher = create_watcher();
+
+            assert!(!watcher.is_relevant(Path::new("/workspace/project/src/generated/index.ts")));
+        }
+    }
+
+    mod get_glob_root {
+        use super::*;
+
+        #[test]
+        fn returns_dir_before_glob() {
+            assert_eq!(
+                get_glob_root("/workspace/project/src/**/*.ts"),
+                PathBuf::from("/workspace/project/src")
+            );
+        }
+
+        #[test]
+        fn supports_globs_in_file_name() {
+            assert_eq!(
+                get_glob_root("/workspace/project/*.json"),
+                PathBuf::from("/workspace/project")
+            );
+        }
+
+        #[test]
+        fn supports_braces() {
+            assert_eq!(
+                get_glob_root("/workspace/{a,b}/src/**/*"),
+                PathBuf::from("/workspace")
+            );
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::errors::WorkspaceError;
-use moon_cache::CacheEngine;
+use moon_cache::{CacheEngine, RemoteCache};
 use moon_config::package::PackageJson;
 use moon_config::tsconfig::TsConfigJson;
 use moon_config::{constants, format_figment_errors, GlobalProjectConfig, WorkspaceConfig};
@@ -177,10 +177,31 @@ impl Workspace {
             load_tsconfig_json(&root_dir, &config.typescript.root_config_file_name).await?;
 
         // Setup components
-        let cache = CacheEngine::create(&root_dir).await?;
+        let mut cache = CacheEngine::create(&root_dir).await?;
+
+        if let Some(remote_config) = &config.cache.remote {
+            cache.remote = Some(RemoteCache::new(remote_config));
+        }
+
         let toolchain = Toolchain::create(&root_dir, &config).await?;
-        let projects =
+        let mut projects =
             ProjectGraph::create(&root_dir, project_config, &config.projects, &cache).await?;
+
+        projects.set_constraints(config.constraints.clone());
+
+        if config.node.infer_project_dependencies || config.typescript.infer_project_references {
+            projects
+                .infer_implicit_dependencies(
+                    config.node.infer_project_dependencies,
+                    if config.typescript.infer_project_references {
+                        Some(config.typescript.project_config_file_name.as_str())
+                    } else {
+                        None
+                    },
+                )
+                .await?;
+        }
+
         let vcs = VcsLoader::load(&config, &root_dir)?;
 
         Ok(Workspace {
@@ -0,0 +1,7 @@
+#!/usr/bin/env bash
+
+echo "before"
+
+sleep 10
+
+echo "This should not appear!"
@@ -86,12 +86,17 @@ const sidebars = {
 				'commands/overview',
 				'commands/bin',
 				'commands/ci',
+				'commands/clean',
+				'commands/hash',
 				'commands/init',
+				'commands/logs',
 				'commands/project',
 				'commands/project-graph',
 				'commands/run',
+				'commands/schema',
 				'commands/setup',
 				'commands/teardown',
+				'commands/validate',
 			],
 			link: {
 				type: 'generated-index',